@@ -0,0 +1,360 @@
+/* Generated by cbindgen from toon-ffi. Do not edit. */
+
+#ifndef TOON_H
+#define TOON_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result code returned by fallible functions.
+typedef enum ToonStatus {
+  TOON_STATUS_OK = 0,
+  TOON_STATUS_NULL_POINTER,
+  TOON_STATUS_INVALID_UTF8,
+  TOON_STATUS_TRUNCATED,
+  TOON_STATUS_UNSUPPORTED_VERSION,
+  TOON_STATUS_CHECKSUM_MISMATCH,
+  TOON_STATUS_UNKNOWN_TYPE_MARKER,
+  TOON_STATUS_INVALID_LENGTH,
+  TOON_STATUS_TRAILING_BYTES,
+  TOON_STATUS_INVALID_REFERENCE,
+  TOON_STATUS_ENCRYPTED,
+  TOON_STATUS_INVALID_DATA,
+  TOON_STATUS_LENGTH_OVERFLOW,
+  TOON_STATUS_TYPE_MISMATCH,
+  TOON_STATUS_OUT_OF_RANGE,
+  TOON_STATUS_NOT_FOUND,
+  TOON_STATUS_DUPLICATE_KEY,
+  TOON_STATUS_BUILDER_STATE,
+  TOON_STATUS_INVALID_ARGUMENT,
+} ToonStatus;
+
+typedef enum ToonValueKind {
+  TOON_VALUE_KIND_NULL = 0,
+  TOON_VALUE_KIND_BOOL,
+  TOON_VALUE_KIND_INT,
+  TOON_VALUE_KIND_FLOAT,
+  TOON_VALUE_KIND_STRING,
+  TOON_VALUE_KIND_ARRAY,
+  TOON_VALUE_KIND_OBJECT,
+  TOON_VALUE_KIND_REF,
+} ToonValueKind;
+
+typedef enum ToonRefStrength {
+  TOON_REF_STRENGTH_STRONG = 0,
+  TOON_REF_STRENGTH_WEAK = 1,
+} ToonRefStrength;
+
+// Incrementally builds a value and serializes it as a token.
+//
+// Scalars are pushed into the innermost open array or object, or become the
+// root when nothing is open. Inside an object every value must be preceded
+// by `toon_builder_key`.
+typedef struct ToonBuilder ToonBuilder;
+
+// A deserialized token. Release with `toon_token_free`.
+typedef struct ToonToken ToonToken;
+
+// A value inside a `ToonToken`. Pointers to it borrow from the token.
+typedef struct ToonValue ToonValue;
+
+// Bytes allocated by the library. Release with `toon_buffer_free`.
+typedef struct ToonBuffer {
+  uint8_t *data;
+  size_t len;
+} ToonBuffer;
+
+// Fixed-size frame header, read without validating the rest of the frame.
+typedef struct ToonHeader {
+  uint8_t version;
+  uint8_t id[16];
+  uint8_t type_marker;
+  uint32_t payload_len;
+} ToonHeader;
+
+// Byte offsets of the sections of a frame whose lengths are consistent.
+typedef struct ToonLayout {
+  struct ToonHeader header;
+  size_t payload_offset;
+  size_t payload_len;
+  size_t checksum_offset;
+} ToonLayout;
+
+// Fixed-size part of a token's metadata, read without decoding its value.
+typedef struct ToonMetadata {
+  uint64_t created_at_ms;
+  uint32_t flags;
+  // Type marker of the value, which a metadata section may wrap.
+  uint8_t value_marker;
+} ToonMetadata;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Releases a buffer returned by the library and resets it to empty.
+//
+// # Safety
+// `buffer` must be null or point to a `ToonBuffer` filled in by this library
+// that has not been freed yet.
+void toon_buffer_free(struct ToonBuffer *buffer);
+
+// Binary format version produced and accepted by this library.
+uint8_t toon_format_version(void);
+
+// Creates an empty builder. Release with `toon_builder_free`.
+struct ToonBuilder *toon_builder_new(void);
+
+// # Safety
+// `builder` must be null or come from `toon_builder_new` and not be freed.
+void toon_builder_free(struct ToonBuilder *builder);
+
+// # Safety
+// `builder` must be null or a live builder.
+enum ToonStatus toon_builder_push_null(struct ToonBuilder *builder);
+
+// # Safety
+// `builder` must be null or a live builder.
+enum ToonStatus toon_builder_push_bool(struct ToonBuilder *builder, bool value);
+
+// # Safety
+// `builder` must be null or a live builder.
+enum ToonStatus toon_builder_push_int(struct ToonBuilder *builder, int64_t value);
+
+// # Safety
+// `builder` must be null or a live builder.
+enum ToonStatus toon_builder_push_float(struct ToonBuilder *builder, double value);
+
+// Pushes a UTF-8 string of `len` bytes.
+//
+// # Safety
+// `builder` must be null or a live builder; `data` must be readable for
+// `len` bytes.
+enum ToonStatus toon_builder_push_string(struct ToonBuilder *builder,
+                                         const uint8_t *data,
+                                         size_t len);
+
+// Pushes a reference to the token whose 16-byte id is at `id`. `strength`
+// is a `ToonRefStrength`; any other value is `TOON_STATUS_INVALID_ARGUMENT`.
+//
+// # Safety
+// `builder` must be null or a live builder; `id` must be readable for 16
+// bytes.
+enum ToonStatus toon_builder_push_ref(struct ToonBuilder *builder,
+                                      const uint8_t *id,
+                                      uint32_t strength);
+
+// Like `toon_builder_push_ref`, additionally pinning the reference to the
+// target checksum at `pin` (see `toon_token_checksum`) unless `pin` is null,
+// and pointing it at the JSON Pointer of `fragment_len` UTF-8 bytes at
+// `fragment` unless that is empty.
+//
+// # Safety
+// `builder` must be null or a live builder; `id` must be readable for 16
+// bytes; `pin` must be null or readable; `fragment` must be readable for
+// `fragment_len` bytes.
+enum ToonStatus toon_builder_push_ref_with(struct ToonBuilder *builder,
+                                           const uint8_t *id,
+                                           uint32_t strength,
+                                           const uint32_t *pin,
+                                           const uint8_t *fragment,
+                                           size_t fragment_len);
+
+// # Safety
+// `builder` must be null or a live builder.
+enum ToonStatus toon_builder_begin_array(struct ToonBuilder *builder);
+
+// # Safety
+// `builder` must be null or a live builder.
+enum ToonStatus toon_builder_end_array(struct ToonBuilder *builder);
+
+// # Safety
+// `builder` must be null or a live builder.
+enum ToonStatus toon_builder_begin_object(struct ToonBuilder *builder);
+
+// Sets the key for the next value pushed into the innermost object.
+//
+// # Safety
+// `builder` must be null or a live builder; `data` must be readable for
+// `len` bytes.
+enum ToonStatus toon_builder_key(struct ToonBuilder *builder, const uint8_t *data, size_t len);
+
+// Closes the innermost object. Fails if a key is still waiting for a value.
+//
+// # Safety
+// `builder` must be null or a live builder.
+enum ToonStatus toon_builder_end_object(struct ToonBuilder *builder);
+
+// Serializes the finished value as a token with the 16-byte id at `id`.
+// The builder is left untouched and can be serialized again.
+//
+// # Safety
+// `builder` must be null or a live builder; `id` must be readable for 16
+// bytes; `out` must be null or writable. On success `out` owns a buffer to
+// release with `toon_buffer_free`.
+enum ToonStatus toon_builder_serialize(const struct ToonBuilder *builder,
+                                       const uint8_t *id,
+                                       struct ToonBuffer *out);
+
+// Static, NUL-terminated description of the `ToonStatus` value `status`;
+// values that are not a `ToonStatus` read as "unknown status".
+const char *toon_status_message(uint32_t status);
+
+// Deserializes and verifies a token. On success `*out` owns the token.
+//
+// # Safety
+// `data` must be readable for `len` bytes; `out` must be null or writable.
+enum ToonStatus toon_token_deserialize(const uint8_t *data, size_t len, struct ToonToken **out);
+
+// Checks that `data` holds one complete, uncorrupted token.
+//
+// # Safety
+// `data` must be readable for `len` bytes.
+enum ToonStatus toon_verify(const uint8_t *data, size_t len);
+
+// # Safety
+// `token` must be null or come from `toon_token_deserialize` and not be
+// freed. Value pointers read from it become dangling.
+void toon_token_free(struct ToonToken *token);
+
+// Copies the token's 16-byte id to `out`.
+//
+// # Safety
+// `token` must be null or a live token; `out` must be writable for 16 bytes.
+enum ToonStatus toon_token_id(const struct ToonToken *token, uint8_t *out);
+
+// Writes the token's checksum to `out`, for pinning references to this
+// version of it with `toon_builder_push_ref_with`.
+//
+// # Safety
+// `token` must be null or a live token; `out` must be null or writable.
+enum ToonStatus toon_token_checksum(const struct ToonToken *token, uint32_t *out);
+
+// Root value of the token, borrowed for the token's lifetime. Null if
+// `token` is null.
+//
+// # Safety
+// `token` must be null or a live token.
+const struct ToonValue *toon_token_value(const struct ToonToken *token);
+
+// # Safety
+// `data` must be readable for `len` bytes; `out` must be null or writable.
+enum ToonStatus toon_read_header(const uint8_t *data, size_t len, struct ToonHeader *out);
+
+// Like `toon_read_header`, additionally checking the version and that the
+// declared payload length matches the input length. The checksum is not
+// verified.
+//
+// # Safety
+// `data` must be readable for `len` bytes; `out` must be null or writable.
+enum ToonStatus toon_read_layout(const uint8_t *data, size_t len, struct ToonLayout *out);
+
+// Reads the metadata of a frame whose lengths are consistent. Encrypted and
+// delta frames keep their metadata inside the payload and are refused.
+//
+// # Safety
+// `data` must be readable for `len` bytes; `out` must be null or writable.
+enum ToonStatus toon_read_metadata(const uint8_t *data, size_t len, struct ToonMetadata *out);
+
+// Kind of `value`; a null pointer reads as `TOON_VALUE_KIND_NULL`.
+//
+// # Safety
+// `value` must be null or point into a live token.
+enum ToonValueKind toon_value_kind(const struct ToonValue *value);
+
+// # Safety
+// `value` must be null or point into a live token; `out` must be null or
+// writable.
+enum ToonStatus toon_value_get_bool(const struct ToonValue *value, bool *out);
+
+// # Safety
+// `value` must be null or point into a live token; `out` must be null or
+// writable.
+enum ToonStatus toon_value_get_int(const struct ToonValue *value, int64_t *out);
+
+// # Safety
+// `value` must be null or point into a live token; `out` must be null or
+// writable.
+enum ToonStatus toon_value_get_float(const struct ToonValue *value, double *out);
+
+// Borrows the UTF-8 bytes of a string value; they are not NUL-terminated.
+//
+// # Safety
+// `value` must be null or point into a live token; `data` and `len` must be
+// null or writable.
+enum ToonStatus toon_value_get_string(const struct ToonValue *value,
+                                      const uint8_t **data,
+                                      size_t *len);
+
+// Copies the referenced token id (16 bytes) to `id` and its strength to
+// `strength`. The pin and fragment are read with `toon_value_get_ref_pin`
+// and `toon_value_get_ref_fragment`.
+//
+// # Safety
+// `value` must be null or point into a live token; `id` must be writable
+// for 16 bytes; `strength` must be null or writable.
+enum ToonStatus toon_value_get_ref(const struct ToonValue *value,
+                                   uint8_t *id,
+                                   enum ToonRefStrength *strength);
+
+// Reads the pin of a reference: `*pinned` tells whether it is pinned and
+// `*pin` receives the target's checksum, or 0 when it is not.
+//
+// # Safety
+// `value` must be null or point into a live token; `pinned` and `pin` must
+// be null or writable.
+enum ToonStatus toon_value_get_ref_pin(const struct ToonValue *value, bool *pinned, uint32_t *pin);
+
+// Borrows the JSON Pointer fragment of a reference; it is empty when the
+// reference points at the whole token, and not NUL-terminated.
+//
+// # Safety
+// `value` must be null or point into a live token; `data` and `len` must be
+// null or writable.
+enum ToonStatus toon_value_get_ref_fragment(const struct ToonValue *value,
+                                            const uint8_t **data,
+                                            size_t *len);
+
+// Number of elements of an array or entries of an object; 0 otherwise.
+//
+// # Safety
+// `value` must be null or point into a live token.
+size_t toon_value_len(const struct ToonValue *value);
+
+// # Safety
+// `value` must be null or point into a live token; `out` must be null or
+// writable.
+enum ToonStatus toon_value_array_get(const struct ToonValue *value,
+                                     size_t index,
+                                     const struct ToonValue **out);
+
+// Looks up the member named by the `key_len` UTF-8 bytes at `key`.
+//
+// # Safety
+// `value` must be null or point into a live token; `key` must be readable
+// for `key_len` bytes; `out` must be null or writable.
+enum ToonStatus toon_value_object_get(const struct ToonValue *value,
+                                      const uint8_t *key,
+                                      size_t key_len,
+                                      const struct ToonValue **out);
+
+// Reads the `index`-th entry of an object, in ascending order of the keys'
+// UTF-8 bytes. The key is borrowed and not NUL-terminated.
+//
+// # Safety
+// `value` must be null or point into a live token; the out pointers must be
+// null or writable.
+enum ToonStatus toon_value_object_entry(const struct ToonValue *value,
+                                        size_t index,
+                                        const uint8_t **key,
+                                        size_t *key_len,
+                                        const struct ToonValue **out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TOON_H */
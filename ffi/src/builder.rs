@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+
+use toon_format::{Metadata, Serializer, Token, TokenRef, Value};
+
+use crate::value::ToonRefStrength;
+use crate::{bytes, token_id, ToonBuffer, ToonStatus};
+
+const STRONG: u32 = ToonRefStrength::Strong as u32;
+const WEAK: u32 = ToonRefStrength::Weak as u32;
+
+/// Incrementally builds a value and serializes it as a token.
+///
+/// Scalars are pushed into the innermost open array or object, or become the
+/// root when nothing is open. Inside an object every value must be preceded
+/// by `toon_builder_key`.
+#[derive(Debug, Default)]
+pub struct ToonBuilder {
+    stack: Vec<Frame>,
+    root: Option<Value>,
+}
+
+#[derive(Debug)]
+enum Frame {
+    Array(Vec<Value>),
+    Object {
+        entries: HashMap<String, Value>,
+        key: Option<String>,
+    },
+}
+
+impl ToonBuilder {
+    fn push(&mut self, value: Value) -> ToonStatus {
+        match self.stack.last_mut() {
+            None if self.root.is_some() => ToonStatus::BuilderState,
+            None => {
+                self.root = Some(value);
+                ToonStatus::Ok
+            }
+            Some(Frame::Array(items)) => {
+                items.push(value);
+                ToonStatus::Ok
+            }
+            Some(Frame::Object { entries, key }) => match key.take() {
+                Some(key) => {
+                    entries.insert(key, value);
+                    ToonStatus::Ok
+                }
+                None => ToonStatus::BuilderState,
+            },
+        }
+    }
+
+    fn open(&mut self, frame: Frame) -> ToonStatus {
+        let can_open = match self.stack.last() {
+            None => self.root.is_none(),
+            Some(Frame::Array(_)) => true,
+            Some(Frame::Object { key, .. }) => key.is_some(),
+        };
+        if !can_open {
+            return ToonStatus::BuilderState;
+        }
+        self.stack.push(frame);
+        ToonStatus::Ok
+    }
+}
+
+/// Creates an empty builder. Release with `toon_builder_free`.
+#[no_mangle]
+pub extern "C" fn toon_builder_new() -> *mut ToonBuilder {
+    Box::into_raw(Box::default())
+}
+
+/// # Safety
+/// `builder` must be null or come from `toon_builder_new` and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_free(builder: *mut ToonBuilder) {
+    if !builder.is_null() {
+        drop(Box::from_raw(builder));
+    }
+}
+
+/// # Safety
+/// `builder` must be null or a live builder.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_push_null(builder: *mut ToonBuilder) -> ToonStatus {
+    with_builder(builder, |b| b.push(Value::Null))
+}
+
+/// # Safety
+/// `builder` must be null or a live builder.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_push_bool(
+    builder: *mut ToonBuilder,
+    value: bool,
+) -> ToonStatus {
+    with_builder(builder, |b| b.push(Value::Bool(value)))
+}
+
+/// # Safety
+/// `builder` must be null or a live builder.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_push_int(
+    builder: *mut ToonBuilder,
+    value: i64,
+) -> ToonStatus {
+    with_builder(builder, |b| b.push(Value::Int(value)))
+}
+
+/// # Safety
+/// `builder` must be null or a live builder.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_push_float(
+    builder: *mut ToonBuilder,
+    value: f64,
+) -> ToonStatus {
+    with_builder(builder, |b| b.push(Value::Float(value)))
+}
+
+/// Pushes a UTF-8 string of `len` bytes.
+///
+/// # Safety
+/// `builder` must be null or a live builder; `data` must be readable for
+/// `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_push_string(
+    builder: *mut ToonBuilder,
+    data: *const u8,
+    len: usize,
+) -> ToonStatus {
+    let Some(s) = string(data, len) else {
+        return invalid_string(data);
+    };
+    with_builder(builder, |b| b.push(Value::String(s)))
+}
+
+/// Pushes a reference to the token whose 16-byte id is at `id`. `strength`
+/// is a `ToonRefStrength`; any other value is `TOON_STATUS_INVALID_ARGUMENT`.
+///
+/// # Safety
+/// `builder` must be null or a live builder; `id` must be readable for 16
+/// bytes.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_push_ref(
+    builder: *mut ToonBuilder,
+    id: *const u8,
+    strength: u32,
+) -> ToonStatus {
+    toon_builder_push_ref_with(builder, id, strength, std::ptr::null(), std::ptr::null(), 0)
+}
+
+/// Like `toon_builder_push_ref`, additionally pinning the reference to the
+/// target checksum at `pin` (see `toon_token_checksum`) unless `pin` is null,
+/// and pointing it at the JSON Pointer of `fragment_len` UTF-8 bytes at
+/// `fragment` unless that is empty.
+///
+/// # Safety
+/// `builder` must be null or a live builder; `id` must be readable for 16
+/// bytes; `pin` must be null or readable; `fragment` must be readable for
+/// `fragment_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_push_ref_with(
+    builder: *mut ToonBuilder,
+    id: *const u8,
+    strength: u32,
+    pin: *const u32,
+    fragment: *const u8,
+    fragment_len: usize,
+) -> ToonStatus {
+    let Some(id) = token_id(id) else {
+        return ToonStatus::NullPointer;
+    };
+    let Some(fragment) = string(fragment, fragment_len) else {
+        return invalid_string(fragment);
+    };
+    let mut reference = match strength {
+        STRONG => TokenRef::strong(id),
+        WEAK => TokenRef::weak(id),
+        _ => return ToonStatus::InvalidArgument,
+    }
+    .with_fragment(fragment);
+    if let Some(&pin) = pin.as_ref() {
+        reference = reference.pinned(pin);
+    }
+    with_builder(builder, |b| b.push(Value::Ref(reference)))
+}
+
+/// # Safety
+/// `builder` must be null or a live builder.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_begin_array(builder: *mut ToonBuilder) -> ToonStatus {
+    with_builder(builder, |b| b.open(Frame::Array(Vec::new())))
+}
+
+/// # Safety
+/// `builder` must be null or a live builder.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_end_array(builder: *mut ToonBuilder) -> ToonStatus {
+    with_builder(builder, |b| match b.stack.pop() {
+        Some(Frame::Array(items)) => b.push(Value::Array(items)),
+        other => {
+            b.stack.extend(other);
+            ToonStatus::BuilderState
+        }
+    })
+}
+
+/// # Safety
+/// `builder` must be null or a live builder.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_begin_object(builder: *mut ToonBuilder) -> ToonStatus {
+    with_builder(builder, |b| {
+        b.open(Frame::Object {
+            entries: HashMap::new(),
+            key: None,
+        })
+    })
+}
+
+/// Sets the key for the next value pushed into the innermost object.
+///
+/// # Safety
+/// `builder` must be null or a live builder; `data` must be readable for
+/// `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_key(
+    builder: *mut ToonBuilder,
+    data: *const u8,
+    len: usize,
+) -> ToonStatus {
+    let Some(s) = string(data, len) else {
+        return invalid_string(data);
+    };
+    with_builder(builder, |b| match b.stack.last_mut() {
+        Some(Frame::Object { entries, key }) if key.is_none() => {
+            if entries.contains_key(&s) {
+                return ToonStatus::DuplicateKey;
+            }
+            *key = Some(s);
+            ToonStatus::Ok
+        }
+        _ => ToonStatus::BuilderState,
+    })
+}
+
+/// Closes the innermost object. Fails if a key is still waiting for a value.
+///
+/// # Safety
+/// `builder` must be null or a live builder.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_end_object(builder: *mut ToonBuilder) -> ToonStatus {
+    with_builder(builder, |b| match b.stack.pop() {
+        Some(Frame::Object { entries, key: None }) => b.push(Value::Object(entries)),
+        other => {
+            b.stack.extend(other);
+            ToonStatus::BuilderState
+        }
+    })
+}
+
+/// Serializes the finished value as a token with the 16-byte id at `id`.
+/// The builder is left untouched and can be serialized again.
+///
+/// # Safety
+/// `builder` must be null or a live builder; `id` must be readable for 16
+/// bytes; `out` must be null or writable. On success `out` owns a buffer to
+/// release with `toon_buffer_free`.
+#[no_mangle]
+pub unsafe extern "C" fn toon_builder_serialize(
+    builder: *const ToonBuilder,
+    id: *const u8,
+    out: *mut ToonBuffer,
+) -> ToonStatus {
+    let (Some(builder), Some(id), Some(out)) = (builder.as_ref(), token_id(id), out.as_mut())
+    else {
+        return ToonStatus::NullPointer;
+    };
+    let Some(root) = builder.root.as_ref().filter(|_| builder.stack.is_empty()) else {
+        return ToonStatus::BuilderState;
+    };
+
+    let token = Token::new(id, root.clone(), Metadata::new(0, 0));
+    match Serializer::new().serialize(&token) {
+        Ok(bytes) => {
+            *out = ToonBuffer::from_vec(bytes);
+            ToonStatus::Ok
+        }
+        Err(err) => err.into(),
+    }
+}
+
+unsafe fn with_builder(
+    builder: *mut ToonBuilder,
+    f: impl FnOnce(&mut ToonBuilder) -> ToonStatus,
+) -> ToonStatus {
+    match builder.as_mut() {
+        Some(builder) => f(builder),
+        None => ToonStatus::NullPointer,
+    }
+}
+
+unsafe fn string(data: *const u8, len: usize) -> Option<String> {
+    String::from_utf8(bytes(data, len)?.to_vec()).ok()
+}
+
+fn invalid_string(data: *const u8) -> ToonStatus {
+    if data.is_null() {
+        ToonStatus::NullPointer
+    } else {
+        ToonStatus::InvalidUtf8
+    }
+}
@@ -0,0 +1,73 @@
+//! C ABI for `toon-format`.
+//!
+//! All pointers passed in must be either null (reported as
+//! `TOON_STATUS_NULL_POINTER`) or valid for the access the function
+//! documents. Byte strings are passed as pointer + length and are not
+//! NUL-terminated.
+
+mod builder;
+mod status;
+mod token;
+mod value;
+
+pub use builder::*;
+pub use status::*;
+pub use token::*;
+pub use value::*;
+
+/// Bytes allocated by the library. Release with `toon_buffer_free`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct ToonBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl ToonBuffer {
+    fn from_vec(bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        Self { data, len }
+    }
+}
+
+/// Releases a buffer returned by the library and resets it to empty.
+///
+/// # Safety
+/// `buffer` must be null or point to a `ToonBuffer` filled in by this library
+/// that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn toon_buffer_free(buffer: *mut ToonBuffer) {
+    let Some(buffer) = buffer.as_mut() else {
+        return;
+    };
+    if !buffer.data.is_null() {
+        let slice = std::ptr::slice_from_raw_parts_mut(buffer.data, buffer.len);
+        drop(Box::from_raw(slice));
+    }
+    buffer.data = std::ptr::null_mut();
+    buffer.len = 0;
+}
+
+/// Binary format version produced and accepted by this library.
+#[no_mangle]
+pub extern "C" fn toon_format_version() -> u8 {
+    toon_format::constants::FORMAT_VERSION
+}
+
+/// Borrows `len` bytes at `ptr`; a null pointer is only accepted for `len == 0`.
+unsafe fn bytes<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    if ptr.is_null() {
+        return (len == 0).then_some(&[]);
+    }
+    Some(std::slice::from_raw_parts(ptr, len))
+}
+
+unsafe fn token_id(ptr: *const u8) -> Option<toon_format::TokenId> {
+    let bytes: [u8; 16] = bytes(ptr, 16)?.try_into().ok()?;
+    Some(uuid_from(bytes))
+}
+
+fn uuid_from(bytes: [u8; 16]) -> toon_format::TokenId {
+    toon_format::TokenId::from(uuid::Uuid::from_bytes(bytes))
+}
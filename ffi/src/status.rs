@@ -0,0 +1,118 @@
+use std::ffi::{c_char, CStr};
+
+use toon_format::{DeserializeError, SerializeError};
+
+/// Result code returned by fallible functions.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToonStatus {
+    Ok = 0,
+    NullPointer,
+    InvalidUtf8,
+    Truncated,
+    UnsupportedVersion,
+    ChecksumMismatch,
+    UnknownTypeMarker,
+    InvalidLength,
+    TrailingBytes,
+    InvalidReference,
+    Encrypted,
+    InvalidData,
+    LengthOverflow,
+    TypeMismatch,
+    OutOfRange,
+    NotFound,
+    DuplicateKey,
+    BuilderState,
+    InvalidArgument,
+}
+
+impl ToonStatus {
+    const ALL: [ToonStatus; 19] = [
+        ToonStatus::Ok,
+        ToonStatus::NullPointer,
+        ToonStatus::InvalidUtf8,
+        ToonStatus::Truncated,
+        ToonStatus::UnsupportedVersion,
+        ToonStatus::ChecksumMismatch,
+        ToonStatus::UnknownTypeMarker,
+        ToonStatus::InvalidLength,
+        ToonStatus::TrailingBytes,
+        ToonStatus::InvalidReference,
+        ToonStatus::Encrypted,
+        ToonStatus::InvalidData,
+        ToonStatus::LengthOverflow,
+        ToonStatus::TypeMismatch,
+        ToonStatus::OutOfRange,
+        ToonStatus::NotFound,
+        ToonStatus::DuplicateKey,
+        ToonStatus::BuilderState,
+        ToonStatus::InvalidArgument,
+    ];
+
+    /// The status whose value is `raw`, if any.
+    fn from_raw(raw: u32) -> Option<Self> {
+        let status = *Self::ALL.get(usize::try_from(raw).ok()?)?;
+        debug_assert_eq!(status as u32, raw);
+        Some(status)
+    }
+
+    fn message(self) -> &'static CStr {
+        match self {
+            ToonStatus::Ok => c"ok",
+            ToonStatus::NullPointer => c"null pointer argument",
+            ToonStatus::InvalidUtf8 => c"invalid utf-8",
+            ToonStatus::Truncated => c"input is truncated",
+            ToonStatus::UnsupportedVersion => c"unsupported format version",
+            ToonStatus::ChecksumMismatch => c"checksum mismatch",
+            ToonStatus::UnknownTypeMarker => c"unknown type marker",
+            ToonStatus::InvalidLength => c"invalid length for type",
+            ToonStatus::TrailingBytes => c"trailing bytes in payload",
+            ToonStatus::InvalidReference => c"invalid reference strength",
+            ToonStatus::Encrypted => c"payload is encrypted",
+            ToonStatus::InvalidData => c"token cannot be decoded",
+            ToonStatus::LengthOverflow => c"payload length does not fit in u32",
+            ToonStatus::TypeMismatch => c"value has a different type",
+            ToonStatus::OutOfRange => c"index out of range",
+            ToonStatus::NotFound => c"key not found",
+            ToonStatus::DuplicateKey => c"duplicate object key",
+            ToonStatus::BuilderState => c"builder call not valid in its current state",
+            ToonStatus::InvalidArgument => c"argument out of range for its type",
+        }
+    }
+}
+
+impl From<DeserializeError> for ToonStatus {
+    fn from(err: DeserializeError) -> Self {
+        match err {
+            DeserializeError::Truncated => ToonStatus::Truncated,
+            DeserializeError::UnsupportedVersion => ToonStatus::UnsupportedVersion,
+            DeserializeError::ChecksumMismatch => ToonStatus::ChecksumMismatch,
+            DeserializeError::UnknownTypeMarker(_) => ToonStatus::UnknownTypeMarker,
+            DeserializeError::InvalidLength => ToonStatus::InvalidLength,
+            DeserializeError::InvalidUtf8 => ToonStatus::InvalidUtf8,
+            DeserializeError::TrailingBytes => ToonStatus::TrailingBytes,
+            DeserializeError::InvalidReferenceStrength => ToonStatus::InvalidReference,
+            DeserializeError::Encrypted => ToonStatus::Encrypted,
+            _ => ToonStatus::InvalidData,
+        }
+    }
+}
+
+impl From<SerializeError> for ToonStatus {
+    fn from(err: SerializeError) -> Self {
+        match err {
+            SerializeError::LengthOverflow => ToonStatus::LengthOverflow,
+            _ => ToonStatus::InvalidData,
+        }
+    }
+}
+
+/// Static, NUL-terminated description of the `ToonStatus` value `status`;
+/// values that are not a `ToonStatus` read as "unknown status".
+#[no_mangle]
+pub extern "C" fn toon_status_message(status: u32) -> *const c_char {
+    ToonStatus::from_raw(status)
+        .map_or(c"unknown status", ToonStatus::message)
+        .as_ptr()
+}
@@ -0,0 +1,237 @@
+use toon_format::{Deserializer, Token};
+
+use crate::value::ToonValue;
+use crate::{bytes, ToonStatus};
+
+/// A deserialized token. Release with `toon_token_free`.
+#[derive(Debug)]
+pub struct ToonToken {
+    /// Handles into `token`, which is boxed so that they stay valid.
+    root: ToonValue,
+    token: Box<Token>,
+}
+
+impl ToonToken {
+    fn new(token: Token) -> Self {
+        let token = Box::new(token);
+        Self {
+            root: ToonValue::new(token.value()),
+            token,
+        }
+    }
+}
+
+/// Fixed-size frame header, read without validating the rest of the frame.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ToonHeader {
+    pub version: u8,
+    pub id: [u8; 16],
+    pub type_marker: u8,
+    pub payload_len: u32,
+}
+
+/// Fixed-size part of a token's metadata, read without decoding its value.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ToonMetadata {
+    pub created_at_ms: u64,
+    pub flags: u32,
+    /// Type marker of the value, which a metadata section may wrap.
+    pub value_marker: u8,
+}
+
+/// Byte offsets of the sections of a frame whose lengths are consistent.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ToonLayout {
+    pub header: ToonHeader,
+    pub payload_offset: usize,
+    pub payload_len: usize,
+    pub checksum_offset: usize,
+}
+
+/// Deserializes and verifies a token. On success `*out` owns the token.
+///
+/// # Safety
+/// `data` must be readable for `len` bytes; `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_token_deserialize(
+    data: *const u8,
+    len: usize,
+    out: *mut *mut ToonToken,
+) -> ToonStatus {
+    let (Some(data), Some(out)) = (bytes(data, len), out.as_mut()) else {
+        return ToonStatus::NullPointer;
+    };
+    match Deserializer::new(data).deserialize() {
+        Ok(token) => {
+            *out = Box::into_raw(Box::new(ToonToken::new(token)));
+            ToonStatus::Ok
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// Checks that `data` holds one complete, uncorrupted token.
+///
+/// # Safety
+/// `data` must be readable for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn toon_verify(data: *const u8, len: usize) -> ToonStatus {
+    let Some(data) = bytes(data, len) else {
+        return ToonStatus::NullPointer;
+    };
+    match Deserializer::new(data).deserialize() {
+        Ok(_) => ToonStatus::Ok,
+        Err(err) => err.into(),
+    }
+}
+
+/// # Safety
+/// `token` must be null or come from `toon_token_deserialize` and not be
+/// freed. Value pointers read from it become dangling.
+#[no_mangle]
+pub unsafe extern "C" fn toon_token_free(token: *mut ToonToken) {
+    if !token.is_null() {
+        drop(Box::from_raw(token));
+    }
+}
+
+/// Copies the token's 16-byte id to `out`.
+///
+/// # Safety
+/// `token` must be null or a live token; `out` must be writable for 16 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn toon_token_id(token: *const ToonToken, out: *mut u8) -> ToonStatus {
+    let Some(token) = token.as_ref() else {
+        return ToonStatus::NullPointer;
+    };
+    if out.is_null() {
+        return ToonStatus::NullPointer;
+    }
+    std::ptr::copy_nonoverlapping(token.token.id().as_bytes().as_ptr(), out, 16);
+    ToonStatus::Ok
+}
+
+/// Writes the token's checksum to `out`, for pinning references to this
+/// version of it with `toon_builder_push_ref_with`.
+///
+/// # Safety
+/// `token` must be null or a live token; `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_token_checksum(token: *const ToonToken, out: *mut u32) -> ToonStatus {
+    let (Some(token), Some(out)) = (token.as_ref(), out.as_mut()) else {
+        return ToonStatus::NullPointer;
+    };
+    match token.token.checksum() {
+        Ok(checksum) => {
+            *out = checksum;
+            ToonStatus::Ok
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// Root value of the token, borrowed for the token's lifetime. Null if
+/// `token` is null.
+///
+/// # Safety
+/// `token` must be null or a live token.
+#[no_mangle]
+pub unsafe extern "C" fn toon_token_value(token: *const ToonToken) -> *const ToonValue {
+    match token.as_ref() {
+        Some(token) => &token.root,
+        None => std::ptr::null(),
+    }
+}
+
+/// # Safety
+/// `data` must be readable for `len` bytes; `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_read_header(
+    data: *const u8,
+    len: usize,
+    out: *mut ToonHeader,
+) -> ToonStatus {
+    let (Some(data), Some(out)) = (bytes(data, len), out.as_mut()) else {
+        return ToonStatus::NullPointer;
+    };
+    match Deserializer::new(data).header() {
+        Ok(header) => {
+            *out = convert_header(header);
+            ToonStatus::Ok
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// Like `toon_read_header`, additionally checking the version and that the
+/// declared payload length matches the input length. The checksum is not
+/// verified.
+///
+/// # Safety
+/// `data` must be readable for `len` bytes; `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_read_layout(
+    data: *const u8,
+    len: usize,
+    out: *mut ToonLayout,
+) -> ToonStatus {
+    let (Some(data), Some(out)) = (bytes(data, len), out.as_mut()) else {
+        return ToonStatus::NullPointer;
+    };
+    match Deserializer::new(data).layout() {
+        Ok(layout) => {
+            *out = ToonLayout {
+                header: convert_header(layout.header),
+                payload_offset: layout.payload_range.start,
+                payload_len: layout.payload_range.len(),
+                checksum_offset: layout.checksum_range.start,
+            };
+            ToonStatus::Ok
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// Reads the metadata of a frame whose lengths are consistent. Encrypted and
+/// delta frames keep their metadata inside the payload and are refused.
+///
+/// # Safety
+/// `data` must be readable for `len` bytes; `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_read_metadata(
+    data: *const u8,
+    len: usize,
+    out: *mut ToonMetadata,
+) -> ToonStatus {
+    let (Some(data), Some(out)) = (bytes(data, len), out.as_mut()) else {
+        return ToonStatus::NullPointer;
+    };
+    let deserializer = Deserializer::new(data);
+    match deserializer.metadata().and_then(|metadata| {
+        deserializer
+            .value_marker()
+            .map(|value_marker| (metadata, value_marker))
+    }) {
+        Ok((metadata, value_marker)) => {
+            *out = ToonMetadata {
+                created_at_ms: metadata.created_at_ms,
+                flags: metadata.flags.bits(),
+                value_marker,
+            };
+            ToonStatus::Ok
+        }
+        Err(err) => err.into(),
+    }
+}
+
+fn convert_header(header: toon_format::TokenHeader) -> ToonHeader {
+    ToonHeader {
+        version: header.version,
+        id: header.id,
+        type_marker: header.type_marker,
+        payload_len: header.payload_len,
+    }
+}
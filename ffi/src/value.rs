@@ -0,0 +1,357 @@
+use toon_format::{TokenRefStrength, Value};
+
+use crate::{bytes, ToonStatus};
+
+/// A value inside a `ToonToken`. Pointers to it borrow from the token.
+// Each handle mirrors one `Value` of the token and keeps its children, with
+// object entries sorted by key once so that they can be read by index.
+#[derive(Debug)]
+pub struct ToonValue {
+    value: *const Value,
+    children: Children,
+}
+
+#[derive(Debug)]
+enum Children {
+    None,
+    Items(Box<[ToonValue]>),
+    /// Entries in ascending key order.
+    Entries(Box<[(*const str, ToonValue)]>),
+}
+
+impl ToonValue {
+    /// Builds handles for `value` and everything below it. They point into
+    /// `value`, which must not move or change while they are alive.
+    pub(crate) fn new(value: &Value) -> Self {
+        let children = match value {
+            Value::Array(items) => Children::Items(items.iter().map(Self::new).collect()),
+            Value::Object(map) => {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+                Children::Entries(
+                    entries
+                        .into_iter()
+                        .map(|(key, member)| (key.as_str() as *const str, Self::new(member)))
+                        .collect(),
+                )
+            }
+            _ => Children::None,
+        };
+        Self { value, children }
+    }
+
+    unsafe fn get<'a>(value: *const ToonValue) -> Option<&'a ToonValue> {
+        value.as_ref()
+    }
+
+    fn value(&self) -> &Value {
+        // SAFETY: the handle is owned by the token holding the value.
+        unsafe { &*self.value }
+    }
+
+    fn entries(&self) -> Option<&[(*const str, ToonValue)]> {
+        match &self.children {
+            Children::Entries(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+fn entry_key(entry: &(*const str, ToonValue)) -> &str {
+    // SAFETY: keys point into the value the entry belongs to.
+    unsafe { &*entry.0 }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToonValueKind {
+    Null = 0,
+    Bool,
+    Int,
+    Float,
+    String,
+    Array,
+    Object,
+    Ref,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToonRefStrength {
+    Strong = 0,
+    Weak = 1,
+}
+
+/// Kind of `value`; a null pointer reads as `TOON_VALUE_KIND_NULL`.
+///
+/// # Safety
+/// `value` must be null or point into a live token.
+#[no_mangle]
+pub unsafe extern "C" fn toon_value_kind(value: *const ToonValue) -> ToonValueKind {
+    match ToonValue::get(value).map(ToonValue::value) {
+        None | Some(Value::Null) => ToonValueKind::Null,
+        Some(Value::Bool(_)) => ToonValueKind::Bool,
+        Some(Value::Int(_)) => ToonValueKind::Int,
+        Some(Value::Float(_)) => ToonValueKind::Float,
+        Some(Value::String(_)) => ToonValueKind::String,
+        Some(Value::Array(_)) => ToonValueKind::Array,
+        Some(Value::Object(_)) => ToonValueKind::Object,
+        Some(Value::Ref(_)) => ToonValueKind::Ref,
+    }
+}
+
+/// # Safety
+/// `value` must be null or point into a live token; `out` must be null or
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_value_get_bool(
+    value: *const ToonValue,
+    out: *mut bool,
+) -> ToonStatus {
+    read(value, out, Value::as_bool)
+}
+
+/// # Safety
+/// `value` must be null or point into a live token; `out` must be null or
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_value_get_int(value: *const ToonValue, out: *mut i64) -> ToonStatus {
+    read(value, out, Value::as_i64)
+}
+
+/// # Safety
+/// `value` must be null or point into a live token; `out` must be null or
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_value_get_float(
+    value: *const ToonValue,
+    out: *mut f64,
+) -> ToonStatus {
+    read(value, out, Value::as_f64)
+}
+
+/// Borrows the UTF-8 bytes of a string value; they are not NUL-terminated.
+///
+/// # Safety
+/// `value` must be null or point into a live token; `data` and `len` must be
+/// null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_value_get_string(
+    value: *const ToonValue,
+    data: *mut *const u8,
+    len: *mut usize,
+) -> ToonStatus {
+    let (Some(value), Some(data), Some(len)) = (ToonValue::get(value), data.as_mut(), len.as_mut())
+    else {
+        return ToonStatus::NullPointer;
+    };
+    let Some(s) = value.value().as_str() else {
+        return ToonStatus::TypeMismatch;
+    };
+    *data = s.as_ptr();
+    *len = s.len();
+    ToonStatus::Ok
+}
+
+/// Copies the referenced token id (16 bytes) to `id` and its strength to
+/// `strength`. The pin and fragment are read with `toon_value_get_ref_pin`
+/// and `toon_value_get_ref_fragment`.
+///
+/// # Safety
+/// `value` must be null or point into a live token; `id` must be writable
+/// for 16 bytes; `strength` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_value_get_ref(
+    value: *const ToonValue,
+    id: *mut u8,
+    strength: *mut ToonRefStrength,
+) -> ToonStatus {
+    let (Some(value), Some(strength)) = (ToonValue::get(value), strength.as_mut()) else {
+        return ToonStatus::NullPointer;
+    };
+    if id.is_null() {
+        return ToonStatus::NullPointer;
+    }
+    let Value::Ref(reference) = value.value() else {
+        return ToonStatus::TypeMismatch;
+    };
+    std::ptr::copy_nonoverlapping(reference.id().as_bytes().as_ptr(), id, 16);
+    *strength = match reference.strength() {
+        TokenRefStrength::Strong => ToonRefStrength::Strong,
+        TokenRefStrength::Weak => ToonRefStrength::Weak,
+    };
+    ToonStatus::Ok
+}
+
+/// Reads the pin of a reference: `*pinned` tells whether it is pinned and
+/// `*pin` receives the target's checksum, or 0 when it is not.
+///
+/// # Safety
+/// `value` must be null or point into a live token; `pinned` and `pin` must
+/// be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_value_get_ref_pin(
+    value: *const ToonValue,
+    pinned: *mut bool,
+    pin: *mut u32,
+) -> ToonStatus {
+    let (Some(value), Some(pinned), Some(pin)) =
+        (ToonValue::get(value), pinned.as_mut(), pin.as_mut())
+    else {
+        return ToonStatus::NullPointer;
+    };
+    let Value::Ref(reference) = value.value() else {
+        return ToonStatus::TypeMismatch;
+    };
+    *pinned = reference.pin().is_some();
+    *pin = reference.pin().unwrap_or(0);
+    ToonStatus::Ok
+}
+
+/// Borrows the JSON Pointer fragment of a reference; it is empty when the
+/// reference points at the whole token, and not NUL-terminated.
+///
+/// # Safety
+/// `value` must be null or point into a live token; `data` and `len` must be
+/// null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_value_get_ref_fragment(
+    value: *const ToonValue,
+    data: *mut *const u8,
+    len: *mut usize,
+) -> ToonStatus {
+    let (Some(value), Some(data), Some(len)) = (ToonValue::get(value), data.as_mut(), len.as_mut())
+    else {
+        return ToonStatus::NullPointer;
+    };
+    let Value::Ref(reference) = value.value() else {
+        return ToonStatus::TypeMismatch;
+    };
+    let fragment = reference.fragment().unwrap_or_default();
+    *data = fragment.as_ptr();
+    *len = fragment.len();
+    ToonStatus::Ok
+}
+
+/// Number of elements of an array or entries of an object; 0 otherwise.
+///
+/// # Safety
+/// `value` must be null or point into a live token.
+#[no_mangle]
+pub unsafe extern "C" fn toon_value_len(value: *const ToonValue) -> usize {
+    match ToonValue::get(value).map(|value| &value.children) {
+        Some(Children::Items(items)) => items.len(),
+        Some(Children::Entries(entries)) => entries.len(),
+        _ => 0,
+    }
+}
+
+/// # Safety
+/// `value` must be null or point into a live token; `out` must be null or
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_value_array_get(
+    value: *const ToonValue,
+    index: usize,
+    out: *mut *const ToonValue,
+) -> ToonStatus {
+    let (Some(value), Some(out)) = (ToonValue::get(value), out.as_mut()) else {
+        return ToonStatus::NullPointer;
+    };
+    let Children::Items(items) = &value.children else {
+        return ToonStatus::TypeMismatch;
+    };
+    match items.get(index) {
+        Some(item) => {
+            *out = item;
+            ToonStatus::Ok
+        }
+        None => ToonStatus::OutOfRange,
+    }
+}
+
+/// Looks up the member named by the `key_len` UTF-8 bytes at `key`.
+///
+/// # Safety
+/// `value` must be null or point into a live token; `key` must be readable
+/// for `key_len` bytes; `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_value_object_get(
+    value: *const ToonValue,
+    key: *const u8,
+    key_len: usize,
+    out: *mut *const ToonValue,
+) -> ToonStatus {
+    let (Some(value), Some(key), Some(out)) =
+        (ToonValue::get(value), bytes(key, key_len), out.as_mut())
+    else {
+        return ToonStatus::NullPointer;
+    };
+    let Ok(key) = std::str::from_utf8(key) else {
+        return ToonStatus::InvalidUtf8;
+    };
+    let Some(entries) = value.entries() else {
+        return ToonStatus::TypeMismatch;
+    };
+    match entries.binary_search_by(|entry| entry_key(entry).cmp(key)) {
+        Ok(index) => {
+            *out = &entries[index].1;
+            ToonStatus::Ok
+        }
+        Err(_) => ToonStatus::NotFound,
+    }
+}
+
+/// Reads the `index`-th entry of an object, in ascending order of the keys'
+/// UTF-8 bytes. The key is borrowed and not NUL-terminated.
+///
+/// # Safety
+/// `value` must be null or point into a live token; the out pointers must be
+/// null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn toon_value_object_entry(
+    value: *const ToonValue,
+    index: usize,
+    key: *mut *const u8,
+    key_len: *mut usize,
+    out: *mut *const ToonValue,
+) -> ToonStatus {
+    let (Some(value), Some(key), Some(key_len), Some(out)) = (
+        ToonValue::get(value),
+        key.as_mut(),
+        key_len.as_mut(),
+        out.as_mut(),
+    ) else {
+        return ToonStatus::NullPointer;
+    };
+    let Some(entries) = value.entries() else {
+        return ToonStatus::TypeMismatch;
+    };
+    match entries.get(index) {
+        Some(entry) => {
+            let name = entry_key(entry);
+            *key = name.as_ptr();
+            *key_len = name.len();
+            *out = &entry.1;
+            ToonStatus::Ok
+        }
+        None => ToonStatus::OutOfRange,
+    }
+}
+
+unsafe fn read<T>(
+    value: *const ToonValue,
+    out: *mut T,
+    get: impl FnOnce(&Value) -> Option<T>,
+) -> ToonStatus {
+    let (Some(value), Some(out)) = (ToonValue::get(value), out.as_mut()) else {
+        return ToonStatus::NullPointer;
+    };
+    match get(value.value()) {
+        Some(v) => {
+            *out = v;
+            ToonStatus::Ok
+        }
+        None => ToonStatus::TypeMismatch,
+    }
+}
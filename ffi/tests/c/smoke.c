@@ -0,0 +1,167 @@
+/* Builds, serializes, verifies and walks a token through the C API. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "toon.h"
+
+#define CHECK(expr)                                                         \
+  do {                                                                      \
+    if (!(expr)) {                                                          \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #expr); \
+      return 1;                                                             \
+    }                                                                       \
+  } while (0)
+
+#define OK(call) CHECK((call) == TOON_STATUS_OK)
+
+static ToonStatus key(ToonBuilder *b, const char *s) {
+  return toon_builder_key(b, (const uint8_t *)s, strlen(s));
+}
+
+static ToonStatus str(ToonBuilder *b, const char *s) {
+  return toon_builder_push_string(b, (const uint8_t *)s, strlen(s));
+}
+
+static const ToonValue *member(const ToonValue *object, const char *name) {
+  const ToonValue *out = NULL;
+  if (toon_value_object_get(object, (const uint8_t *)name, strlen(name), &out) != TOON_STATUS_OK) {
+    return NULL;
+  }
+  return out;
+}
+
+int main(void) {
+  uint8_t id[16], parent[16], read_id[16];
+  memset(id, 0x11, sizeof id);
+  memset(parent, 0x22, sizeof parent);
+
+  ToonBuilder *b = toon_builder_new();
+  CHECK(b != NULL);
+  OK(toon_builder_begin_object(b));
+  OK(key(b, "name"));
+  OK(str(b, "widget"));
+  OK(key(b, "count"));
+  OK(toon_builder_push_int(b, -42));
+  OK(key(b, "ratio"));
+  OK(toon_builder_push_float(b, 0.5));
+  OK(key(b, "parent"));
+  CHECK(toon_builder_push_ref(b, parent, 7) == TOON_STATUS_INVALID_ARGUMENT);
+  OK(toon_builder_push_ref(b, parent, TOON_REF_STRENGTH_WEAK));
+  OK(key(b, "style"));
+  uint32_t pin = 0xDEADBEEF;
+  OK(toon_builder_push_ref_with(b, parent, TOON_REF_STRENGTH_STRONG, &pin, (const uint8_t *)"/font", 5));
+  CHECK(toon_builder_push_ref_with(b, parent, TOON_REF_STRENGTH_STRONG, NULL, (const uint8_t *)"\xff", 1) ==
+        TOON_STATUS_INVALID_UTF8);
+  OK(key(b, "tags"));
+  OK(toon_builder_begin_array(b));
+  OK(toon_builder_push_bool(b, true));
+  OK(toon_builder_push_null(b));
+  OK(toon_builder_end_array(b));
+  CHECK(key(b, "name") == TOON_STATUS_DUPLICATE_KEY);
+  CHECK(toon_builder_push_int(b, 1) == TOON_STATUS_BUILDER_STATE);
+  OK(toon_builder_end_object(b));
+  CHECK(toon_builder_push_int(b, 1) == TOON_STATUS_BUILDER_STATE);
+
+  ToonBuffer buf = {0};
+  OK(toon_builder_serialize(b, id, &buf));
+  toon_builder_free(b);
+
+  ToonHeader header;
+  OK(toon_read_header(buf.data, buf.len, &header));
+  CHECK(header.version == toon_format_version());
+  CHECK(memcmp(header.id, id, 16) == 0);
+
+  ToonMetadata metadata;
+  OK(toon_read_metadata(buf.data, buf.len, &metadata));
+  CHECK(metadata.value_marker == header.type_marker);
+  CHECK(metadata.flags == 0);
+
+  ToonLayout layout;
+  OK(toon_read_layout(buf.data, buf.len, &layout));
+  CHECK(layout.payload_offset == 22);
+  CHECK(layout.checksum_offset + 4 == buf.len);
+  OK(toon_verify(buf.data, buf.len));
+
+  ToonToken *token = NULL;
+  OK(toon_token_deserialize(buf.data, buf.len, &token));
+  OK(toon_token_id(token, read_id));
+  CHECK(memcmp(read_id, id, 16) == 0);
+
+  const ToonValue *root = toon_token_value(token);
+  CHECK(toon_value_kind(root) == TOON_VALUE_KIND_OBJECT);
+  CHECK(toon_value_len(root) == 6);
+  uint32_t checksum;
+  OK(toon_token_checksum(token, &checksum));
+
+  const uint8_t *text;
+  size_t text_len;
+  OK(toon_value_get_string(member(root, "name"), &text, &text_len));
+  CHECK(text_len == 6 && memcmp(text, "widget", 6) == 0);
+
+  int64_t count;
+  OK(toon_value_get_int(member(root, "count"), &count));
+  CHECK(count == -42);
+  CHECK(toon_value_get_float(member(root, "count"), &(double){0}) == TOON_STATUS_TYPE_MISMATCH);
+
+  double ratio;
+  OK(toon_value_get_float(member(root, "ratio"), &ratio));
+  CHECK(ratio == 0.5);
+
+  ToonRefStrength strength;
+  OK(toon_value_get_ref(member(root, "parent"), read_id, &strength));
+  CHECK(strength == TOON_REF_STRENGTH_WEAK);
+  CHECK(memcmp(read_id, parent, 16) == 0);
+  bool pinned = true;
+  uint32_t read_pin = 1;
+  OK(toon_value_get_ref_pin(member(root, "parent"), &pinned, &read_pin));
+  CHECK(!pinned && read_pin == 0);
+  OK(toon_value_get_ref_fragment(member(root, "parent"), &text, &text_len));
+  CHECK(text_len == 0);
+
+  OK(toon_value_get_ref(member(root, "style"), read_id, &strength));
+  CHECK(strength == TOON_REF_STRENGTH_STRONG);
+  OK(toon_value_get_ref_pin(member(root, "style"), &pinned, &read_pin));
+  CHECK(pinned && read_pin == 0xDEADBEEF);
+  OK(toon_value_get_ref_fragment(member(root, "style"), &text, &text_len));
+  CHECK(text_len == 5 && memcmp(text, "/font", 5) == 0);
+  CHECK(toon_value_get_ref_pin(member(root, "count"), &pinned, &read_pin) == TOON_STATUS_TYPE_MISMATCH);
+
+  const ToonValue *tags = member(root, "tags"), *item;
+  CHECK(toon_value_len(tags) == 2);
+  OK(toon_value_array_get(tags, 0, &item));
+  bool flag = false;
+  OK(toon_value_get_bool(item, &flag));
+  CHECK(flag);
+  OK(toon_value_array_get(tags, 1, &item));
+  CHECK(toon_value_kind(item) == TOON_VALUE_KIND_NULL);
+  CHECK(toon_value_array_get(tags, 2, &item) == TOON_STATUS_OUT_OF_RANGE);
+  CHECK(member(root, "missing") == NULL);
+
+  static const char *const sorted[] = {"count", "name", "parent", "ratio", "style", "tags"};
+  for (size_t i = 0; i < toon_value_len(root); i++) {
+    const uint8_t *name;
+    size_t name_len;
+    const ToonValue *value;
+    OK(toon_value_object_entry(root, i, &name, &name_len, &value));
+    CHECK(name_len == strlen(sorted[i]) && memcmp(name, sorted[i], name_len) == 0);
+    CHECK(value == member(root, sorted[i]));
+  }
+  CHECK(toon_value_object_entry(root, 6, &text, &text_len, &item) == TOON_STATUS_OUT_OF_RANGE);
+  toon_token_free(token);
+
+  buf.data[30] ^= 0xFF;
+  CHECK(toon_verify(buf.data, buf.len) == TOON_STATUS_CHECKSUM_MISMATCH);
+  CHECK(toon_token_deserialize(buf.data, buf.len - 1, &token) != TOON_STATUS_OK);
+  CHECK(strcmp(toon_status_message(TOON_STATUS_CHECKSUM_MISMATCH), "checksum mismatch") == 0);
+  CHECK(strcmp(toon_status_message(TOON_STATUS_INVALID_ARGUMENT), "argument out of range for its type") == 0);
+  CHECK(strcmp(toon_status_message(9999), "unknown status") == 0);
+  CHECK(toon_token_deserialize(NULL, 4, &token) == TOON_STATUS_NULL_POINTER);
+
+  toon_buffer_free(&buf);
+  CHECK(buf.data == NULL && buf.len == 0);
+  toon_buffer_free(&buf);
+
+  puts("ok");
+  return 0;
+}
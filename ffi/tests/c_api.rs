@@ -0,0 +1,44 @@
+//! Compiles `tests/c/smoke.c` against the cdylib and `include/toon.h` and
+//! runs it.
+
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn library_dir() -> PathBuf {
+    // The test binary lives in `target/<profile>/deps`; the cdylib is placed
+    // one level up.
+    let exe = std::env::current_exe().unwrap();
+    exe.parent().unwrap().parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn c_program_uses_the_api() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lib_dir = library_dir();
+    let exe = Path::new(env!("CARGO_TARGET_TMPDIR")).join("toon_ffi_smoke");
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let status = Command::new(compiler)
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-o"])
+        .arg(&exe)
+        .arg(crate_dir.join("tests/c/smoke.c"))
+        .arg("-I")
+        .arg(crate_dir.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-ltoon_ffi")
+        .status()
+        .expect("C compiler not found; set CC");
+    assert!(status.success(), "compiling smoke.c failed");
+
+    let output = Command::new(&exe).output().unwrap();
+    assert!(
+        output.status.success(),
+        "smoke test failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "ok");
+}
@@ -0,0 +1,26 @@
+use std::path::Path;
+
+#[test]
+fn header_is_up_to_date() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(crate_dir)
+        .with_config(config)
+        .generate()
+        .unwrap()
+        .write(&mut generated);
+
+    let path = crate_dir.join("include/toon.h");
+    if std::env::var_os("TOON_FFI_UPDATE_HEADER").is_some() {
+        std::fs::write(&path, &generated).unwrap();
+        return;
+    }
+
+    let committed = std::fs::read(&path).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "include/toon.h is stale; rerun with TOON_FFI_UPDATE_HEADER=1"
+    );
+}
@@ -0,0 +1,56 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use uuid::Uuid;
+
+use toon_format::{toon, ArenaDecoder, Deserializer, Metadata, Serializer, Token, TokenId};
+
+const TOKENS: usize = 10_000;
+
+/// Short-lived ingestion tokens: a few scalars, a nested object and a small
+/// array of strings.
+fn build_frames() -> Vec<Vec<u8>> {
+    (0..TOKENS as i64)
+        .map(|i| {
+            let token = Token::new(
+                TokenId::from(Uuid::from_u128(i as u128)),
+                toon! {
+                    "event": "page_view",
+                    "seq": i,
+                    "latency_ms": i as f64 * 0.1,
+                    "client": { "agent": "bench/1.0", "region": "eu-west" },
+                    "tags": ["web", "mobile", "beta"],
+                },
+                Metadata::new(0, 0),
+            );
+            Serializer::new().serialize(&token).unwrap()
+        })
+        .collect()
+}
+
+fn bench_decode_many(c: &mut Criterion) {
+    let frames = build_frames();
+
+    let mut group = c.benchmark_group("decode_10k_small_tokens");
+    group.throughput(Throughput::Elements(TOKENS as u64));
+
+    group.bench_function("deserialize", |b| {
+        b.iter(|| {
+            for bytes in &frames {
+                black_box(Deserializer::new(black_box(bytes)).deserialize().unwrap());
+            }
+        })
+    });
+
+    group.bench_function("arena_decoder", |b| {
+        let mut decoder = ArenaDecoder::new();
+        b.iter(|| {
+            for bytes in &frames {
+                black_box(decoder.decode(black_box(bytes)).unwrap());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_decode_many);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rayon::ThreadPoolBuilder;
+use uuid::Uuid;
+
+use toon_format::{toon, Deserializer, Metadata, Serializer, Token, TokenId, Value};
+
+const ELEMENTS: usize = 500_000;
+
+fn build_token() -> Token {
+    let items: Vec<Value> = (0..ELEMENTS as i64)
+        .map(|i| toon! { "id": i, "name": format!("item-{i}"), "weight": i as f64 * 0.5 })
+        .collect();
+    let id = TokenId::from(Uuid::from_bytes([5u8; 16]));
+    Token::new(id, Value::Array(items), Metadata::new(0, 0))
+}
+
+fn thread_counts() -> Vec<usize> {
+    let max = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut counts: Vec<usize> = [1, 2, 4, 8].into_iter().filter(|&n| n < max).collect();
+    counts.push(max);
+    counts
+}
+
+fn bench_parallel_encode(c: &mut Criterion) {
+    let token = build_token();
+    let serializer = Serializer::new();
+
+    let mut group = c.benchmark_group("parallel_serialize_500k");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(ELEMENTS as u64));
+    for threads in thread_counts() {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &token, |b, token| {
+            b.iter(|| pool.install(|| serializer.serialize(black_box(token)).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+fn bench_parallel_decode(c: &mut Criterion) {
+    let bytes = Serializer::new().serialize(&build_token()).unwrap();
+
+    let mut group = c.benchmark_group("parallel_deserialize_500k");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(ELEMENTS as u64));
+    for threads in thread_counts() {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &bytes, |b, bytes| {
+            b.iter(|| pool.install(|| Deserializer::new(black_box(bytes)).deserialize().unwrap()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parallel_encode, bench_parallel_decode);
+criterion_main!(benches);
@@ -1,9 +1,9 @@
-use std::collections::HashMap;
 use std::f64::consts::PI;
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use uuid::Uuid;
 
+use toon_format::collections::HashMap;
 use toon_format::{Metadata, Serializer, Token, TokenId, Value};
 
 fn build_sample_value() -> Value {
@@ -0,0 +1,107 @@
+//! `tokio_util` codec framing tokens on a byte stream.
+//!
+//! Tokens are written back to back in their regular serialized form; the
+//! header's `payload_len` delimits each frame, so no extra framing is added.
+
+use std::io;
+
+use bytes::BytesMut;
+use thiserror::Error;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::deserialization::{frame_len, HEADER_LEN};
+use crate::{DeserializeError, Deserializer, SerializeError, Serializer, Token, TokenDecoder};
+
+#[derive(Debug, Error)]
+pub enum CodecError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Serialize(#[from] SerializeError),
+
+    #[error(transparent)]
+    Deserialize(#[from] DeserializeError),
+
+    #[error("frame of {0} bytes exceeds the maximum frame length")]
+    FrameTooLarge(usize),
+}
+
+/// Encodes and decodes `Token` frames. Decoding verifies each frame's
+/// checksum; frames longer than `max_frame_len` are refused in both
+/// directions, on decode as soon as their header has arrived. Frames are
+/// delimited and limited exactly as by `TokenDecoder`.
+#[derive(Debug, Clone)]
+pub struct TokenCodec {
+    max_frame_len: usize,
+}
+
+impl Default for TokenCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenCodec {
+    pub const DEFAULT_MAX_FRAME_LEN: usize = TokenDecoder::DEFAULT_MAX_FRAME_LEN;
+
+    pub fn new() -> Self {
+        Self::with_max_frame_len(Self::DEFAULT_MAX_FRAME_LEN)
+    }
+
+    pub fn with_max_frame_len(max_frame_len: usize) -> Self {
+        Self { max_frame_len }
+    }
+
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+}
+
+impl Decoder for TokenCodec {
+    type Item = Token;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Token>, CodecError> {
+        let len = match frame_len(src, self.max_frame_len) {
+            Ok(Some(len)) => len,
+            Ok(None) => {
+                src.reserve(HEADER_LEN - src.len());
+                return Ok(None);
+            }
+            Err(DeserializeError::FrameTooLarge(len)) => {
+                return Err(CodecError::FrameTooLarge(len))
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        if src.len() < len {
+            src.reserve(len - src.len());
+            return Ok(None);
+        }
+
+        let frame = src.split_to(len);
+        Ok(Some(Deserializer::new(&frame).deserialize()?))
+    }
+}
+
+impl Encoder<&Token> for TokenCodec {
+    type Error = CodecError;
+
+    fn encode(&mut self, token: &Token, dst: &mut BytesMut) -> Result<(), CodecError> {
+        let frame = Serializer::new().serialize(token)?;
+        if frame.len() > self.max_frame_len {
+            return Err(CodecError::FrameTooLarge(frame.len()));
+        }
+        dst.extend_from_slice(&frame);
+        Ok(())
+    }
+}
+
+impl Encoder<Token> for TokenCodec {
+    type Error = CodecError;
+
+    fn encode(&mut self, token: Token, dst: &mut BytesMut) -> Result<(), CodecError> {
+        self.encode(&token, dst)
+    }
+}
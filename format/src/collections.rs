@@ -0,0 +1,7 @@
+//! Map type backing `Value::Object`: `std::collections::HashMap` with the
+//! `std` feature, `hashbrown::HashMap` without it.
+
+#[cfg(not(feature = "std"))]
+pub use hashbrown::HashMap;
+#[cfg(feature = "std")]
+pub use std::collections::HashMap;
@@ -0,0 +1,134 @@
+use alloc::vec::Vec;
+
+use crate::collections::HashMap;
+use crate::deserialization::DeserializeError;
+
+// A delta is a sequence of operations that rebuild the target from the base:
+//   OP_COPY   offset u32, len u32   copy base[offset..offset + len]
+//   OP_INSERT len u32, bytes        append literal bytes
+const OP_COPY: u8 = 0x01;
+const OP_INSERT: u8 = 0x02;
+
+const BLOCK: usize = 16;
+
+/// Computes a delta turning `base` into `target`.
+///
+/// `base` is indexed in fixed-size blocks; `target` is scanned byte by byte
+/// for blocks that also occur in `base`, and each hit is extended in both
+/// directions before being emitted as a copy.
+pub(crate) fn compute(base: &[u8], target: &[u8]) -> Vec<u8> {
+    let mut index: HashMap<&[u8], usize> = HashMap::new();
+    for (i, block) in base.chunks_exact(BLOCK).enumerate() {
+        index.entry(block).or_insert(i * BLOCK);
+    }
+
+    let mut out = Vec::new();
+    let mut literal_start = 0;
+    let mut pos = 0;
+
+    while pos + BLOCK <= target.len() {
+        let Some(&base_pos) = index.get(&target[pos..pos + BLOCK]) else {
+            pos += 1;
+            continue;
+        };
+
+        let mut start = pos;
+        let mut base_start = base_pos;
+        while start > literal_start && base_start > 0 && target[start - 1] == base[base_start - 1] {
+            start -= 1;
+            base_start -= 1;
+        }
+
+        let mut end = pos + BLOCK;
+        let mut base_end = base_pos + BLOCK;
+        while end < target.len() && base_end < base.len() && target[end] == base[base_end] {
+            end += 1;
+            base_end += 1;
+        }
+
+        push_insert(&mut out, &target[literal_start..start]);
+        push_copy(&mut out, base_start, end - start);
+        literal_start = end;
+        pos = end;
+    }
+
+    push_insert(&mut out, &target[literal_start..]);
+    out
+}
+
+/// Rebuilds the target from `base`. Fails with `InvalidDelta` if the output
+/// would grow past `max_len` bytes or past `u32::MAX`, the largest payload a
+/// frame can hold.
+pub(crate) fn apply(
+    base: &[u8],
+    delta: &[u8],
+    max_len: usize,
+) -> Result<Vec<u8>, DeserializeError> {
+    let max_len = max_len.min(u32::MAX as usize);
+    let mut out = Vec::new();
+    let mut rest = delta;
+
+    while let Some((&op, tail)) = rest.split_first() {
+        rest = tail;
+        match op {
+            OP_COPY => {
+                let offset = take_u32(&mut rest)? as usize;
+                let len = take_u32(&mut rest)? as usize;
+                let end = offset
+                    .checked_add(len)
+                    .ok_or(DeserializeError::InvalidDelta)?;
+                let chunk = base
+                    .get(offset..end)
+                    .ok_or(DeserializeError::InvalidDelta)?;
+                reserve(&out, len, max_len)?;
+                out.extend_from_slice(chunk);
+            }
+            OP_INSERT => {
+                let len = take_u32(&mut rest)? as usize;
+                if rest.len() < len {
+                    return Err(DeserializeError::Truncated);
+                }
+                let (chunk, tail) = rest.split_at(len);
+                reserve(&out, len, max_len)?;
+                out.extend_from_slice(chunk);
+                rest = tail;
+            }
+            _ => return Err(DeserializeError::InvalidDelta),
+        }
+    }
+
+    Ok(out)
+}
+
+fn reserve(out: &[u8], len: usize, max_len: usize) -> Result<(), DeserializeError> {
+    match out.len().checked_add(len) {
+        Some(total) if total <= max_len => Ok(()),
+        _ => Err(DeserializeError::InvalidDelta),
+    }
+}
+
+fn push_copy(out: &mut Vec<u8>, offset: usize, len: usize) {
+    out.push(OP_COPY);
+    out.extend_from_slice(&(offset as u32).to_le_bytes());
+    out.extend_from_slice(&(len as u32).to_le_bytes());
+}
+
+fn push_insert(out: &mut Vec<u8>, bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+    out.push(OP_INSERT);
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn take_u32(rest: &mut &[u8]) -> Result<u32, DeserializeError> {
+    if rest.len() < 4 {
+        return Err(DeserializeError::Truncated);
+    }
+    let (bytes, tail) = rest.split_at(4);
+    *rest = tail;
+    Ok(u32::from_le_bytes(
+        bytes.try_into().map_err(|_| DeserializeError::Truncated)?,
+    ))
+}
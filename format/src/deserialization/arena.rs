@@ -0,0 +1,282 @@
+use alloc::string::ToString;
+
+use bumpalo::collections::Vec as BumpVec;
+use bumpalo::Bump;
+use uuid::Uuid;
+
+use crate::collections::HashMap;
+use crate::{constants, Metadata, Token, TokenId, TokenRef, TokenRefStrength, Value};
+
+use super::decoder::{decode_value, split_ref, split_token};
+use super::deserializer::{DeserializeError, Deserializer};
+use super::reader::ByteReader;
+
+/// A decoded value whose arrays and objects live in a `Bump` arena and whose
+/// strings borrow from the input bytes.
+///
+/// Object entries are kept in wire order. A key that appears more than once
+/// resolves to its last entry, as it does when decoding into `Value`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaValue<'a> {
+    Int(i64),
+    Float(f64),
+    String(&'a str),
+    Bool(bool),
+    Null,
+    Ref(ArenaRef<'a>),
+    Array(&'a [ArenaValue<'a>]),
+    Object(&'a [(&'a str, ArenaValue<'a>)]),
+}
+
+impl<'a> ArenaValue<'a> {
+    /// Looks up an object member.
+    pub fn get(&self, key: &str) -> Option<&ArenaValue<'a>> {
+        match self {
+            ArenaValue::Object(entries) => entries
+                .iter()
+                .rev()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Looks up an array element.
+    pub fn index(&self, index: usize) -> Option<&ArenaValue<'a>> {
+        match self {
+            ArenaValue::Array(items) => items.get(index),
+            _ => None,
+        }
+    }
+
+    /// Copies the value out of the arena.
+    pub fn to_value(&self) -> Value {
+        match *self {
+            ArenaValue::Int(v) => Value::Int(v),
+            ArenaValue::Float(v) => Value::Float(v),
+            ArenaValue::String(s) => Value::String(s.to_string()),
+            ArenaValue::Bool(v) => Value::Bool(v),
+            ArenaValue::Null => Value::Null,
+            ArenaValue::Ref(r) => Value::Ref(r.to_token_ref()),
+            ArenaValue::Array(items) => Value::Array(items.iter().map(Self::to_value).collect()),
+            ArenaValue::Object(entries) => {
+                let mut map = HashMap::with_capacity(entries.len());
+                for (key, value) in entries {
+                    map.insert(key.to_string(), value.to_value());
+                }
+                Value::Object(map)
+            }
+        }
+    }
+}
+
+/// A reference decoded into an arena; its fragment borrows from the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArenaRef<'a> {
+    pub id: TokenId,
+    pub strength: TokenRefStrength,
+    pub pin: Option<u32>,
+    pub fragment: Option<&'a str>,
+}
+
+impl ArenaRef<'_> {
+    pub fn to_token_ref(&self) -> TokenRef {
+        let mut r = match self.strength {
+            TokenRefStrength::Strong => TokenRef::strong(self.id),
+            TokenRefStrength::Weak => TokenRef::weak(self.id),
+        };
+        if let Some(pin) = self.pin {
+            r = r.pinned(pin);
+        }
+        if let Some(fragment) = self.fragment {
+            r = r.with_fragment(fragment);
+        }
+        r
+    }
+}
+
+/// A token decoded into an arena. See `ArenaValue`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaToken<'a> {
+    id: TokenId,
+    value: ArenaValue<'a>,
+    metadata: Metadata,
+    tombstone: bool,
+}
+
+impl<'a> ArenaToken<'a> {
+    pub fn id(&self) -> TokenId {
+        self.id
+    }
+
+    pub fn value(&self) -> &ArenaValue<'a> {
+        &self.value
+    }
+
+    /// The token's metadata, which is held outside the arena.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// The deletion time of a tombstone, whose value is `Null`.
+    pub fn deleted_at_ms(&self) -> Option<u64> {
+        self.tombstone.then_some(self.metadata.created_at_ms)
+    }
+
+    /// Copies the token out of the arena.
+    pub fn to_token(&self) -> Token {
+        if self.tombstone {
+            Token::tombstone_with_metadata(self.id, self.metadata.clone())
+        } else {
+            Token::new(self.id, self.value.to_value(), self.metadata.clone())
+        }
+    }
+}
+
+/// Decodes one token at a time into a reusable arena, which is reset at the
+/// start of every `decode`. The returned token borrows the decoder, so it must
+/// be dropped (or copied out with `to_token`) before the next call.
+#[derive(Debug, Default)]
+pub struct ArenaDecoder {
+    arena: Bump,
+}
+
+impl ArenaDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a decoder whose arena starts with `capacity` bytes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            arena: Bump::with_capacity(capacity),
+        }
+    }
+
+    pub fn decode<'a>(&'a mut self, bytes: &'a [u8]) -> Result<ArenaToken<'a>, DeserializeError> {
+        self.arena.reset();
+        Deserializer::new(bytes).deserialize_in(&self.arena)
+    }
+
+    /// Bytes currently held by the arena, including unused capacity.
+    pub fn allocated_bytes(&self) -> usize {
+        self.arena.allocated_bytes()
+    }
+}
+
+impl<'b> Deserializer<'b> {
+    /// Deserializes into `arena` instead of the global allocator. Strings
+    /// borrow from the input, so the result lives no longer than either.
+    pub fn deserialize_in<'a>(&self, arena: &'a Bump) -> Result<ArenaToken<'a>, DeserializeError>
+    where
+        'b: 'a,
+    {
+        let (header, payload) = self.verified_payload()?;
+
+        if header.type_marker == constants::TYPE_ENCRYPTED {
+            return Err(DeserializeError::Encrypted);
+        }
+        if header.type_marker == constants::TYPE_DELTA {
+            return Err(DeserializeError::Delta);
+        }
+
+        let (metadata, type_marker, payload) = split_token(header.type_marker, payload)?;
+        let value = match type_marker {
+            constants::TYPE_TOMBSTONE => ArenaValue::Null,
+            _ => decode_value_in(arena, type_marker, payload)?,
+        };
+        Ok(ArenaToken {
+            id: TokenId::from(Uuid::from_bytes(header.id)),
+            value,
+            metadata,
+            tombstone: type_marker == constants::TYPE_TOMBSTONE,
+        })
+    }
+}
+
+fn decode_value_in<'a>(
+    arena: &'a Bump,
+    type_marker: u8,
+    payload: &'a [u8],
+) -> Result<ArenaValue<'a>, DeserializeError> {
+    match type_marker {
+        constants::TYPE_STRING => core::str::from_utf8(payload)
+            .map(ArenaValue::String)
+            .map_err(|_| DeserializeError::InvalidUtf8),
+        constants::TYPE_REF => {
+            let parts = split_ref(payload)?;
+            Ok(ArenaValue::Ref(ArenaRef {
+                id: parts.id,
+                strength: parts.strength,
+                pin: parts.pin,
+                fragment: parts.fragment,
+            }))
+        }
+        constants::TYPE_ARRAY => decode_array_in(arena, payload),
+        constants::TYPE_OBJECT => decode_object_in(arena, payload),
+        // Remaining markers are scalars, which decode without allocating.
+        other => Ok(match decode_value(other, payload)? {
+            Value::Int(v) => ArenaValue::Int(v),
+            Value::Float(v) => ArenaValue::Float(v),
+            Value::Bool(v) => ArenaValue::Bool(v),
+            Value::Null => ArenaValue::Null,
+            Value::String(_) | Value::Ref(_) | Value::Array(_) | Value::Object(_) => {
+                return Err(DeserializeError::UnknownTypeMarker(other))
+            }
+        }),
+    }
+}
+
+fn decode_array_in<'a>(
+    arena: &'a Bump,
+    payload: &'a [u8],
+) -> Result<ArenaValue<'a>, DeserializeError> {
+    let mut reader = ByteReader::new(payload);
+    let count = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
+
+    let mut items = BumpVec::with_capacity_in(count.min(reader.remaining() / 5), arena);
+
+    for _ in 0..count {
+        let type_marker = reader.read_u8().ok_or(DeserializeError::Truncated)?;
+        let len = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
+        let item_payload = reader.read_bytes(len).ok_or(DeserializeError::Truncated)?;
+        items.push(decode_value_in(arena, type_marker, item_payload)?);
+    }
+
+    if reader.remaining() != 0 {
+        return Err(DeserializeError::TrailingBytes);
+    }
+
+    Ok(ArenaValue::Array(items.into_bump_slice()))
+}
+
+fn decode_object_in<'a>(
+    arena: &'a Bump,
+    payload: &'a [u8],
+) -> Result<ArenaValue<'a>, DeserializeError> {
+    let mut reader = ByteReader::new(payload);
+    let count = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
+
+    let mut entries = BumpVec::with_capacity_in(count.min(reader.remaining() / 9), arena);
+
+    for _ in 0..count {
+        let key_len = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
+        let key_bytes = reader
+            .read_bytes(key_len)
+            .ok_or(DeserializeError::Truncated)?;
+        let key = core::str::from_utf8(key_bytes).map_err(|_| DeserializeError::InvalidUtf8)?;
+
+        let type_marker = reader.read_u8().ok_or(DeserializeError::Truncated)?;
+        let val_len = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
+        let val_payload = reader
+            .read_bytes(val_len)
+            .ok_or(DeserializeError::Truncated)?;
+        entries.push((key, decode_value_in(arena, type_marker, val_payload)?));
+    }
+
+    if reader.remaining() != 0 {
+        return Err(DeserializeError::TrailingBytes);
+    }
+
+    Ok(ArenaValue::Object(entries.into_bump_slice()))
+}
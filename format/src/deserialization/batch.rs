@@ -0,0 +1,96 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crc32fast::Hasher;
+use uuid::Uuid;
+
+use crate::{constants, Token, TokenId};
+
+use super::decoder::decode_token;
+use super::deserializer::DeserializeError;
+use super::reader::ByteReader;
+
+/// Reads artifacts written by `BatchSerializer`.
+pub struct BatchDeserializer<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> BatchDeserializer<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn dictionary(&self) -> Result<Vec<String>, DeserializeError> {
+        let mut reader = self.verified_reader()?;
+        read_dictionary(&mut reader)
+    }
+
+    pub fn deserialize(&self) -> Result<Vec<Token>, DeserializeError> {
+        let mut reader = self.verified_reader()?;
+        let dictionary = read_dictionary(&mut reader)?;
+
+        let count = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
+        let mut tokens = Vec::with_capacity(count.min(reader.remaining() / 21));
+
+        for _ in 0..count {
+            let id_bytes: [u8; 16] = reader
+                .read_bytes(16)
+                .ok_or(DeserializeError::Truncated)?
+                .try_into()
+                .map_err(|_| DeserializeError::Truncated)?;
+            let type_marker = reader.read_u8().ok_or(DeserializeError::Truncated)?;
+            let len = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
+            let payload = reader.read_bytes(len).ok_or(DeserializeError::Truncated)?;
+
+            let id = TokenId::from(Uuid::from_bytes(id_bytes));
+            tokens.push(decode_token(id, type_marker, payload, Some(&dictionary))?);
+        }
+
+        if reader.remaining() != 0 {
+            return Err(DeserializeError::TrailingBytes);
+        }
+
+        Ok(tokens)
+    }
+
+    /// Checks magic, version and checksum, returning a reader positioned at
+    /// the dictionary and bounded before the checksum.
+    fn verified_reader(&self) -> Result<ByteReader<'a>, DeserializeError> {
+        let header_len = constants::BATCH_MAGIC.len() + 1;
+        if self.bytes.len() < header_len + 4 {
+            return Err(DeserializeError::Truncated);
+        }
+        if self.bytes[..4] != constants::BATCH_MAGIC {
+            return Err(DeserializeError::InvalidMagic);
+        }
+        if !constants::is_supported_version(self.bytes[4]) {
+            return Err(DeserializeError::UnsupportedVersion);
+        }
+
+        let checksum_offset = self.bytes.len() - 4;
+        let actual = u32::from_le_bytes(
+            self.bytes[checksum_offset..]
+                .try_into()
+                .map_err(|_| DeserializeError::Truncated)?,
+        );
+        let mut hasher = Hasher::new();
+        hasher.update(&self.bytes[..checksum_offset]);
+        if hasher.finalize() != actual {
+            return Err(DeserializeError::ChecksumMismatch);
+        }
+
+        Ok(ByteReader::new(&self.bytes[header_len..checksum_offset]))
+    }
+}
+
+fn read_dictionary(reader: &mut ByteReader<'_>) -> Result<Vec<String>, DeserializeError> {
+    let count = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
+    let mut entries = Vec::with_capacity(count.min(reader.remaining() / 4));
+    for _ in 0..count {
+        let len = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
+        let bytes = reader.read_bytes(len).ok_or(DeserializeError::Truncated)?;
+        let entry = core::str::from_utf8(bytes).map_err(|_| DeserializeError::InvalidUtf8)?;
+        entries.push(entry.to_string());
+    }
+    Ok(entries)
+}
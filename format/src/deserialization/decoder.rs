@@ -1,11 +1,103 @@
-use std::collections::HashMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 
-use crate::{constants, TokenId, TokenRef, TokenRefStrength, Value};
+use crate::collections::HashMap;
+use crate::{
+    constants, parallel, Metadata, MetadataFlags, Token, TokenId, TokenRef, TokenRefStrength, Value,
+};
 
 use super::deserializer::DeserializeError;
 use super::reader::ByteReader;
 
 pub fn decode_value(type_marker: u8, payload: &[u8]) -> Result<Value, DeserializeError> {
+    decode_value_with(type_marker, payload, None)
+}
+
+/// Decodes the top-level payload of token `id`: a value, or a tombstone's
+/// deletion time, either of which may be wrapped in a metadata section.
+pub(crate) fn decode_token(
+    id: TokenId,
+    type_marker: u8,
+    payload: &[u8],
+    dictionary: Option<&[String]>,
+) -> Result<Token, DeserializeError> {
+    let (metadata, type_marker, payload) = split_token(type_marker, payload)?;
+    Ok(match type_marker {
+        constants::TYPE_TOMBSTONE => Token::tombstone_with_metadata(id, metadata),
+        _ => Token::new(
+            id,
+            decode_value_with(type_marker, payload, dictionary)?,
+            metadata,
+        ),
+    })
+}
+
+/// Separates a token's metadata from its top-level payload, returning the
+/// value's type marker and payload. Tombstones are returned with their
+/// deletion time applied to the metadata and `TYPE_TOMBSTONE` as the marker.
+pub(crate) fn split_token(
+    type_marker: u8,
+    payload: &[u8],
+) -> Result<(Metadata, u8, &[u8]), DeserializeError> {
+    let (mut metadata, type_marker, payload) = match type_marker {
+        constants::TYPE_METADATA => split_metadata(payload)?,
+        _ => (Metadata::new(0, 0), type_marker, payload),
+    };
+    if type_marker == constants::TYPE_TOMBSTONE {
+        let bytes: [u8; 8] = payload
+            .try_into()
+            .map_err(|_| DeserializeError::InvalidLength)?;
+        metadata.created_at_ms = u64::from_le_bytes(bytes);
+        metadata.flags |= MetadataFlags::TOMBSTONE;
+    }
+    Ok((metadata, type_marker, payload))
+}
+
+fn split_metadata(payload: &[u8]) -> Result<(Metadata, u8, &[u8]), DeserializeError> {
+    let mut reader = ByteReader::new(payload);
+    let flags = reader
+        .read_u32_le()
+        .ok_or(DeserializeError::InvalidLength)?;
+    let created_at_ms = reader
+        .read_u64_le()
+        .ok_or(DeserializeError::InvalidLength)?;
+    let mut metadata = Metadata::new(created_at_ms, flags);
+
+    let count = reader
+        .read_u32_le()
+        .ok_or(DeserializeError::InvalidLength)?;
+    for _ in 0..count {
+        let key = read_str(&mut reader)?;
+        let value = read_str(&mut reader)?;
+        metadata
+            .attributes
+            .insert(key.to_string(), value.to_string());
+    }
+
+    let type_marker = reader.read_u8().ok_or(DeserializeError::InvalidLength)?;
+    let rest = reader
+        .read_bytes(reader.remaining())
+        .ok_or(DeserializeError::InvalidLength)?;
+    Ok((metadata, type_marker, rest))
+}
+
+fn read_str<'a>(reader: &mut ByteReader<'a>) -> Result<&'a str, DeserializeError> {
+    let len = reader
+        .read_u32_le()
+        .ok_or(DeserializeError::InvalidLength)? as usize;
+    let bytes = reader
+        .read_bytes(len)
+        .ok_or(DeserializeError::InvalidLength)?;
+    core::str::from_utf8(bytes).map_err(|_| DeserializeError::InvalidUtf8)
+}
+
+/// Decodes a value that may contain dictionary-indexed strings and object
+/// keys. Without a dictionary those markers are rejected as unknown.
+pub(crate) fn decode_value_with(
+    type_marker: u8,
+    payload: &[u8],
+    dictionary: Option<&[String]>,
+) -> Result<Value, DeserializeError> {
     match type_marker {
         constants::TYPE_NULL => {
             if !payload.is_empty() {
@@ -48,83 +140,195 @@ pub fn decode_value(type_marker: u8, payload: &[u8]) -> Result<Value, Deserializ
             Ok(Value::Float(v))
         }
         constants::TYPE_STRING => {
-            let s = std::str::from_utf8(payload).map_err(|_| DeserializeError::InvalidUtf8)?;
+            let s = core::str::from_utf8(payload).map_err(|_| DeserializeError::InvalidUtf8)?;
             Ok(Value::String(s.to_string()))
         }
-        constants::TYPE_REF => {
-            if payload.len() != 17 {
-                return Err(DeserializeError::InvalidLength);
-            }
-            let strength = match payload[0] {
-                0 => TokenRefStrength::Strong,
-                1 => TokenRefStrength::Weak,
-                _ => return Err(DeserializeError::InvalidReferenceStrength),
-            };
-            let id_bytes: [u8; 16] = payload[1..17]
+        constants::TYPE_REF => split_ref(payload).map(|parts| Value::Ref(parts.to_token_ref())),
+        constants::TYPE_ARRAY => decode_array(payload, dictionary),
+        constants::TYPE_OBJECT => decode_object(payload, dictionary),
+        constants::TYPE_STRING_DICT if dictionary.is_some() => {
+            let index: [u8; 4] = payload
                 .try_into()
                 .map_err(|_| DeserializeError::InvalidLength)?;
-            let id = TokenId::from(uuid::Uuid::from_bytes(id_bytes));
-            let r = match strength {
-                TokenRefStrength::Strong => TokenRef::strong(id),
-                TokenRefStrength::Weak => TokenRef::weak(id),
-            };
-            Ok(Value::Ref(r))
+            lookup(dictionary, u32::from_le_bytes(index)).map(|s| Value::String(s.to_string()))
+        }
+        constants::TYPE_OBJECT_DICT if dictionary.is_some() => {
+            decode_dictionary_object(payload, dictionary)
         }
-        constants::TYPE_ARRAY => decode_array(payload),
-        constants::TYPE_OBJECT => decode_object(payload),
         other => Err(DeserializeError::UnknownTypeMarker(other)),
     }
 }
 
-fn decode_array(payload: &[u8]) -> Result<Value, DeserializeError> {
+/// The fields of a `TYPE_REF` payload, with the fragment borrowed from it.
+pub(super) struct RefParts<'a> {
+    pub(super) id: TokenId,
+    pub(super) strength: TokenRefStrength,
+    pub(super) pin: Option<u32>,
+    pub(super) fragment: Option<&'a str>,
+}
+
+impl RefParts<'_> {
+    pub(super) fn to_token_ref(&self) -> TokenRef {
+        let mut r = match self.strength {
+            TokenRefStrength::Strong => TokenRef::strong(self.id),
+            TokenRefStrength::Weak => TokenRef::weak(self.id),
+        };
+        if let Some(pin) = self.pin {
+            r = r.pinned(pin);
+        }
+        if let Some(fragment) = self.fragment {
+            r = r.with_fragment(fragment);
+        }
+        r
+    }
+}
+
+pub(super) fn split_ref(payload: &[u8]) -> Result<RefParts<'_>, DeserializeError> {
+    let mut reader = ByteReader::new(payload);
+    let flags = reader.read_u8().ok_or(DeserializeError::InvalidLength)?;
+    let known = constants::REF_WEAK | constants::REF_PINNED | constants::REF_FRAGMENT;
+    if flags & !known != 0 {
+        return Err(DeserializeError::InvalidReferenceStrength);
+    }
+
+    let id_bytes: [u8; 16] = reader
+        .read_bytes(16)
+        .ok_or(DeserializeError::InvalidLength)?
+        .try_into()
+        .map_err(|_| DeserializeError::InvalidLength)?;
+    let pin = if flags & constants::REF_PINNED != 0 {
+        Some(
+            reader
+                .read_u32_le()
+                .ok_or(DeserializeError::InvalidLength)?,
+        )
+    } else {
+        None
+    };
+
+    let rest = reader
+        .read_bytes(reader.remaining())
+        .ok_or(DeserializeError::InvalidLength)?;
+    let has_fragment = flags & constants::REF_FRAGMENT != 0;
+    if rest.is_empty() == has_fragment {
+        return Err(DeserializeError::InvalidLength);
+    }
+    let fragment = if has_fragment {
+        Some(core::str::from_utf8(rest).map_err(|_| DeserializeError::InvalidUtf8)?)
+    } else {
+        None
+    };
+
+    Ok(RefParts {
+        id: TokenId::from(uuid::Uuid::from_bytes(id_bytes)),
+        strength: if flags & constants::REF_WEAK != 0 {
+            TokenRefStrength::Weak
+        } else {
+            TokenRefStrength::Strong
+        },
+        pin,
+        fragment,
+    })
+}
+
+fn decode_array(payload: &[u8], dictionary: Option<&[String]>) -> Result<Value, DeserializeError> {
     let mut reader = ByteReader::new(payload);
     let count = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
 
-    let mut items = Vec::with_capacity(count);
+    let mut children = Vec::with_capacity(count.min(reader.remaining() / 5));
 
     for _ in 0..count {
         let type_marker = reader.read_u8().ok_or(DeserializeError::Truncated)?;
         let len = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
         let item_payload = reader.read_bytes(len).ok_or(DeserializeError::Truncated)?;
-        let value = decode_value(type_marker, item_payload)?;
-        items.push(value);
+        children.push((type_marker, item_payload));
     }
 
     if reader.remaining() != 0 {
         return Err(DeserializeError::TrailingBytes);
     }
 
+    let items = parallel::try_map(&children, |&(type_marker, item_payload)| {
+        decode_value_with(type_marker, item_payload, dictionary)
+    })?;
     Ok(Value::Array(items))
 }
 
-fn decode_object(payload: &[u8]) -> Result<Value, DeserializeError> {
+fn decode_object(payload: &[u8], dictionary: Option<&[String]>) -> Result<Value, DeserializeError> {
     let mut reader = ByteReader::new(payload);
     let count = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
 
-    let mut map = HashMap::with_capacity(count);
+    let mut entries = Vec::with_capacity(count.min(reader.remaining() / 9));
 
     for _ in 0..count {
         let key_len = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
         let key_bytes = reader
             .read_bytes(key_len)
             .ok_or(DeserializeError::Truncated)?;
-        let key = std::str::from_utf8(key_bytes)
-            .map_err(|_| DeserializeError::InvalidUtf8)?
-            .to_string();
+        let key = core::str::from_utf8(key_bytes).map_err(|_| DeserializeError::InvalidUtf8)?;
 
         let type_marker = reader.read_u8().ok_or(DeserializeError::Truncated)?;
         let val_len = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
         let val_payload = reader
             .read_bytes(val_len)
             .ok_or(DeserializeError::Truncated)?;
-        let value = decode_value(type_marker, val_payload)?;
+        entries.push((key, type_marker, val_payload));
+    }
 
-        map.insert(key, value);
+    if reader.remaining() != 0 {
+        return Err(DeserializeError::TrailingBytes);
+    }
+
+    decode_entries(&entries, dictionary)
+}
+
+fn decode_dictionary_object(
+    payload: &[u8],
+    dictionary: Option<&[String]>,
+) -> Result<Value, DeserializeError> {
+    let mut reader = ByteReader::new(payload);
+    let count = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
+
+    let mut entries = Vec::with_capacity(count.min(reader.remaining() / 9));
+
+    for _ in 0..count {
+        let key_index = reader.read_u32_le().ok_or(DeserializeError::Truncated)?;
+        let key = lookup(dictionary, key_index)?;
+
+        let type_marker = reader.read_u8().ok_or(DeserializeError::Truncated)?;
+        let val_len = reader.read_u32_le().ok_or(DeserializeError::Truncated)? as usize;
+        let val_payload = reader
+            .read_bytes(val_len)
+            .ok_or(DeserializeError::Truncated)?;
+        entries.push((key, type_marker, val_payload));
     }
 
     if reader.remaining() != 0 {
         return Err(DeserializeError::TrailingBytes);
     }
 
+    decode_entries(&entries, dictionary)
+}
+
+/// Decodes framed object entries. Later duplicates of a key win.
+fn decode_entries(
+    entries: &[(&str, u8, &[u8])],
+    dictionary: Option<&[String]>,
+) -> Result<Value, DeserializeError> {
+    let values = parallel::try_map(entries, |&(_, type_marker, val_payload)| {
+        decode_value_with(type_marker, val_payload, dictionary)
+    })?;
+
+    let mut map = HashMap::with_capacity(entries.len());
+    for (&(key, _, _), value) in entries.iter().zip(values) {
+        map.insert(key.to_string(), value);
+    }
     Ok(Value::Object(map))
 }
+
+fn lookup(dictionary: Option<&[String]>, index: u32) -> Result<&str, DeserializeError> {
+    dictionary
+        .and_then(|d| d.get(index as usize))
+        .map(String::as_str)
+        .ok_or(DeserializeError::InvalidDictionaryIndex(index))
+}
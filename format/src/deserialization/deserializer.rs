@@ -1,11 +1,14 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
 use crc32fast::Hasher;
-use std::ops::Range;
 use thiserror::Error;
 use uuid::Uuid;
 
-use crate::{constants, Metadata, Token, TokenId, Value};
+use crate::{constants, delta, fec, Metadata, Path, Serializer, Token, TokenId, Value};
 
-use super::decoder::decode_value;
+use super::decoder::{decode_token, split_token};
+use super::salvage::{salvage_value, Damage, Salvage};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TokenHeader {
@@ -20,6 +23,8 @@ pub struct TokenLayout {
     pub header: TokenHeader,
     pub payload_range: Range<usize>,
     pub checksum_range: Range<usize>,
+    /// Reed-Solomon parity section following the checksum, if present.
+    pub parity_range: Option<Range<usize>>,
 }
 
 #[derive(Debug, Error, PartialEq, Eq)]
@@ -47,15 +52,60 @@ pub enum DeserializeError {
 
     #[error("invalid reference strength")]
     InvalidReferenceStrength,
+
+    #[error("payload is encrypted")]
+    Encrypted,
+
+    #[error("no key available for key id {0}")]
+    MissingKey(u32),
+
+    #[error("payload decryption failed")]
+    DecryptionFailed,
+
+    #[error("not a token batch")]
+    InvalidMagic,
+
+    #[error("dictionary index {0} out of range")]
+    InvalidDictionaryIndex(u32),
+
+    #[error("payload is a delta against another token")]
+    Delta,
+
+    #[error("delta base token {0:?} not found")]
+    BaseNotFound(TokenId),
+
+    #[error("delta base checksum mismatch")]
+    BaseChecksumMismatch,
+
+    #[error("invalid delta instructions")]
+    InvalidDelta,
+
+    #[error("frame of {0} bytes exceeds the maximum frame length")]
+    FrameTooLarge(usize),
+
+    #[error("too many corrupted bytes to repair")]
+    Unrepairable,
 }
 
 pub struct Deserializer<'a> {
     bytes: &'a [u8],
+    max_delta_len: usize,
 }
 
 impl<'a> Deserializer<'a> {
     pub fn new(bytes: &'a [u8]) -> Self {
-        Self { bytes }
+        Self {
+            bytes,
+            max_delta_len: u32::MAX as usize,
+        }
+    }
+
+    /// Caps the payload a delta token may expand to, which otherwise is only
+    /// limited by the largest payload a frame can hold (`u32::MAX` bytes).
+    /// Larger deltas fail with `InvalidDelta`.
+    pub fn with_max_delta_len(mut self, max_len: usize) -> Self {
+        self.max_delta_len = max_len;
+        self
     }
 
     pub fn header(&self) -> Result<TokenHeader, DeserializeError> {
@@ -89,42 +139,272 @@ impl<'a> Deserializer<'a> {
             return Err(DeserializeError::UnsupportedVersion);
         }
 
-        let checksum_start = self
-            .bytes
-            .len()
-            .checked_sub(4)
-            .ok_or(DeserializeError::Truncated)?;
-
         let payload_start: usize = 22;
         let payload_len_usize = header.payload_len as usize;
         let payload_end = payload_start
             .checked_add(payload_len_usize)
             .ok_or(DeserializeError::Truncated)?;
+        let checksum_end = payload_end
+            .checked_add(4)
+            .ok_or(DeserializeError::Truncated)?;
 
-        if payload_end > checksum_start {
+        if checksum_end > self.bytes.len() {
             return Err(DeserializeError::Truncated);
         }
 
-        if payload_end != checksum_start {
-            return Err(DeserializeError::TrailingBytes);
-        }
+        let parity_range = if checksum_end == self.bytes.len() {
+            None
+        } else {
+            match fec::locate(self.bytes) {
+                Some((data_len, _)) if data_len == checksum_end => {
+                    Some(checksum_end..self.bytes.len())
+                }
+                _ => return Err(DeserializeError::TrailingBytes),
+            }
+        };
 
         Ok(TokenLayout {
             header,
             payload_range: payload_start..payload_end,
-            checksum_range: checksum_start..self.bytes.len(),
+            checksum_range: payload_end..checksum_end,
+            parity_range,
         })
     }
 
     pub fn deserialize(&self) -> Result<Token, DeserializeError> {
+        let layout = self.verified_layout()?;
+        let header = layout.header;
+
+        if header.type_marker == constants::TYPE_ENCRYPTED {
+            return Err(DeserializeError::Encrypted);
+        }
+        if header.type_marker == constants::TYPE_DELTA {
+            return Err(DeserializeError::Delta);
+        }
+
+        let payload = &self.bytes[layout.payload_range];
+        let id = TokenId::from(Uuid::from_bytes(header.id));
+        decode_token(id, header.type_marker, payload, None)
+    }
+
+    /// Returns the key id an encrypted token was sealed with, or `None` for a
+    /// plaintext token. Only the header and envelope prefix are read.
+    #[cfg(feature = "encryption")]
+    pub fn key_id(&self) -> Result<Option<crate::encryption::KeyId>, DeserializeError> {
+        let layout = self.layout()?;
+        if layout.header.type_marker != constants::TYPE_ENCRYPTED {
+            return Ok(None);
+        }
+        crate::encryption::key_id(&self.bytes[layout.payload_range]).map(Some)
+    }
+
+    /// Deserializes a token that may be encrypted, fetching the key from
+    /// `provider` by the key id stored in the token. Plaintext tokens are
+    /// decoded as with `deserialize`.
+    #[cfg(feature = "encryption")]
+    pub fn deserialize_decrypted<P>(&self, provider: &P) -> Result<Token, DeserializeError>
+    where
+        P: crate::encryption::KeyProvider + ?Sized,
+    {
+        let layout = self.verified_layout()?;
+        let header = layout.header;
+
+        if header.type_marker != constants::TYPE_ENCRYPTED {
+            return self.deserialize();
+        }
+
+        let envelope = &self.bytes[layout.payload_range];
+        let (type_marker, plaintext) =
+            crate::encryption::open(provider, &self.bytes[..18], envelope)?;
+        let id = TokenId::from(Uuid::from_bytes(header.id));
+        decode_token(id, type_marker, &plaintext, None)
+    }
+
+    /// Returns the base token id and checksum a delta token was encoded
+    /// against, or `None` for a self-contained token.
+    pub fn delta_base(&self) -> Result<Option<(TokenId, u32)>, DeserializeError> {
+        let layout = self.layout()?;
+        if layout.header.type_marker != constants::TYPE_DELTA {
+            return Ok(None);
+        }
+        let (base_id, base_checksum, _, _) = split_delta(&self.bytes[layout.payload_range])?;
+        Ok(Some((base_id, base_checksum)))
+    }
+
+    /// Returns the deletion time if the token is a tombstone, or `None` for
+    /// any other token. Only the header and the tombstone's payload are read.
+    /// Encrypted and delta tokens are refused, as with `metadata`.
+    pub fn tombstone(&self) -> Result<Option<u64>, DeserializeError> {
+        let (metadata, value_marker) = self.metadata_section()?;
+        Ok((value_marker == constants::TYPE_TOMBSTONE).then_some(metadata.created_at_ms))
+    }
+
+    /// Returns the token's metadata without decoding its value. Only the
+    /// header and metadata section are read. Encrypted and delta tokens keep
+    /// their metadata inside the payload and are refused.
+    pub fn metadata(&self) -> Result<Metadata, DeserializeError> {
+        self.metadata_section().map(|(metadata, _)| metadata)
+    }
+
+    /// Returns the type marker of the token's value, which differs from the
+    /// header's when a `TYPE_METADATA` section wraps the value. Encrypted and
+    /// delta tokens are refused, as with `metadata`.
+    pub fn value_marker(&self) -> Result<u8, DeserializeError> {
+        self.metadata_section()
+            .map(|(_, value_marker)| value_marker)
+    }
+
+    fn metadata_section(&self) -> Result<(Metadata, u8), DeserializeError> {
         let layout = self.layout()?;
+        match layout.header.type_marker {
+            constants::TYPE_ENCRYPTED => Err(DeserializeError::Encrypted),
+            constants::TYPE_DELTA => Err(DeserializeError::Delta),
+            type_marker => split_token(type_marker, &self.bytes[layout.payload_range])
+                .map(|(metadata, value_marker, _)| (metadata, value_marker)),
+        }
+    }
+
+    /// Deserializes a delta token, taking its base from `registry`.
+    /// Self-contained tokens are decoded as with `deserialize`.
+    #[cfg(feature = "std")]
+    pub fn deserialize_with_base(
+        &self,
+        registry: &crate::TokenRegistry,
+    ) -> Result<Token, DeserializeError> {
+        match self.delta_base()? {
+            Some((base_id, _)) => {
+                let base = registry
+                    .get(base_id)
+                    .ok_or(DeserializeError::BaseNotFound(base_id))?;
+                self.deserialize_against(&base)
+            }
+            None => self.deserialize(),
+        }
+    }
+
+    /// Deserializes a delta token against an explicitly supplied base. The
+    /// base must be the exact version the delta was encoded against.
+    pub fn deserialize_against(&self, base: &Token) -> Result<Token, DeserializeError> {
+        let layout = self.verified_layout()?;
         let header = layout.header;
+
+        if header.type_marker != constants::TYPE_DELTA {
+            return self.deserialize();
+        }
+
+        let (base_id, base_checksum, type_marker, instructions) =
+            split_delta(&self.bytes[layout.payload_range])?;
+        if base.id() != base_id {
+            return Err(DeserializeError::BaseNotFound(base_id));
+        }
+
+        let base_frame = Serializer::new()
+            .serialize(base)
+            .map_err(|_| DeserializeError::InvalidLength)?;
+        let (base_payload, base_trailer) = base_frame.split_at(base_frame.len() - 4);
+        if base_trailer != base_checksum.to_le_bytes() {
+            return Err(DeserializeError::BaseChecksumMismatch);
+        }
+
+        let payload = delta::apply(&base_payload[22..], instructions, self.max_delta_len)?;
+        let id = TokenId::from(Uuid::from_bytes(header.id));
+        decode_token(id, type_marker, &payload, None)
+    }
+
+    /// Deserializes a token written with `Serializer::serialize_with_parity`,
+    /// using its parity section to repair corrupted bytes. Returns the token
+    /// and the number of bytes corrected. A token that decodes as it is,
+    /// with or without parity, is returned with nothing corrected.
+    pub fn deserialize_repaired(&self) -> Result<(Token, usize), DeserializeError> {
+        let plain = self.deserialize();
+        if plain.is_ok() {
+            return plain.map(|token| (token, 0));
+        }
+        let Some((data_len, nsym)) = fec::locate(self.bytes) else {
+            return plain.map(|token| (token, 0));
+        };
+
+        let mut repaired = self.bytes.to_vec();
+        let corrected =
+            fec::correct(&mut repaired, data_len, nsym).ok_or(DeserializeError::Unrepairable)?;
+        let token = Deserializer::new(&repaired).deserialize()?;
+        Ok((token, corrected))
+    }
+
+    /// Recovers what it can from a damaged token for forensic use.
+    ///
+    /// The checksum is reported rather than enforced, and decoding continues
+    /// past unreadable subtrees, which are listed in `Salvage::damaged`. If the
+    /// header's payload length disagrees with the input length, everything
+    /// between header and trailer is taken as the payload. Only the header
+    /// itself must be intact, and encrypted or delta tokens are refused.
+    pub fn salvage(&self) -> Result<Salvage, DeserializeError> {
+        let header = self.header()?;
+        if !constants::is_supported_version(header.version) {
+            return Err(DeserializeError::UnsupportedVersion);
+        }
+        match header.type_marker {
+            constants::TYPE_ENCRYPTED => return Err(DeserializeError::Encrypted),
+            constants::TYPE_DELTA => return Err(DeserializeError::Delta),
+            _ => {}
+        }
+
+        let mut damaged = Vec::new();
+        let (payload, checksum_range) = match self.layout() {
+            Ok(layout) => (&self.bytes[layout.payload_range], layout.checksum_range),
+            Err(error) => {
+                damaged.push(Damage {
+                    path: Path::root(),
+                    error,
+                });
+                let checksum_start = self.bytes.len() - 4;
+                (
+                    &self.bytes[22..checksum_start],
+                    checksum_start..self.bytes.len(),
+                )
+            }
+        };
+        let checksum_valid =
+            crc32(&self.bytes[..checksum_range.start]).to_le_bytes() == self.bytes[checksum_range];
+
+        let id = TokenId::from(Uuid::from_bytes(header.id));
+        let token = match split_token(header.type_marker, payload) {
+            Ok((metadata, constants::TYPE_TOMBSTONE, _)) => {
+                Token::tombstone_with_metadata(id, metadata)
+            }
+            Ok((metadata, type_marker, payload)) => {
+                let value = salvage_value(type_marker, payload, &mut Path::root(), &mut damaged);
+                Token::new(id, value, metadata)
+            }
+            Err(error) => {
+                damaged.push(Damage {
+                    path: Path::root(),
+                    error,
+                });
+                Token::new(id, Value::Null, Metadata::new(0, 0))
+            }
+        };
+        Ok(Salvage {
+            token,
+            checksum_valid,
+            damaged,
+        })
+    }
+
+    /// Returns the header and payload of a checksum-verified frame, borrowing
+    /// from the input rather than from `self`.
+    #[cfg(feature = "arena")]
+    pub(super) fn verified_payload(&self) -> Result<(TokenHeader, &'a [u8]), DeserializeError> {
+        let layout = self.verified_layout()?;
+        Ok((layout.header, &self.bytes[layout.payload_range]))
+    }
+
+    fn verified_layout(&self) -> Result<TokenLayout, DeserializeError> {
+        let layout = self.layout()?;
         let checksum_offset = layout.checksum_range.start;
-        let payload_start = layout.payload_range.start;
-        let payload_end = layout.payload_range.end;
 
         let actual = u32::from_le_bytes(
-            self.bytes[checksum_offset..]
+            self.bytes[layout.checksum_range.clone()]
                 .try_into()
                 .map_err(|_| DeserializeError::Truncated)?,
         );
@@ -134,12 +414,29 @@ impl<'a> Deserializer<'a> {
             return Err(DeserializeError::ChecksumMismatch);
         }
 
-        let payload = &self.bytes[payload_start..payload_end];
-        let value: Value = decode_value(header.type_marker, payload)?;
+        Ok(layout)
+    }
+}
 
-        let id = TokenId::from(Uuid::from_bytes(header.id));
-        Ok(Token::new(id, value, Metadata::new(0, 0)))
+// Delta payload: [base id 16][base checksum u32][target type u8][instructions]
+fn split_delta(payload: &[u8]) -> Result<(TokenId, u32, u8, &[u8]), DeserializeError> {
+    if payload.len() < 16 + 4 + 1 {
+        return Err(DeserializeError::Truncated);
     }
+    let id: [u8; 16] = payload[..16]
+        .try_into()
+        .map_err(|_| DeserializeError::Truncated)?;
+    let checksum = u32::from_le_bytes(
+        payload[16..20]
+            .try_into()
+            .map_err(|_| DeserializeError::Truncated)?,
+    );
+    Ok((
+        TokenId::from(Uuid::from_bytes(id)),
+        checksum,
+        payload[20],
+        &payload[21..],
+    ))
 }
 
 fn crc32(bytes: &[u8]) -> u32 {
@@ -1,5 +1,17 @@
+#[cfg(feature = "arena")]
+mod arena;
+mod batch;
 mod decoder;
 mod deserializer;
 mod reader;
+mod salvage;
+mod stream;
 
+#[cfg(feature = "arena")]
+pub use arena::{ArenaDecoder, ArenaRef, ArenaToken, ArenaValue};
+pub use batch::BatchDeserializer;
 pub use deserializer::{DeserializeError, Deserializer, TokenHeader, TokenLayout};
+pub use salvage::{Damage, Salvage};
+#[cfg(feature = "tokio")]
+pub(crate) use stream::{frame_len, HEADER_LEN};
+pub use stream::{Decoded, TokenDecoder};
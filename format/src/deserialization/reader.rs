@@ -27,6 +27,11 @@ impl<'a> ByteReader<'a> {
         Some(u32::from_le_bytes(bytes.try_into().ok()?))
     }
 
+    pub fn read_u64_le(&mut self) -> Option<u64> {
+        let bytes = self.read_bytes(8)?;
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+
     pub fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
         let end = self.pos.checked_add(len)?;
         if end > self.bytes.len() {
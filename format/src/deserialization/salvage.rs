@@ -0,0 +1,136 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::collections::HashMap;
+use crate::{constants, Path, Token, Value};
+
+use super::decoder::decode_value;
+use super::deserializer::DeserializeError;
+use super::reader::ByteReader;
+
+/// Outcome of `Deserializer::salvage`.
+#[derive(Debug, PartialEq)]
+pub struct Salvage {
+    /// The recovered token. Unreadable values are replaced by `Value::Null`;
+    /// object entries whose key is unreadable are dropped.
+    pub token: Token,
+
+    /// Whether the trailer checksum matched the frame.
+    pub checksum_valid: bool,
+
+    /// Subtrees that could not be decoded, in encounter order.
+    pub damaged: Vec<Damage>,
+}
+
+impl Salvage {
+    /// True when every byte was accounted for and the checksum matched.
+    pub fn is_intact(&self) -> bool {
+        self.checksum_valid && self.damaged.is_empty()
+    }
+}
+
+/// An unreadable part of a salvaged value.
+///
+/// For a leaf, `path` points at the `Null` placeholder. For a container whose
+/// framing broke part way, it points at the container, which keeps the
+/// entries read before the break.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Damage {
+    pub path: Path,
+    pub error: DeserializeError,
+}
+
+pub(crate) fn salvage_value(
+    type_marker: u8,
+    payload: &[u8],
+    path: &mut Path,
+    damaged: &mut Vec<Damage>,
+) -> Value {
+    match type_marker {
+        constants::TYPE_ARRAY => salvage_array(payload, path, damaged),
+        constants::TYPE_OBJECT => salvage_object(payload, path, damaged),
+        _ => match decode_value(type_marker, payload) {
+            Ok(value) => value,
+            Err(error) => {
+                report(damaged, path, error);
+                Value::Null
+            }
+        },
+    }
+}
+
+fn salvage_array(payload: &[u8], path: &mut Path, damaged: &mut Vec<Damage>) -> Value {
+    let mut reader = ByteReader::new(payload);
+    let Some(count) = reader.read_u32_le() else {
+        report(damaged, path, DeserializeError::Truncated);
+        return Value::Array(Vec::new());
+    };
+
+    let mut items = Vec::new();
+    for index in 0..count as usize {
+        let Some((type_marker, item_payload)) = read_item(&mut reader) else {
+            report(damaged, path, DeserializeError::Truncated);
+            return Value::Array(items);
+        };
+        path.push_index(index);
+        items.push(salvage_value(type_marker, item_payload, path, damaged));
+        path.pop();
+    }
+
+    if reader.remaining() != 0 {
+        report(damaged, path, DeserializeError::TrailingBytes);
+    }
+    Value::Array(items)
+}
+
+fn salvage_object(payload: &[u8], path: &mut Path, damaged: &mut Vec<Damage>) -> Value {
+    let mut reader = ByteReader::new(payload);
+    let Some(count) = reader.read_u32_le() else {
+        report(damaged, path, DeserializeError::Truncated);
+        return Value::Object(HashMap::new());
+    };
+
+    let mut map = HashMap::new();
+    for _ in 0..count {
+        let entry = reader
+            .read_u32_le()
+            .and_then(|len| reader.read_bytes(len as usize))
+            .and_then(|key| Some((key, read_item(&mut reader)?)));
+        let Some((key_bytes, (type_marker, val_payload))) = entry else {
+            report(damaged, path, DeserializeError::Truncated);
+            return Value::Object(map);
+        };
+
+        // The entry's framing is intact even if its key is not, so decoding
+        // can continue with the next entry either way.
+        let Ok(key) = core::str::from_utf8(key_bytes) else {
+            path.push_key(String::from_utf8_lossy(key_bytes));
+            report(damaged, path, DeserializeError::InvalidUtf8);
+            path.pop();
+            continue;
+        };
+
+        path.push_key(key);
+        let value = salvage_value(type_marker, val_payload, path, damaged);
+        path.pop();
+        map.insert(String::from(key), value);
+    }
+
+    if reader.remaining() != 0 {
+        report(damaged, path, DeserializeError::TrailingBytes);
+    }
+    Value::Object(map)
+}
+
+fn read_item<'a>(reader: &mut ByteReader<'a>) -> Option<(u8, &'a [u8])> {
+    let type_marker = reader.read_u8()?;
+    let len = reader.read_u32_le()? as usize;
+    Some((type_marker, reader.read_bytes(len)?))
+}
+
+fn report(damaged: &mut Vec<Damage>, path: &Path, error: DeserializeError) {
+    damaged.push(Damage {
+        path: path.clone(),
+        error,
+    });
+}
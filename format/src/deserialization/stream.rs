@@ -0,0 +1,137 @@
+use alloc::vec::Vec;
+
+use crate::{constants, Token};
+
+use super::deserializer::{DeserializeError, Deserializer};
+
+pub(crate) const HEADER_LEN: usize = 1 + 16 + 1 + 4;
+const CHECKSUM_LEN: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decoded {
+    /// A complete token was decoded; unread input may hold further frames.
+    Token(Token),
+
+    /// The input was exhausted before the current frame completed. Carries
+    /// the number of bytes still missing, as far as is known: until the
+    /// header is complete only the header's remainder is counted.
+    NeedMore(usize),
+}
+
+/// Push-style decoder for a stream of serialized tokens arriving in chunks.
+///
+/// The decoder performs no I/O. It copies at most one frame into its buffer,
+/// so memory use is bounded by `max_frame_len`. Frames announcing a larger
+/// payload are rejected as soon as their header is seen. Only the header is
+/// discarded then, so the stream cannot be resynchronized, but the decoder
+/// can be reused for a new stream.
+#[derive(Debug)]
+pub struct TokenDecoder {
+    buffer: Vec<u8>,
+    max_frame_len: usize,
+}
+
+impl Default for TokenDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenDecoder {
+    pub const DEFAULT_MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+    pub fn new() -> Self {
+        Self::with_max_frame_len(Self::DEFAULT_MAX_FRAME_LEN)
+    }
+
+    pub fn with_max_frame_len(max_frame_len: usize) -> Self {
+        Self {
+            buffer: Vec::new(),
+            max_frame_len,
+        }
+    }
+
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    /// Number of bytes of the current, incomplete frame held by the decoder.
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Consumes bytes from the front of `input` until a token is complete or
+    /// `input` is empty. Call repeatedly while it returns `Decoded::Token` to
+    /// drain a chunk holding several frames.
+    ///
+    /// A frame that fails to decode is discarded whole before the error is
+    /// returned, so decoding can continue with the next frame. A frame whose
+    /// header is rejected has only its header discarded.
+    pub fn decode(&mut self, input: &mut &[u8]) -> Result<Decoded, DeserializeError> {
+        if self.buffer.len() < HEADER_LEN {
+            let missing = HEADER_LEN - self.buffer.len();
+            if !self.fill(input, missing) {
+                return Ok(Decoded::NeedMore(HEADER_LEN - self.buffer.len()));
+            }
+        }
+
+        let frame_len = match frame_len(&self.buffer, self.max_frame_len) {
+            Ok(Some(len)) => len,
+            Ok(None) => return Ok(Decoded::NeedMore(HEADER_LEN - self.buffer.len())),
+            Err(err) => {
+                self.buffer.clear();
+                return Err(err);
+            }
+        };
+
+        if !self.fill(input, frame_len - self.buffer.len()) {
+            return Ok(Decoded::NeedMore(frame_len - self.buffer.len()));
+        }
+
+        let result = Deserializer::new(&self.buffer).deserialize();
+        self.buffer.clear();
+        result.map(Decoded::Token)
+    }
+
+    /// Moves up to `wanted` bytes from `input` into the buffer, returning
+    /// whether all of them were available.
+    fn fill(&mut self, input: &mut &[u8], wanted: usize) -> bool {
+        let take = wanted.min(input.len());
+        let (head, tail) = input.split_at(take);
+        self.buffer.extend_from_slice(head);
+        *input = tail;
+        take == wanted
+    }
+}
+
+/// Length of the frame starting at `bytes`, read from its header, or `None`
+/// when the header is not complete yet.
+pub(crate) fn frame_len(
+    bytes: &[u8],
+    max_frame_len: usize,
+) -> Result<Option<usize>, DeserializeError> {
+    if bytes.len() < HEADER_LEN {
+        return Ok(None);
+    }
+
+    if !constants::is_supported_version(bytes[0]) {
+        return Err(DeserializeError::UnsupportedVersion);
+    }
+
+    let payload_len = u32::from_le_bytes(
+        bytes[18..22]
+            .try_into()
+            .map_err(|_| DeserializeError::Truncated)?,
+    ) as usize;
+    // On 32-bit targets the sum can overflow, which is as good as too large.
+    let len = HEADER_LEN
+        .checked_add(payload_len)
+        .and_then(|len| len.checked_add(CHECKSUM_LEN))
+        .ok_or(DeserializeError::FrameTooLarge(usize::MAX))?;
+
+    if len > max_frame_len {
+        return Err(DeserializeError::FrameTooLarge(len));
+    }
+
+    Ok(Some(len))
+}
@@ -0,0 +1,99 @@
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use crate::deserialization::DeserializeError;
+use crate::serialization::SerializeError;
+
+use super::{KeyId, KeyProvider};
+
+pub(crate) const NONCE_LEN: usize = 12;
+pub(crate) const TAG_LEN: usize = 16;
+
+// Envelope payload: inner type marker, key id, nonce, ciphertext || tag.
+const PREFIX_LEN: usize = 1 + 4 + NONCE_LEN;
+
+/// Encrypts `plaintext` into an envelope payload.
+///
+/// `header` is the frame prefix (version, id, outer type marker) and is bound
+/// to the ciphertext as associated data, together with the inner type marker
+/// and key id, so none of them can be swapped without failing authentication.
+pub(crate) fn seal<P: KeyProvider + ?Sized>(
+    provider: &P,
+    header: &[u8],
+    inner_type_marker: u8,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, SerializeError> {
+    let key_id = provider.current_key_id();
+    let key = provider
+        .key(key_id)
+        .ok_or(SerializeError::MissingKey(key_id))?;
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let aad = associated_data(header, inner_type_marker, key_id);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| SerializeError::EncryptionFailed)?;
+
+    let mut out = Vec::with_capacity(PREFIX_LEN + ciphertext.len());
+    out.push(inner_type_marker);
+    out.extend_from_slice(&key_id.to_le_bytes());
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Returns the key id recorded in an envelope payload.
+pub(crate) fn key_id(envelope: &[u8]) -> Result<KeyId, DeserializeError> {
+    if envelope.len() < PREFIX_LEN + TAG_LEN {
+        return Err(DeserializeError::InvalidLength);
+    }
+    let bytes: [u8; 4] = envelope[1..5]
+        .try_into()
+        .map_err(|_| DeserializeError::InvalidLength)?;
+    Ok(KeyId::from_le_bytes(bytes))
+}
+
+/// Decrypts an envelope payload, returning the inner type marker and plaintext.
+pub(crate) fn open<P: KeyProvider + ?Sized>(
+    provider: &P,
+    header: &[u8],
+    envelope: &[u8],
+) -> Result<(u8, Vec<u8>), DeserializeError> {
+    let key_id = key_id(envelope)?;
+    let inner_type_marker = envelope[0];
+    let nonce = Nonce::from_slice(&envelope[5..PREFIX_LEN]);
+    let ciphertext = &envelope[PREFIX_LEN..];
+
+    let key = provider
+        .key(key_id)
+        .ok_or(DeserializeError::MissingKey(key_id))?;
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+
+    let aad = associated_data(header, inner_type_marker, key_id);
+    let plaintext = cipher
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| DeserializeError::DecryptionFailed)?;
+
+    Ok((inner_type_marker, plaintext))
+}
+
+fn associated_data(header: &[u8], inner_type_marker: u8, key_id: KeyId) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(header.len() + 1 + 4);
+    aad.extend_from_slice(header);
+    aad.push(inner_type_marker);
+    aad.extend_from_slice(&key_id.to_le_bytes());
+    aad
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+pub type KeyId = u32;
+
+pub const KEY_LEN: usize = 32;
+
+/// Source of symmetric keys for encrypted tokens.
+///
+/// Tokens are always sealed with `current_key_id`, and opened with whichever
+/// key id is recorded in the token, so retired keys must stay resolvable until
+/// every token sealed with them has been re-encrypted.
+pub trait KeyProvider {
+    fn current_key_id(&self) -> KeyId;
+
+    fn key(&self, id: KeyId) -> Option<[u8; KEY_LEN]>;
+}
+
+impl<P: KeyProvider + ?Sized> KeyProvider for &P {
+    fn current_key_id(&self) -> KeyId {
+        (**self).current_key_id()
+    }
+
+    fn key(&self, id: KeyId) -> Option<[u8; KEY_LEN]> {
+        (**self).key(id)
+    }
+}
+
+/// In-memory `KeyProvider` holding every known key and the id used for new tokens.
+pub struct KeyRing {
+    keys: HashMap<KeyId, [u8; KEY_LEN]>,
+    current: KeyId,
+}
+
+impl KeyRing {
+    pub fn new(id: KeyId, key: [u8; KEY_LEN]) -> Self {
+        let mut keys = HashMap::new();
+        keys.insert(id, key);
+        Self { keys, current: id }
+    }
+
+    /// Adds `key` and makes it the key used for new tokens; older keys remain
+    /// available for decryption.
+    pub fn rotate(&mut self, id: KeyId, key: [u8; KEY_LEN]) {
+        self.keys.insert(id, key);
+        self.current = id;
+    }
+
+    pub fn retire(&mut self, id: KeyId) -> bool {
+        if id == self.current {
+            return false;
+        }
+        self.keys.remove(&id).is_some()
+    }
+}
+
+impl KeyProvider for KeyRing {
+    fn current_key_id(&self) -> KeyId {
+        self.current
+    }
+
+    fn key(&self, id: KeyId) -> Option<[u8; KEY_LEN]> {
+        self.keys.get(&id).copied()
+    }
+}
@@ -0,0 +1,5 @@
+mod envelope;
+mod key_provider;
+
+pub(crate) use envelope::{key_id, open, seal};
+pub use key_provider::{KeyId, KeyProvider, KeyRing, KEY_LEN};
@@ -0,0 +1,152 @@
+//! Reed-Solomon parity section appended to a frame:
+//!
+//! ```text
+//! [frame: header | payload | crc32][parity: blocks * nsym][descriptor * 3]
+//! descriptor: magic "TNRS" | nsym u8 | crc32 of magic and nsym (u32 LE)
+//! ```
+//!
+//! The frame is split into `blocks = ceil(len / (255 - nsym))` interleaved
+//! codewords (block `j` holds frame bytes `j, j + blocks, j + 2 * blocks, ...`)
+//! so that a burst of corrupted bytes is spread across codewords. Each block
+//! corrects up to `nsym / 2` bytes. The descriptor is stored three times and
+//! read from an intact copy or else by a bytewise majority vote, so every byte
+//! of the artifact is protected. Its magic and checksum keep the tail of a
+//! frame without parity from being mistaken for a descriptor.
+
+mod reed_solomon;
+
+use alloc::vec::Vec;
+
+use crc32fast::Hasher;
+
+pub(crate) const MIN_PARITY: u8 = 2;
+pub(crate) const MAX_PARITY: u8 = 128;
+
+const MAGIC: [u8; 4] = *b"TNRS";
+const DESCRIPTOR_LEN: usize = MAGIC.len() + 1 + 4;
+const DESCRIPTOR_COPIES: usize = 3;
+pub(crate) const TRAILER_LEN: usize = DESCRIPTOR_LEN * DESCRIPTOR_COPIES;
+
+fn blocks(data_len: usize, nsym: u8) -> usize {
+    data_len.div_ceil(255 - nsym as usize)
+}
+
+/// Parity section (including descriptor) for `data`.
+pub(crate) fn encode(data: &[u8], nsym: u8) -> Vec<u8> {
+    let blocks = blocks(data.len(), nsym);
+    let mut out = Vec::with_capacity(blocks * nsym as usize + TRAILER_LEN);
+    for j in 0..blocks {
+        let block: Vec<u8> = data.iter().skip(j).step_by(blocks).copied().collect();
+        out.extend(reed_solomon::parity(&block, nsym as usize));
+    }
+    let descriptor = descriptor(nsym);
+    for _ in 0..DESCRIPTOR_COPIES {
+        out.extend_from_slice(&descriptor);
+    }
+    out
+}
+
+/// Reads the descriptor at the end of `bytes` and returns the protected data
+/// length and parity length, if the tail is a consistent parity section.
+pub(crate) fn locate(bytes: &[u8]) -> Option<(usize, u8)> {
+    let trailer = bytes.get(bytes.len().checked_sub(TRAILER_LEN)?..)?;
+    let nsym = trailer
+        .chunks_exact(DESCRIPTOR_LEN)
+        .find_map(read_descriptor)
+        .or_else(|| {
+            let mut voted = [0; DESCRIPTOR_LEN];
+            for (i, byte) in voted.iter_mut().enumerate() {
+                let copies: Vec<u8> = trailer
+                    .iter()
+                    .skip(i)
+                    .step_by(DESCRIPTOR_LEN)
+                    .copied()
+                    .collect();
+                *byte = majority(&copies)?;
+            }
+            read_descriptor(&voted)
+        })?;
+
+    // Find the data length whose block count accounts for the rest.
+    let available = bytes.len() - TRAILER_LEN;
+    let mut count = 1;
+    loop {
+        let data_len = available.checked_sub(count * nsym as usize)?;
+        if data_len == 0 {
+            return None;
+        }
+        match blocks(data_len, nsym) {
+            b if b == count => return Some((data_len, nsym)),
+            b if b < count => return None,
+            _ => count += 1,
+        }
+    }
+}
+
+/// Repairs `bytes` (data, parity section and descriptor, as located by
+/// `locate`) in place. Returns the number of corrected bytes, or `None` when
+/// some block has more errors than its parity can correct.
+pub(crate) fn correct(bytes: &mut [u8], data_len: usize, nsym: u8) -> Option<usize> {
+    let blocks = blocks(data_len, nsym);
+    let n = nsym as usize;
+    let (data, rest) = bytes.split_at_mut(data_len);
+    let (parity, trailer) = rest.split_at_mut(blocks * n);
+
+    let mut corrected = 0;
+    for j in 0..blocks {
+        let mut codeword: Vec<u8> = data.iter().skip(j).step_by(blocks).copied().collect();
+        codeword.extend_from_slice(&parity[j * n..(j + 1) * n]);
+
+        let fixed = reed_solomon::correct(&mut codeword, n)?;
+        if fixed == 0 {
+            continue;
+        }
+        corrected += fixed;
+
+        let (block_data, block_parity) = codeword.split_at(codeword.len() - n);
+        for (dst, &src) in data.iter_mut().skip(j).step_by(blocks).zip(block_data) {
+            *dst = src;
+        }
+        parity[j * n..(j + 1) * n].copy_from_slice(block_parity);
+    }
+
+    let expected = descriptor(nsym);
+    for copy in trailer.chunks_exact_mut(DESCRIPTOR_LEN) {
+        for (byte, &good) in copy.iter_mut().zip(&expected) {
+            if *byte != good {
+                *byte = good;
+                corrected += 1;
+            }
+        }
+    }
+    Some(corrected)
+}
+
+fn descriptor(nsym: u8) -> [u8; DESCRIPTOR_LEN] {
+    let mut out = [0; DESCRIPTOR_LEN];
+    out[..MAGIC.len()].copy_from_slice(&MAGIC);
+    out[MAGIC.len()] = nsym;
+    let checksum = crc32(&out[..MAGIC.len() + 1]);
+    out[MAGIC.len() + 1..].copy_from_slice(&checksum.to_le_bytes());
+    out
+}
+
+/// The parity length of an intact descriptor in the supported range.
+fn read_descriptor(copy: &[u8]) -> Option<u8> {
+    let nsym = copy[MAGIC.len()];
+    let valid = (MIN_PARITY..=MAX_PARITY).contains(&nsym) && copy == descriptor(nsym);
+    valid.then_some(nsym)
+}
+
+fn majority(copies: &[u8]) -> Option<u8> {
+    copies
+        .iter()
+        .copied()
+        .find(|&c| copies.iter().filter(|&&o| o == c).count() * 2 > copies.len())
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut hasher = Hasher::new();
+    hasher.update(bytes);
+    hasher.finalize()
+}
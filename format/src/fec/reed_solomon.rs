@@ -0,0 +1,206 @@
+//! Systematic Reed-Solomon code over GF(2^8) (primitive polynomial 0x11d,
+//! generator 2, first consecutive root 1 = α^0). Polynomials are stored
+//! highest degree first, matching the order bytes appear in a codeword.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+const PRIMITIVE: u16 = 0x11d;
+
+struct Tables {
+    exp: [u8; 512],
+    log: [u8; 256],
+}
+
+const TABLES: Tables = build_tables();
+
+const fn build_tables() -> Tables {
+    let mut exp = [0u8; 512];
+    let mut log = [0u8; 256];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x as u8;
+        log[x as usize] = i as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= PRIMITIVE;
+        }
+        i += 1;
+    }
+    while i < 512 {
+        exp[i] = exp[i - 255];
+        i += 1;
+    }
+    Tables { exp, log }
+}
+
+fn mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    TABLES.exp[TABLES.log[a as usize] as usize + TABLES.log[b as usize] as usize]
+}
+
+fn div(a: u8, b: u8) -> u8 {
+    debug_assert!(b != 0);
+    if a == 0 {
+        return 0;
+    }
+    TABLES.exp[(TABLES.log[a as usize] as usize + 255 - TABLES.log[b as usize] as usize) % 255]
+}
+
+/// α^power.
+fn alpha(power: usize) -> u8 {
+    TABLES.exp[power % 255]
+}
+
+fn poly_eval(poly: &[u8], x: u8) -> u8 {
+    poly.iter().fold(0, |acc, &c| mul(acc, x) ^ c)
+}
+
+fn poly_mul(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut out = vec![0u8; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            out[i + j] ^= mul(x, y);
+        }
+    }
+    out
+}
+
+fn poly_add(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let len = a.len().max(b.len());
+    let mut out = vec![0u8; len];
+    for (i, &x) in a.iter().enumerate() {
+        out[i + len - a.len()] = x;
+    }
+    for (i, &y) in b.iter().enumerate() {
+        out[i + len - b.len()] ^= y;
+    }
+    out
+}
+
+fn poly_scale(poly: &[u8], factor: u8) -> Vec<u8> {
+    poly.iter().map(|&c| mul(c, factor)).collect()
+}
+
+fn generator(nsym: usize) -> Vec<u8> {
+    (0..nsym).fold(vec![1], |g, i| poly_mul(&g, &[1, alpha(i)]))
+}
+
+/// Parity bytes for `data`; `data.len() + nsym` must not exceed 255.
+pub(crate) fn parity(data: &[u8], nsym: usize) -> Vec<u8> {
+    debug_assert!(data.len() + nsym <= 255);
+    let generator = generator(nsym);
+    let mut remainder = vec![0u8; nsym];
+
+    // Long division of data * x^nsym by the generator, keeping only the
+    // running remainder.
+    for &byte in data {
+        let factor = byte ^ remainder[0];
+        remainder.remove(0);
+        remainder.push(0);
+        if factor != 0 {
+            for (r, &g) in remainder.iter_mut().zip(&generator[1..]) {
+                *r ^= mul(g, factor);
+            }
+        }
+    }
+    remainder
+}
+
+/// Corrects `codeword` (data followed by `nsym` parity bytes) in place.
+/// Returns the number of bytes changed, or `None` if there are more errors
+/// than the code can correct.
+pub(crate) fn correct(codeword: &mut [u8], nsym: usize) -> Option<usize> {
+    let n = codeword.len();
+    let syndromes: Vec<u8> = (0..nsym).map(|i| poly_eval(codeword, alpha(i))).collect();
+    if syndromes.iter().all(|&s| s == 0) {
+        return Some(0);
+    }
+
+    let locator = error_locator(&syndromes)?;
+    let errors = locator.len() - 1;
+    if errors * 2 > nsym {
+        return None;
+    }
+
+    // Chien search: position p (from the front) is in error when the
+    // locator vanishes at α^-(n-1-p).
+    let reversed: Vec<u8> = locator.iter().rev().copied().collect();
+    let positions: Vec<usize> = (0..n)
+        .filter(|&i| poly_eval(&reversed, alpha(i)) == 0)
+        .map(|i| n - 1 - i)
+        .collect();
+    if positions.len() != errors {
+        return None;
+    }
+
+    let magnitudes = forney(&syndromes, &positions, n);
+    for (&pos, &magnitude) in positions.iter().zip(&magnitudes) {
+        codeword[pos] ^= magnitude;
+    }
+
+    if (0..nsym).any(|i| poly_eval(codeword, alpha(i)) != 0) {
+        return None;
+    }
+    Some(magnitudes.iter().filter(|&&m| m != 0).count())
+}
+
+/// Berlekamp-Massey.
+fn error_locator(syndromes: &[u8]) -> Option<Vec<u8>> {
+    let mut locator = vec![1u8];
+    let mut previous = vec![1u8];
+
+    for k in 0..syndromes.len() {
+        let mut delta = syndromes[k];
+        for j in 1..locator.len().min(k + 1) {
+            delta ^= mul(locator[locator.len() - 1 - j], syndromes[k - j]);
+        }
+        previous.push(0);
+        if delta != 0 {
+            if previous.len() > locator.len() {
+                let next = poly_scale(&previous, delta);
+                previous = poly_scale(&locator, div(1, delta));
+                locator = next;
+            }
+            locator = poly_add(&locator, &poly_scale(&previous, delta));
+        }
+    }
+
+    let leading = locator.iter().position(|&c| c != 0)?;
+    Some(locator.split_off(leading))
+}
+
+/// Error magnitudes at `positions` (indices from the front of an `n`-byte
+/// codeword).
+fn forney(syndromes: &[u8], positions: &[usize], n: usize) -> Vec<u8> {
+    let coefficients: Vec<usize> = positions.iter().map(|&p| n - 1 - p).collect();
+
+    // Errata locator ∏ (1 + α^c x) and evaluator Ω = S(x) Λ(x) mod x^(e+1).
+    let locator = coefficients
+        .iter()
+        .fold(vec![1u8], |acc, &c| poly_mul(&acc, &[alpha(c), 1]));
+    // S(x) = s0 x + s1 x^2 + ... + s(n-1) x^n, highest degree first.
+    let mut syndrome_poly: Vec<u8> = syndromes.iter().rev().copied().collect();
+    syndrome_poly.push(0);
+    let product = poly_mul(&syndrome_poly, &locator);
+    let evaluator = &product[product.len() - locator.len()..];
+
+    let roots: Vec<u8> = coefficients.iter().map(|&c| alpha(c)).collect();
+    roots
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let x_inv = div(1, x);
+            let derivative = roots
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .fold(1u8, |acc, (_, &xj)| mul(acc, 1 ^ mul(x_inv, xj)));
+            let y = mul(x, poly_eval(evaluator, x_inv));
+            div(y, derivative)
+        })
+        .collect()
+}
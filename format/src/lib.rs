@@ -1,13 +1,57 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![forbid(unsafe_code)]
 
+extern crate alloc;
+
+mod delta;
+mod fec;
+mod macros;
+mod parallel;
+
+#[cfg(feature = "tokio")]
+pub mod codec;
+pub mod collections;
 pub mod deserialization;
+#[cfg(feature = "encryption")]
+pub mod encryption;
+#[cfg(feature = "std")]
+pub mod patch;
+#[cfg(feature = "std")]
+pub mod query;
+#[cfg(feature = "std")]
 pub mod registry;
 pub mod serialization;
 pub mod spec;
+#[cfg(any(feature = "proptest", feature = "arbitrary"))]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod text;
 pub mod types;
 
-pub use deserialization::{DeserializeError, Deserializer, TokenHeader, TokenLayout};
-pub use registry::{RegistryError, TokenRegistry};
-pub use serialization::{SerializeError, Serializer};
+#[cfg(feature = "arena")]
+pub use bumpalo;
+#[cfg(feature = "arena")]
+pub use deserialization::{ArenaDecoder, ArenaRef, ArenaToken, ArenaValue};
+pub use deserialization::{
+    BatchDeserializer, Damage, Decoded, DeserializeError, Deserializer, Salvage, TokenDecoder,
+    TokenHeader, TokenLayout,
+};
+#[cfg(feature = "std")]
+pub use patch::{Patch, PatchError, PatchOp};
+#[cfg(feature = "std")]
+pub use query::{Query, QueryError, QueryMatch};
+#[cfg(feature = "std")]
+pub use registry::{Bundle, RegistryError, TokenRegistry};
+pub use serialization::{BatchSerializer, SerializeError, Serializer};
 pub use spec::constants;
-pub use types::{Metadata, Token, TokenId, TokenRef, TokenRefStrength, Value};
+pub use types::{
+    Metadata, MetadataFlags, Path, PathSegment, SharedValue, Token, TokenId, TokenRef,
+    TokenRefStrength, TypeMismatch, Value, ValueIndex,
+};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::collections::HashMap;
+    pub use alloc::string::String;
+    pub use alloc::vec::Vec;
+}
@@ -0,0 +1,145 @@
+/// Builds a `Value` from a JSON-like literal.
+///
+/// ```
+/// use toon_format::{toon, TokenId, Value};
+///
+/// let parent = TokenId::from(uuid::Uuid::from_bytes([7; 16]));
+/// let value = toon! {
+///     "name": "x",
+///     "tags": ["a", 1, 2.5, null],
+///     "parent": &strong parent,
+///     "meta": { "draft": true },
+/// };
+///
+/// assert_eq!(value["tags"][0], Value::from("a"));
+/// ```
+///
+/// Keys are string literals or parenthesized expressions convertible into
+/// `String`; `&strong id` and `&weak id` build references; any other value
+/// position accepts an expression convertible into `Value`.
+#[macro_export]
+macro_rules! toon {
+    () => {
+        $crate::Value::Object($crate::__private::HashMap::new())
+    };
+    ($key:literal : $($rest:tt)*) => {
+        $crate::toon!({ $key : $($rest)* })
+    };
+    ($($value:tt)+) => {
+        $crate::__toon_value!($($value)+)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __toon_value {
+    (null) => {
+        $crate::Value::Null
+    };
+    (true) => {
+        $crate::Value::Bool(true)
+    };
+    (false) => {
+        $crate::Value::Bool(false)
+    };
+    ([]) => {
+        $crate::Value::Array($crate::__private::Vec::new())
+    };
+    ([ $($tt:tt)+ ]) => {
+        $crate::Value::Array({
+            let mut array = $crate::__private::Vec::new();
+            $crate::__toon_array!(array $($tt)+);
+            array
+        })
+    };
+    ({}) => {
+        $crate::Value::Object($crate::__private::HashMap::new())
+    };
+    ({ $($tt:tt)+ }) => {
+        $crate::Value::Object({
+            let mut object = $crate::__private::HashMap::new();
+            $crate::__toon_object!(object $($tt)+);
+            object
+        })
+    };
+    (& strong $id:expr) => {
+        $crate::Value::Ref($crate::TokenRef::strong($id))
+    };
+    (& weak $id:expr) => {
+        $crate::Value::Ref($crate::TokenRef::weak($id))
+    };
+    ($other:expr) => {
+        $crate::Value::from($other)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __toon_array {
+    ($array:ident) => {};
+    ($array:ident null $(, $($rest:tt)*)?) => {
+        $array.push($crate::__toon_value!(null));
+        $crate::__toon_array!($array $($($rest)*)?);
+    };
+    ($array:ident true $(, $($rest:tt)*)?) => {
+        $array.push($crate::__toon_value!(true));
+        $crate::__toon_array!($array $($($rest)*)?);
+    };
+    ($array:ident false $(, $($rest:tt)*)?) => {
+        $array.push($crate::__toon_value!(false));
+        $crate::__toon_array!($array $($($rest)*)?);
+    };
+    ($array:ident [ $($inner:tt)* ] $(, $($rest:tt)*)?) => {
+        $array.push($crate::__toon_value!([ $($inner)* ]));
+        $crate::__toon_array!($array $($($rest)*)?);
+    };
+    ($array:ident { $($inner:tt)* } $(, $($rest:tt)*)?) => {
+        $array.push($crate::__toon_value!({ $($inner)* }));
+        $crate::__toon_array!($array $($($rest)*)?);
+    };
+    ($array:ident & $strength:ident $id:expr $(, $($rest:tt)*)?) => {
+        $array.push($crate::__toon_value!(& $strength $id));
+        $crate::__toon_array!($array $($($rest)*)?);
+    };
+    ($array:ident $value:expr $(, $($rest:tt)*)?) => {
+        $array.push($crate::__toon_value!($value));
+        $crate::__toon_array!($array $($($rest)*)?);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __toon_object {
+    ($object:ident) => {};
+    ($object:ident $key:tt : null $(, $($rest:tt)*)?) => {
+        $crate::__toon_object!(@insert $object $key ($crate::__toon_value!(null)));
+        $crate::__toon_object!($object $($($rest)*)?);
+    };
+    ($object:ident $key:tt : true $(, $($rest:tt)*)?) => {
+        $crate::__toon_object!(@insert $object $key ($crate::__toon_value!(true)));
+        $crate::__toon_object!($object $($($rest)*)?);
+    };
+    ($object:ident $key:tt : false $(, $($rest:tt)*)?) => {
+        $crate::__toon_object!(@insert $object $key ($crate::__toon_value!(false)));
+        $crate::__toon_object!($object $($($rest)*)?);
+    };
+    ($object:ident $key:tt : [ $($inner:tt)* ] $(, $($rest:tt)*)?) => {
+        $crate::__toon_object!(@insert $object $key ($crate::__toon_value!([ $($inner)* ])));
+        $crate::__toon_object!($object $($($rest)*)?);
+    };
+    ($object:ident $key:tt : { $($inner:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::__toon_object!(@insert $object $key ($crate::__toon_value!({ $($inner)* })));
+        $crate::__toon_object!($object $($($rest)*)?);
+    };
+    ($object:ident $key:tt : & $strength:ident $id:expr $(, $($rest:tt)*)?) => {
+        $crate::__toon_object!(@insert $object $key ($crate::__toon_value!(& $strength $id)));
+        $crate::__toon_object!($object $($($rest)*)?);
+    };
+    ($object:ident $key:tt : $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__toon_object!(@insert $object $key ($crate::__toon_value!($value)));
+        $crate::__toon_object!($object $($($rest)*)?);
+    };
+    (@insert $object:ident $key:tt ($value:expr)) => {
+        let _ = $object.insert($crate::__private::String::from($key), $value);
+    };
+}
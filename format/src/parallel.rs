@@ -0,0 +1,34 @@
+use alloc::vec::Vec;
+
+/// Containers with at least this many children are encoded and decoded in
+/// parallel when the `rayon` feature is enabled.
+#[cfg(feature = "rayon")]
+pub(crate) const PARALLEL_THRESHOLD: usize = 1024;
+
+/// Maps `f` over `items`, keeping their order. With the `rayon` feature,
+/// slices of at least `PARALLEL_THRESHOLD` items are mapped on the rayon
+/// thread pool. Either way the first failing item, in order, is reported.
+#[cfg(feature = "rayon")]
+pub(crate) fn try_map<T, R, E, F>(items: &[T], f: F) -> Result<Vec<R>, E>
+where
+    T: Sync,
+    R: Send,
+    E: Send,
+    F: Fn(&T) -> Result<R, E> + Sync + Send,
+{
+    use rayon::prelude::*;
+
+    if items.len() < PARALLEL_THRESHOLD {
+        return items.iter().map(f).collect();
+    }
+    let results: Vec<Result<R, E>> = items.par_iter().map(f).collect();
+    results.into_iter().collect()
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn try_map<T, R, E, F>(items: &[T], f: F) -> Result<Vec<R>, E>
+where
+    F: Fn(&T) -> Result<R, E>,
+{
+    items.iter().map(f).collect()
+}
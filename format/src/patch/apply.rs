@@ -0,0 +1,75 @@
+use crate::{Path, PathSegment, Value};
+
+use super::{Patch, PatchError, PatchOp};
+
+/// Applies `patch` to `target`.
+///
+/// Every `Remove` and `Replace` checks that the current value matches the one
+/// recorded in the patch, and `Add` refuses to overwrite an existing object
+/// key. On any error `target` is left untouched.
+pub fn apply(target: &mut Value, patch: &Patch) -> Result<(), PatchError> {
+    let mut working = target.clone();
+    for op in patch.ops() {
+        apply_op(&mut working, op)?;
+    }
+    *target = working;
+    Ok(())
+}
+
+fn apply_op(root: &mut Value, op: &PatchOp) -> Result<(), PatchError> {
+    match op {
+        PatchOp::Replace { path, old, new } => {
+            let slot = lookup_mut(root, path)?;
+            check(path, old, slot)?;
+            *slot = new.clone();
+            Ok(())
+        }
+        PatchOp::Add { path, value } => {
+            let (parent_path, last) = path
+                .split_last()
+                .ok_or_else(|| PatchError::InvalidPath(path.clone()))?;
+            match (lookup_mut(root, &parent_path)?, last) {
+                (Value::Object(map), PathSegment::Key(key)) => {
+                    if map.contains_key(key) {
+                        return Err(PatchError::AlreadyExists(path.clone()));
+                    }
+                    map.insert(key.clone(), value.clone());
+                    Ok(())
+                }
+                (Value::Array(items), PathSegment::Index(index)) if *index <= items.len() => {
+                    items.insert(*index, value.clone());
+                    Ok(())
+                }
+                _ => Err(PatchError::PathNotFound(path.clone())),
+            }
+        }
+        PatchOp::Remove { path, old } => {
+            let (parent_path, last) = path
+                .split_last()
+                .ok_or_else(|| PatchError::InvalidPath(path.clone()))?;
+            check(path, old, lookup_mut(root, path)?)?;
+            match (lookup_mut(root, &parent_path)?, last) {
+                (Value::Object(map), PathSegment::Key(key)) => {
+                    map.remove(key);
+                }
+                (Value::Array(items), PathSegment::Index(index)) => {
+                    items.remove(*index);
+                }
+                _ => unreachable!("lookup_mut already resolved the full path"),
+            }
+            Ok(())
+        }
+    }
+}
+
+fn check(path: &Path, expected: &Value, actual: &Value) -> Result<(), PatchError> {
+    if expected != actual {
+        return Err(PatchError::Conflict(path.clone()));
+    }
+    Ok(())
+}
+
+fn lookup_mut<'a>(root: &'a mut Value, path: &Path) -> Result<&'a mut Value, PatchError> {
+    root.get_path_mut(path)
+        .ok_or_else(|| PatchError::PathNotFound(path.clone()))
+}
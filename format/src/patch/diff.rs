@@ -0,0 +1,88 @@
+use crate::{Path, Value};
+
+use super::{Patch, PatchOp};
+
+/// Computes the operations that turn `old` into `new`.
+///
+/// Objects are compared key by key and arrays element by element after
+/// trimming their common prefix and suffix, so an insertion or deletion in the
+/// middle of an array becomes a single `Add` or `Remove`.
+pub fn diff(old: &Value, new: &Value) -> Patch {
+    let mut ops = Vec::new();
+    let mut path = Path::root();
+    diff_into(old, new, &mut path, &mut ops);
+    Patch::new(ops)
+}
+
+fn diff_into(old: &Value, new: &Value, path: &mut Path, ops: &mut Vec<PatchOp>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut removed: Vec<_> = a.keys().filter(|k| !b.contains_key(*k)).collect();
+            removed.sort();
+            for key in removed {
+                ops.push(PatchOp::Remove {
+                    path: path.key(key.as_str()),
+                    old: a[key].clone(),
+                });
+            }
+
+            let mut keys: Vec<_> = b.keys().collect();
+            keys.sort();
+            for key in keys {
+                match a.get(key) {
+                    Some(prev) => {
+                        path.push_key(key.as_str());
+                        diff_into(prev, &b[key], path, ops);
+                        path.pop();
+                    }
+                    None => ops.push(PatchOp::Add {
+                        path: path.key(key.as_str()),
+                        value: b[key].clone(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => diff_arrays(a, b, path, ops),
+        _ if old == new => {}
+        _ => ops.push(PatchOp::Replace {
+            path: path.clone(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+    }
+}
+
+fn diff_arrays(a: &[Value], b: &[Value], path: &mut Path, ops: &mut Vec<PatchOp>) {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let old_mid = &a[prefix..a.len() - suffix];
+    let new_mid = &b[prefix..b.len() - suffix];
+    let common = old_mid.len().min(new_mid.len());
+
+    for i in 0..common {
+        path.push_index(prefix + i);
+        diff_into(&old_mid[i], &new_mid[i], path, ops);
+        path.pop();
+    }
+
+    // Remove from the back so earlier indices stay valid.
+    for i in (common..old_mid.len()).rev() {
+        ops.push(PatchOp::Remove {
+            path: path.index(prefix + i),
+            old: old_mid[i].clone(),
+        });
+    }
+
+    for (i, value) in new_mid.iter().enumerate().skip(common) {
+        ops.push(PatchOp::Add {
+            path: path.index(prefix + i),
+            value: value.clone(),
+        });
+    }
+}
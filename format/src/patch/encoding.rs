@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use crate::{Path, PathSegment, Value};
+
+use super::{Patch, PatchError, PatchOp};
+
+// A patch encodes as an array of objects:
+//   {"op": "add", "path": [...], "value": v}
+//   {"op": "remove", "path": [...], "old": v}
+//   {"op": "replace", "path": [...], "old": v, "new": v}
+// where "path" lists object keys as strings and array indices as ints.
+
+impl Patch {
+    pub fn to_value(&self) -> Value {
+        Value::Array(self.ops.iter().map(op_to_value).collect())
+    }
+
+    pub fn from_value(value: &Value) -> Result<Self, PatchError> {
+        let Value::Array(items) = value else {
+            return Err(PatchError::InvalidEncoding("patch must be an array"));
+        };
+        items
+            .iter()
+            .map(op_from_value)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Patch::new)
+    }
+}
+
+impl From<&Patch> for Value {
+    fn from(patch: &Patch) -> Self {
+        patch.to_value()
+    }
+}
+
+impl TryFrom<&Value> for Patch {
+    type Error = PatchError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        Patch::from_value(value)
+    }
+}
+
+fn op_to_value(op: &PatchOp) -> Value {
+    let mut map = HashMap::new();
+    let (name, path) = match op {
+        PatchOp::Add { path, value } => {
+            map.insert("value".to_string(), value.clone());
+            ("add", path)
+        }
+        PatchOp::Remove { path, old } => {
+            map.insert("old".to_string(), old.clone());
+            ("remove", path)
+        }
+        PatchOp::Replace { path, old, new } => {
+            map.insert("old".to_string(), old.clone());
+            map.insert("new".to_string(), new.clone());
+            ("replace", path)
+        }
+    };
+    map.insert("op".to_string(), Value::String(name.to_string()));
+    map.insert("path".to_string(), path_to_value(path));
+    Value::Object(map)
+}
+
+fn op_from_value(value: &Value) -> Result<PatchOp, PatchError> {
+    let Value::Object(map) = value else {
+        return Err(PatchError::InvalidEncoding("operation must be an object"));
+    };
+    let field = |name: &'static str| {
+        map.get(name)
+            .cloned()
+            .ok_or(PatchError::InvalidEncoding("operation is missing a field"))
+    };
+
+    let path = path_from_value(
+        map.get("path")
+            .ok_or(PatchError::InvalidEncoding("operation is missing a path"))?,
+    )?;
+
+    match map.get("op") {
+        Some(Value::String(op)) if op == "add" => Ok(PatchOp::Add {
+            path,
+            value: field("value")?,
+        }),
+        Some(Value::String(op)) if op == "remove" => Ok(PatchOp::Remove {
+            path,
+            old: field("old")?,
+        }),
+        Some(Value::String(op)) if op == "replace" => Ok(PatchOp::Replace {
+            path,
+            old: field("old")?,
+            new: field("new")?,
+        }),
+        _ => Err(PatchError::InvalidEncoding("unknown operation")),
+    }
+}
+
+fn path_to_value(path: &Path) -> Value {
+    Value::Array(
+        path.segments()
+            .iter()
+            .map(|segment| match segment {
+                PathSegment::Key(key) => Value::String(key.clone()),
+                PathSegment::Index(index) => Value::Int(*index as i64),
+            })
+            .collect(),
+    )
+}
+
+fn path_from_value(value: &Value) -> Result<Path, PatchError> {
+    let Value::Array(items) = value else {
+        return Err(PatchError::InvalidEncoding("path must be an array"));
+    };
+    items
+        .iter()
+        .map(|item| match item {
+            Value::String(key) => Ok(PathSegment::Key(key.clone())),
+            Value::Int(index) => usize::try_from(*index)
+                .map(PathSegment::Index)
+                .map_err(|_| PatchError::InvalidEncoding("negative array index")),
+            _ => Err(PatchError::InvalidEncoding(
+                "path segment must be a string or int",
+            )),
+        })
+        .collect()
+}
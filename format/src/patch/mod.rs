@@ -0,0 +1,82 @@
+mod apply;
+mod diff;
+mod encoding;
+
+use thiserror::Error;
+
+use crate::{Path, Value};
+
+pub use apply::apply;
+pub use diff::diff;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PatchOp {
+    /// Inserts `value` at `path`. For arrays the index may equal the length
+    /// (append); later elements shift right.
+    Add { path: Path, value: Value },
+
+    /// Removes the value at `path`, which must currently equal `old`.
+    Remove { path: Path, old: Value },
+
+    /// Swaps the value at `path`, which must currently equal `old`, for `new`.
+    Replace { path: Path, old: Value, new: Value },
+}
+
+impl PatchOp {
+    pub fn path(&self) -> &Path {
+        match self {
+            PatchOp::Add { path, .. } => path,
+            PatchOp::Remove { path, .. } => path,
+            PatchOp::Replace { path, .. } => path,
+        }
+    }
+}
+
+/// Ordered list of operations turning one `Value` into another.
+///
+/// Operations are applied in sequence, so array indices in later operations
+/// refer to the array as left by the earlier ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Patch {
+    ops: Vec<PatchOp>,
+}
+
+impl Patch {
+    pub fn new(ops: Vec<PatchOp>) -> Self {
+        Self { ops }
+    }
+
+    pub fn ops(&self) -> &[PatchOp] {
+        &self.ops
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn into_ops(self) -> Vec<PatchOp> {
+        self.ops
+    }
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum PatchError {
+    #[error("path not found: {0}")]
+    PathNotFound(Path),
+
+    #[error("value at {0} does not match the patch")]
+    Conflict(Path),
+
+    #[error("value already present at {0}")]
+    AlreadyExists(Path),
+
+    #[error("operation not allowed on path {0}")]
+    InvalidPath(Path),
+
+    #[error("invalid patch encoding: {0}")]
+    InvalidEncoding(&'static str),
+}
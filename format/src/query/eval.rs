@@ -0,0 +1,282 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::types::pointer_path;
+use crate::{Path, PathSegment, RegistryError, Token, TokenRefStrength, TokenRegistry, Value};
+
+use super::parser::{CmpOp, Expr, Operand, Segment};
+use super::{QueryError, QueryMatch};
+
+/// A node reached during evaluation: either borrowed from the queried value
+/// or located inside a token pulled from the registry.
+#[derive(Clone)]
+enum Node<'a> {
+    Local(&'a Value),
+    Remote(Arc<Token>, Path),
+}
+
+impl<'a> Node<'a> {
+    fn value(&self) -> &Value {
+        match self {
+            Node::Local(value) => value,
+            Node::Remote(token, path) => token
+                .value()
+                .get_path(path)
+                .expect("remote paths are built from existing children"),
+        }
+    }
+
+    fn into_cow(self) -> Cow<'a, Value> {
+        match self {
+            Node::Local(value) => Cow::Borrowed(value),
+            node @ Node::Remote(..) => Cow::Owned(node.value().clone()),
+        }
+    }
+
+    fn child(&self, segment: &PathSegment) -> Option<Node<'a>> {
+        match self {
+            Node::Local(value) => value.get(segment).map(Node::Local),
+            Node::Remote(token, path) => {
+                self.value().get(segment)?;
+                let mut path = path.clone();
+                match segment {
+                    PathSegment::Key(key) => path.push_key(key.as_str()),
+                    PathSegment::Index(index) => path.push_index(*index),
+                }
+                Some(Node::Remote(Arc::clone(token), path))
+            }
+        }
+    }
+
+    /// Member keys in sorted order or element indices, so results come out in
+    /// a stable order.
+    fn child_segments(&self) -> Vec<PathSegment> {
+        match self.value() {
+            Value::Array(items) => (0..items.len()).map(PathSegment::Index).collect(),
+            Value::Object(map) => {
+                let mut keys: Vec<_> = map.keys().cloned().collect();
+                keys.sort();
+                keys.into_iter().map(PathSegment::Key).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+struct Ctx<'a, 'r> {
+    root: &'a Value,
+    registry: Option<&'r TokenRegistry>,
+}
+
+type Nodes<'a> = Vec<(Path, Node<'a>)>;
+
+pub(crate) fn evaluate<'a>(
+    segments: &[Segment],
+    root: &'a Value,
+    registry: Option<&TokenRegistry>,
+) -> Result<Vec<QueryMatch<'a>>, QueryError> {
+    let ctx = Ctx { root, registry };
+    let nodes = ctx.run(segments, vec![(Path::root(), Node::Local(root))])?;
+    Ok(nodes
+        .into_iter()
+        .map(|(path, node)| QueryMatch {
+            path,
+            value: node.into_cow(),
+        })
+        .collect())
+}
+
+impl<'a> Ctx<'a, '_> {
+    fn run(&self, segments: &[Segment], mut nodes: Nodes<'a>) -> Result<Nodes<'a>, QueryError> {
+        for segment in segments {
+            let mut next = Vec::new();
+            for (path, node) in nodes {
+                self.select(segment, path, node, true, &mut next)?;
+            }
+            nodes = next;
+        }
+        Ok(nodes)
+    }
+
+    fn select(
+        &self,
+        segment: &Segment,
+        path: Path,
+        node: Node<'a>,
+        follow_refs: bool,
+        out: &mut Nodes<'a>,
+    ) -> Result<(), QueryError> {
+        if let Segment::Descendant(inner) = segment {
+            let mut stack = vec![(path, node)];
+            while let Some((path, node)) = stack.pop() {
+                let children: Vec<_> = node
+                    .child_segments()
+                    .into_iter()
+                    .rev()
+                    .filter_map(|seg| Some((extend(&path, &seg), node.child(&seg)?)))
+                    .collect();
+                self.select(inner, path, node, false, out)?;
+                stack.extend(children);
+            }
+            return Ok(());
+        }
+
+        let node = if follow_refs {
+            match self.deref(node)? {
+                Some(node) => node,
+                None => return Ok(()),
+            }
+        } else {
+            node
+        };
+
+        match segment {
+            Segment::Child(key) => {
+                let seg = PathSegment::Key(key.clone());
+                if let Some(child) = node.child(&seg) {
+                    out.push((extend(&path, &seg), child));
+                }
+            }
+            Segment::Index(index) => {
+                if let Value::Array(items) = node.value() {
+                    let len = items.len() as i64;
+                    let index = if *index < 0 { len + index } else { *index };
+                    if (0..len).contains(&index) {
+                        let seg = PathSegment::Index(index as usize);
+                        if let Some(child) = node.child(&seg) {
+                            out.push((extend(&path, &seg), child));
+                        }
+                    }
+                }
+            }
+            Segment::Wildcard => {
+                for seg in node.child_segments() {
+                    if let Some(child) = node.child(&seg) {
+                        out.push((extend(&path, &seg), child));
+                    }
+                }
+            }
+            Segment::Filter(expr) => {
+                for seg in node.child_segments() {
+                    if let Some(child) = node.child(&seg) {
+                        if self.test(expr, &child)? {
+                            out.push((extend(&path, &seg), child));
+                        }
+                    }
+                }
+            }
+            Segment::Descendant(_) => unreachable!("handled above"),
+        }
+        Ok(())
+    }
+
+    /// Replaces a reference node by the referenced token's value when a
+    /// registry is available. Returns `None` for a missing weak target.
+    fn deref(&self, node: Node<'a>) -> Result<Option<Node<'a>>, QueryError> {
+        let Some(registry) = self.registry else {
+            return Ok(Some(node));
+        };
+
+        let mut node = node;
+        let mut seen = HashSet::new();
+        while let Value::Ref(r) = node.value() {
+            // Keyed by id and fragment: two fragments of one token are distinct.
+            let r = r.clone();
+            if !seen.insert(r.clone()) {
+                let ids = seen.into_iter().map(|seen| seen.id()).collect();
+                return Err(RegistryError::CircularReference(ids).into());
+            }
+            let token = match (registry.resolve_ref(&r), r.strength()) {
+                (Ok(token), _) => token,
+                (Err(RegistryError::NotFound(_)), TokenRefStrength::Weak) => return Ok(None),
+                (Err(err), _) => return Err(err.into()),
+            };
+            let path = match r.fragment() {
+                Some(fragment) => pointer_path(token.value(), fragment).map_err(|missing| {
+                    RegistryError::FragmentNotFound(r.id(), missing.to_string())
+                })?,
+                None => Path::root(),
+            };
+            node = Node::Remote(token, path);
+        }
+        Ok(Some(node))
+    }
+
+    fn test(&self, expr: &Expr, current: &Node<'a>) -> Result<bool, QueryError> {
+        Ok(match expr {
+            Expr::Or(a, b) => self.test(a, current)? || self.test(b, current)?,
+            Expr::And(a, b) => self.test(a, current)? && self.test(b, current)?,
+            Expr::Not(inner) => !self.test(inner, current)?,
+            Expr::Exists(operand) => self.operand(operand, current)?.is_some(),
+            Expr::Compare(lhs, op, rhs) => {
+                let (Some(lhs), Some(rhs)) =
+                    (self.operand(lhs, current)?, self.operand(rhs, current)?)
+                else {
+                    return Ok(false);
+                };
+                compare(lhs.value(), *op, rhs.value())
+            }
+        })
+    }
+
+    /// Resolves a filter operand; paths yield their first match, if any.
+    fn operand<'e>(
+        &self,
+        operand: &'e Operand,
+        current: &Node<'a>,
+    ) -> Result<Option<Resolved<'e, 'a>>, QueryError> {
+        let (start, segments) = match operand {
+            Operand::Literal(value) => return Ok(Some(Resolved::Literal(value))),
+            Operand::Current(segments) => (current.clone(), segments),
+            Operand::Root(segments) => (Node::Local(self.root), segments),
+        };
+        let nodes = self.run(segments, vec![(Path::root(), start)])?;
+        Ok(nodes
+            .into_iter()
+            .next()
+            .map(|(_, node)| Resolved::Node(node)))
+    }
+}
+
+enum Resolved<'e, 'a> {
+    Literal(&'e Value),
+    Node(Node<'a>),
+}
+
+impl Resolved<'_, '_> {
+    fn value(&self) -> &Value {
+        match self {
+            Resolved::Literal(value) => value,
+            Resolved::Node(node) => node.value(),
+        }
+    }
+}
+
+fn compare(lhs: &Value, op: CmpOp, rhs: &Value) -> bool {
+    let ordering = match (lhs, rhs) {
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+        (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
+        (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+
+    match op {
+        CmpOp::Eq => ordering.map_or(lhs == rhs, |o| o == Ordering::Equal),
+        CmpOp::Ne => ordering.map_or(lhs != rhs, |o| o != Ordering::Equal),
+        CmpOp::Lt => ordering == Some(Ordering::Less),
+        CmpOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        CmpOp::Gt => ordering == Some(Ordering::Greater),
+        CmpOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+fn extend(path: &Path, segment: &PathSegment) -> Path {
+    match segment {
+        PathSegment::Key(key) => path.key(key.as_str()),
+        PathSegment::Index(index) => path.index(*index),
+    }
+}
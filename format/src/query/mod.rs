@@ -0,0 +1,78 @@
+mod eval;
+mod parser;
+
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::{Path, RegistryError, TokenRegistry, Value};
+
+/// A compiled path query, e.g. `$.orders[*].items[?(@.qty > 2)].sku`.
+///
+/// Supported selectors:
+///
+/// - `$` the root value, `@` the current node inside a filter
+/// - `.name` / `['name']` object member, `[n]` array element (negative counts
+///   from the end), `.*` / `[*]` every member or element
+/// - `..sel` applies `sel` to the node and all of its descendants
+/// - `[?(expr)]` keeps members or elements for which `expr` holds; `expr`
+///   combines `@`/`$` paths and literals with `== != < <= > >=`, `&&`, `||`,
+///   `!` and parentheses, and a bare path tests for existence
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    segments: Vec<parser::Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryMatch<'a> {
+    pub path: Path,
+    pub value: Cow<'a, Value>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum QueryError {
+    #[error("syntax error at offset {offset}: {message}")]
+    Syntax {
+        offset: usize,
+        message: &'static str,
+    },
+
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+}
+
+impl Query {
+    /// Compiles `src`. Negations, parentheses and filters may be nested at
+    /// most 128 deep.
+    pub fn compile(src: &str) -> Result<Self, QueryError> {
+        parser::parse(src).map(|segments| Self { segments })
+    }
+
+    /// Returns every sub-value of `value` selected by the query, in document
+    /// order. References are treated as leaves.
+    pub fn evaluate<'a>(&self, value: &'a Value) -> Vec<QueryMatch<'a>> {
+        eval::evaluate(&self.segments, value, None).expect("no registry, no resolution errors")
+    }
+
+    /// Like `evaluate`, but a selector applied to a `Value::Ref` continues in
+    /// the referenced token's value. Missing weak targets select nothing;
+    /// missing strong targets are an error. Values reached through a
+    /// reference are returned owned, and their paths run through the
+    /// referencing position. `..` does not descend through references.
+    pub fn evaluate_with_registry<'a>(
+        &self,
+        value: &'a Value,
+        registry: &TokenRegistry,
+    ) -> Result<Vec<QueryMatch<'a>>, QueryError> {
+        eval::evaluate(&self.segments, value, Some(registry))
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Query::compile(s)
+    }
+}
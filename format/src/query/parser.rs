@@ -0,0 +1,334 @@
+use crate::Value;
+
+use super::QueryError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Segment {
+    Child(String),
+    Index(i64),
+    Wildcard,
+    Filter(Expr),
+    Descendant(Box<Segment>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CmpOp, Operand),
+    Exists(Operand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Operand {
+    Current(Vec<Segment>),
+    Root(Vec<Segment>),
+    Literal(Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Deepest nesting of negations, parentheses and filters that `parse`
+/// accepts.
+pub(crate) const MAX_DEPTH: usize = 128;
+
+pub(crate) fn parse(src: &str) -> Result<Vec<Segment>, QueryError> {
+    let mut parser = Parser {
+        src,
+        pos: 0,
+        depth: 0,
+    };
+    parser.skip_ws();
+    parser.expect('$', "query must start with '$'")?;
+    let segments = parser.parse_segments()?;
+    parser.skip_ws();
+    if parser.pos != src.len() {
+        return Err(parser.error("unexpected character"));
+    }
+    Ok(segments)
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &'static str) -> QueryError {
+        QueryError::Syntax {
+            offset: self.pos,
+            message,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.src[self.pos..].starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char, message: &'static str) -> Result<(), QueryError> {
+        if self.peek() == Some(c) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.bump();
+        }
+    }
+
+    fn parse_segments(&mut self) -> Result<Vec<Segment>, QueryError> {
+        let mut segments = Vec::new();
+        loop {
+            if self.eat("..") {
+                let selector = match self.peek() {
+                    Some('[') => self.parse_bracket()?,
+                    _ => self.parse_dot_selector()?,
+                };
+                segments.push(Segment::Descendant(Box::new(selector)));
+            } else if self.eat(".") {
+                segments.push(self.parse_dot_selector()?);
+            } else if self.peek() == Some('[') {
+                segments.push(self.parse_bracket()?);
+            } else {
+                return Ok(segments);
+            }
+        }
+    }
+
+    fn parse_dot_selector(&mut self) -> Result<Segment, QueryError> {
+        if self.eat("*") {
+            return Ok(Segment::Wildcard);
+        }
+        let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+        if name.is_empty() {
+            return Err(self.error("expected member name or '*'"));
+        }
+        Ok(Segment::Child(name.to_string()))
+    }
+
+    fn parse_bracket(&mut self) -> Result<Segment, QueryError> {
+        self.expect('[', "expected '['")?;
+        self.skip_ws();
+
+        let segment = match self.peek() {
+            Some('*') => {
+                self.bump();
+                Segment::Wildcard
+            }
+            Some('\'') | Some('"') => Segment::Child(self.parse_string()?),
+            Some('?') => {
+                self.bump();
+                self.skip_ws();
+                self.expect('(', "expected '(' after '?'")?;
+                let expr = self.parse_or()?;
+                self.skip_ws();
+                self.expect(')', "expected ')'")?;
+                Segment::Filter(expr)
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let start = self.pos;
+                self.bump();
+                self.take_while(|c| c.is_ascii_digit());
+                let index =
+                    self.src[start..self.pos]
+                        .parse::<i64>()
+                        .map_err(|_| QueryError::Syntax {
+                            offset: start,
+                            message: "invalid index",
+                        })?;
+                Segment::Index(index)
+            }
+            _ => return Err(self.error("expected index, name, '*' or filter")),
+        };
+
+        self.skip_ws();
+        self.expect(']', "expected ']'")?;
+        Ok(segment)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, QueryError> {
+        let mut lhs = self.parse_and()?;
+        loop {
+            self.skip_ws();
+            if !self.eat("||") {
+                return Ok(lhs);
+            }
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, QueryError> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            self.skip_ws();
+            if !self.eat("&&") {
+                return Ok(lhs);
+            }
+            let rhs = self.parse_unary()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    /// Every level of nesting passes through here, so this is where the
+    /// depth is limited.
+    fn parse_unary(&mut self) -> Result<Expr, QueryError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("expression nested too deeply"));
+        }
+        self.depth += 1;
+        let expr = self.parse_unary_inner();
+        self.depth -= 1;
+        expr
+    }
+
+    fn parse_unary_inner(&mut self) -> Result<Expr, QueryError> {
+        self.skip_ws();
+        if self.peek() == Some('!') && !self.src[self.pos..].starts_with("!=") {
+            self.bump();
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.eat("(") {
+            let expr = self.parse_or()?;
+            self.skip_ws();
+            self.expect(')', "expected ')'")?;
+            return Ok(expr);
+        }
+
+        let lhs = self.parse_operand()?;
+        self.skip_ws();
+        let op = if self.eat("==") {
+            CmpOp::Eq
+        } else if self.eat("!=") {
+            CmpOp::Ne
+        } else if self.eat("<=") {
+            CmpOp::Le
+        } else if self.eat(">=") {
+            CmpOp::Ge
+        } else if self.eat("<") {
+            CmpOp::Lt
+        } else if self.eat(">") {
+            CmpOp::Gt
+        } else {
+            return match lhs {
+                Operand::Literal(_) => Err(self.error("expected comparison operator")),
+                path => Ok(Expr::Exists(path)),
+            };
+        };
+        let rhs = self.parse_operand()?;
+        Ok(Expr::Compare(lhs, op, rhs))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, QueryError> {
+        self.skip_ws();
+        match self.peek() {
+            Some('@') => {
+                self.bump();
+                Ok(Operand::Current(self.parse_segments()?))
+            }
+            Some('$') => {
+                self.bump();
+                Ok(Operand::Root(self.parse_segments()?))
+            }
+            Some('\'') | Some('"') => Ok(Operand::Literal(Value::String(self.parse_string()?))),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) if c.is_ascii_alphabetic() => {
+                let start = self.pos;
+                match self.take_while(|c| c.is_ascii_alphabetic()) {
+                    "true" => Ok(Operand::Literal(Value::Bool(true))),
+                    "false" => Ok(Operand::Literal(Value::Bool(false))),
+                    "null" => Ok(Operand::Literal(Value::Null)),
+                    _ => Err(QueryError::Syntax {
+                        offset: start,
+                        message: "unknown literal",
+                    }),
+                }
+            }
+            _ => Err(self.error("expected path or literal")),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Operand, QueryError> {
+        let start = self.pos;
+        self.eat("-");
+        self.take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-'));
+        let text = &self.src[start..self.pos];
+        let invalid = QueryError::Syntax {
+            offset: start,
+            message: "invalid number",
+        };
+
+        if text
+            .trim_start_matches('-')
+            .bytes()
+            .all(|b| b.is_ascii_digit())
+        {
+            return text
+                .parse::<i64>()
+                .map(|v| Operand::Literal(Value::Int(v)))
+                .map_err(|_| invalid);
+        }
+        text.parse::<f64>()
+            .map(|v| Operand::Literal(Value::Float(v)))
+            .map_err(|_| invalid)
+    }
+
+    fn parse_string(&mut self) -> Result<String, QueryError> {
+        let start = self.pos;
+        let quote = self.bump().ok_or_else(|| self.error("expected string"))?;
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None => {
+                    return Err(QueryError::Syntax {
+                        offset: start,
+                        message: "unterminated string",
+                    })
+                }
+                Some(c) if c == quote => return Ok(out),
+                Some('\\') => match self.bump() {
+                    Some(c) if c == quote || c == '\\' => out.push(c),
+                    _ => return Err(self.error("invalid escape")),
+                },
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &str {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if pred(c)) {
+            self.bump();
+        }
+        &self.src[start..self.pos]
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::{
+    BatchDeserializer, BatchSerializer, DeserializeError, SerializeError, Token, TokenId,
+    TokenRegistry,
+};
+
+use super::resolver;
+use super::token_registry::RegistryError;
+
+/// A root token together with every token it strongly references, directly or
+/// transitively, for shipping as one artifact.
+///
+/// Bundles serialize with the batch layout, root first. Weakly referenced
+/// tokens are not included.
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    tokens: Vec<Arc<Token>>,
+}
+
+impl Bundle {
+    /// Gathers `root` and its strong-reference closure from `registry`. Fails
+    /// if a strongly referenced token is not registered, is a tombstone or is
+    /// not the version a pinned ref asks for, or the strong refs form a cycle.
+    pub fn from_registry(root: TokenId, registry: &TokenRegistry) -> Result<Self, RegistryError> {
+        let ids = resolver::strong_closure(root, |id| registry.get(id))?;
+        let tokens = ids
+            .into_iter()
+            .map(|id| registry.get(id).ok_or(RegistryError::NotFound(id)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { tokens })
+    }
+
+    pub fn root(&self) -> TokenId {
+        self.tokens[0].id()
+    }
+
+    /// The bundled tokens, root first.
+    pub fn tokens(&self) -> &[Arc<Token>] {
+        &self.tokens
+    }
+
+    pub fn serialize(&self) -> Result<Vec<u8>, SerializeError> {
+        BatchSerializer::new().serialize_tokens(&self.tokens)
+    }
+
+    /// Reads a bundle written by `serialize`. The first token is the root.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        let tokens = BatchDeserializer::new(bytes).deserialize()?;
+        if tokens.is_empty() {
+            return Err(DeserializeError::Truncated);
+        }
+        Ok(Self {
+            tokens: tokens.into_iter().map(Arc::new).collect(),
+        })
+    }
+
+    /// Registers every bundled token and returns the root's id.
+    ///
+    /// Each strong ref must resolve to a bundled token or one already in
+    /// `registry`, of the pinned version if it has a pin, and strong refs must
+    /// not form a cycle. This is checked before anything is registered, so a
+    /// failed import leaves `registry` unchanged.
+    pub fn register_into(&self, registry: &TokenRegistry) -> Result<TokenId, RegistryError> {
+        let bundled: HashMap<TokenId, &Arc<Token>> = self
+            .tokens
+            .iter()
+            .map(|token| (token.id(), token))
+            .collect();
+        let get = |id| {
+            bundled
+                .get(&id)
+                .map(|token| Arc::clone(token))
+                .or_else(|| registry.get(id))
+        };
+
+        let mut checked = HashSet::new();
+        for token in &self.tokens {
+            if !checked.contains(&token.id()) {
+                checked.extend(resolver::strong_closure(token.id(), get)?);
+            }
+        }
+
+        for token in &self.tokens {
+            registry.insert_shared(Arc::clone(token));
+        }
+        Ok(self.root())
+    }
+}
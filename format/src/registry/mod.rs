@@ -1,5 +1,7 @@
+mod bundle;
 mod cache;
 mod resolver;
 mod token_registry;
 
+pub use bundle::Bundle;
 pub use token_registry::{RegistryError, TokenRegistry};
@@ -21,19 +21,53 @@ where
         let _ = insert(loaded);
     }
 
-    let mut visiting: HashSet<TokenId> = HashSet::new();
-    let mut visited: HashSet<TokenId> = HashSet::new();
-    let mut stack: Vec<TokenId> = Vec::new();
+    let mut traversal = Traversal::new(true);
+    visit_token(root, &mut loader, &mut get, &mut insert, &mut traversal)
+}
 
+/// Returns `root` and every token reachable from it through strong refs, root
+/// first, failing if any is missing, deleted or not the version a pinned ref
+/// asks for, or a strong cycle is found. Weak refs are not followed.
+pub(crate) fn strong_closure<Get>(
+    root: TokenId,
+    mut get: Get,
+) -> Result<Vec<TokenId>, RegistryError>
+where
+    Get: FnMut(TokenId) -> Option<Arc<Token>>,
+{
+    let mut traversal = Traversal::new(false);
     visit_token(
         root,
-        &mut loader,
+        &mut |_| None,
         &mut get,
-        &mut insert,
-        &mut visiting,
-        &mut visited,
-        &mut stack,
-    )
+        &mut |token| Arc::new(token),
+        &mut traversal,
+    )?;
+
+    let mut order = traversal.order;
+    order.reverse();
+    Ok(order)
+}
+
+struct Traversal {
+    follow_weak: bool,
+    visiting: HashSet<TokenId>,
+    visited: HashSet<TokenId>,
+    stack: Vec<TokenId>,
+    /// Visited tokens in post-order.
+    order: Vec<TokenId>,
+}
+
+impl Traversal {
+    fn new(follow_weak: bool) -> Self {
+        Self {
+            follow_weak,
+            visiting: HashSet::new(),
+            visited: HashSet::new(),
+            stack: Vec::new(),
+            order: Vec::new(),
+        }
+    }
 }
 
 fn visit_token<F, Get, Insert>(
@@ -41,58 +75,73 @@ fn visit_token<F, Get, Insert>(
     loader: &mut F,
     get: &mut Get,
     insert: &mut Insert,
-    visiting: &mut HashSet<TokenId>,
-    visited: &mut HashSet<TokenId>,
-    stack: &mut Vec<TokenId>,
+    traversal: &mut Traversal,
 ) -> Result<(), RegistryError>
 where
     F: FnMut(TokenId) -> Option<Token>,
     Get: FnMut(TokenId) -> Option<Arc<Token>>,
     Insert: FnMut(Token) -> Arc<Token>,
 {
-    if visited.contains(&id) {
+    if traversal.visited.contains(&id) {
         return Ok(());
     }
 
-    if visiting.contains(&id) {
-        let mut cycle = stack.clone();
+    if traversal.visiting.contains(&id) {
+        let mut cycle = traversal.stack.clone();
         cycle.push(id);
         return Err(RegistryError::CircularReference(cycle));
     }
 
-    visiting.insert(id);
-    stack.push(id);
+    traversal.visiting.insert(id);
+    traversal.stack.push(id);
 
     let token = get(id).ok_or(RegistryError::NotFound(id))?;
+    if token.is_tombstone() {
+        return Err(RegistryError::Deleted(id));
+    }
     let mut refs: Vec<TokenRef> = Vec::new();
     collect_refs(token.value(), &mut refs);
 
     for r in refs {
         match r.strength() {
             TokenRefStrength::Strong => {
-                if get(r.id()).is_none() {
-                    let loaded = loader(r.id()).ok_or(RegistryError::NotFound(r.id()))?;
-                    let _ = insert(loaded);
+                let target = match get(r.id()) {
+                    Some(target) => target,
+                    None => insert(loader(r.id()).ok_or(RegistryError::NotFound(r.id()))?),
+                };
+                if !matches_pin(&r, &target) {
+                    return Err(RegistryError::VersionMismatch(r.id()));
                 }
-                visit_token(r.id(), loader, get, insert, visiting, visited, stack)?;
+                visit_token(r.id(), loader, get, insert, traversal)?;
             }
             TokenRefStrength::Weak => {
-                if get(r.id()).is_some() {
-                    visit_token(r.id(), loader, get, insert, visiting, visited, stack)?;
+                // Weak refs are only followed to the live, pinned version.
+                let live = get(r.id())
+                    .is_some_and(|token| !token.is_tombstone() && matches_pin(&r, &token));
+                if traversal.follow_weak && live {
+                    visit_token(r.id(), loader, get, insert, traversal)?;
                 }
             }
         }
     }
 
-    stack.pop();
-    visiting.remove(&id);
-    visited.insert(id);
+    traversal.stack.pop();
+    traversal.visiting.remove(&id);
+    traversal.visited.insert(id);
+    traversal.order.push(id);
     Ok(())
 }
 
+/// A token too large to serialize matches no pin.
+fn matches_pin(reference: &TokenRef, token: &Token) -> bool {
+    reference
+        .pin()
+        .is_none_or(|pin| token.checksum().ok() == Some(pin))
+}
+
 fn collect_refs(value: &Value, out: &mut Vec<TokenRef>) {
     match value {
-        Value::Ref(r) => out.push(*r),
+        Value::Ref(r) => out.push(r.clone()),
         Value::Array(items) => {
             for item in items {
                 collect_refs(item, out);
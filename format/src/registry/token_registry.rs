@@ -1,9 +1,10 @@
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use parking_lot::RwLock;
 use thiserror::Error;
 
-use crate::{Token, TokenId, TokenRef, TokenRefStrength};
+use crate::types::pointer_path;
+use crate::{SharedValue, Token, TokenId, TokenRef, TokenRefStrength, Value};
 
 use super::cache::LruCache;
 use super::resolver;
@@ -15,10 +16,55 @@ pub enum RegistryError {
 
     #[error("circular reference detected")]
     CircularReference(Vec<TokenId>),
+
+    #[error("fragment {1} not found in token {0:?}")]
+    FragmentNotFound(TokenId, String),
+
+    #[error("token {0:?} does not match the pinned version")]
+    VersionMismatch(TokenId),
+
+    #[error("token {0:?} was deleted")]
+    Deleted(TokenId),
 }
 
 pub struct TokenRegistry {
-    cache: RwLock<LruCache<TokenId, Arc<Token>>>,
+    cache: RwLock<LruCache<TokenId, Entry>>,
+}
+
+/// A registered token with its `SharedValue` form and checksum, each computed
+/// on first request.
+#[derive(Clone)]
+struct Entry {
+    token: Arc<Token>,
+    shared: Arc<OnceLock<SharedValue>>,
+    checksum: Arc<OnceLock<Option<u32>>>,
+}
+
+impl Entry {
+    /// Fails if the token is a tombstone: with `Deleted` for a strong
+    /// reference, and with `NotFound` for a weak one, which treats it as absent.
+    fn check_live(&self, reference: &TokenRef) -> Result<(), RegistryError> {
+        if !self.token.is_tombstone() {
+            return Ok(());
+        }
+        Err(match reference.strength() {
+            TokenRefStrength::Strong => RegistryError::Deleted(reference.id()),
+            TokenRefStrength::Weak => RegistryError::NotFound(reference.id()),
+        })
+    }
+
+    /// Fails with `VersionMismatch` if `reference` is pinned to a different
+    /// version of the token. A token too large to serialize matches no pin.
+    fn check_pin(&self, reference: &TokenRef) -> Result<(), RegistryError> {
+        let Some(pin) = reference.pin() else {
+            return Ok(());
+        };
+        let checksum = self.checksum.get_or_init(|| self.token.checksum().ok());
+        if *checksum != Some(pin) {
+            return Err(RegistryError::VersionMismatch(reference.id()));
+        }
+        Ok(())
+    }
 }
 
 impl TokenRegistry {
@@ -39,12 +85,49 @@ impl TokenRegistry {
     }
 
     pub fn get(&self, id: TokenId) -> Option<Arc<Token>> {
-        self.cache.write().get_cloned(&id)
+        self.cache.write().get_cloned(&id).map(|entry| entry.token)
     }
 
+    /// Returns the token's value as a `SharedValue`. It is converted once per
+    /// registration, after which this and cloning any of its subtrees is O(1).
+    pub fn get_shared(&self, id: TokenId) -> Option<SharedValue> {
+        let entry = self.cache.write().get_cloned(&id)?;
+        let shared = entry
+            .shared
+            .get_or_init(|| SharedValue::from(entry.token.value()));
+        Some(shared.clone())
+    }
+
+    /// Resolves `reference` to its target token, checking its pin if it has
+    /// one. A tombstoned target is `Deleted` for a strong reference and
+    /// `NotFound` for a weak one.
     pub fn resolve_ref(&self, reference: &TokenRef) -> Result<Arc<Token>, RegistryError> {
-        self.get(reference.id())
-            .ok_or(RegistryError::NotFound(reference.id()))
+        let entry = self
+            .cache
+            .write()
+            .get_cloned(&reference.id())
+            .ok_or(RegistryError::NotFound(reference.id()))?;
+        entry.check_live(reference)?;
+        entry.check_pin(reference)?;
+        Ok(entry.token)
+    }
+
+    /// Resolves `reference` to the value it points at: the target token's
+    /// value, or the sub-value selected by its fragment. A missing sub-value is
+    /// reported with the shortest prefix of the fragment that does not exist.
+    pub fn resolve_value(&self, reference: &TokenRef) -> Result<Value, RegistryError> {
+        let token = self.resolve_ref(reference)?;
+        let Some(fragment) = reference.fragment() else {
+            return Ok(token.value().clone());
+        };
+        let path = pointer_path(token.value(), fragment).map_err(|missing| {
+            RegistryError::FragmentNotFound(reference.id(), missing.to_string())
+        })?;
+        Ok(token
+            .value()
+            .get_path(&path)
+            .expect("resolved path exists")
+            .clone())
     }
 
     pub fn resolve_ref_or_load<F>(
@@ -55,8 +138,14 @@ impl TokenRegistry {
     where
         F: FnOnce(TokenId) -> Option<Token>,
     {
-        if let Some(token) = self.get(reference.id()) {
-            return Ok(Some(token));
+        let cached = self.cache.write().get_cloned(&reference.id());
+        if let Some(entry) = cached {
+            if entry.token.is_tombstone() && reference.strength() == TokenRefStrength::Weak {
+                return Ok(None);
+            }
+            entry.check_live(reference)?;
+            entry.check_pin(reference)?;
+            return Ok(Some(entry.token));
         }
 
         if reference.strength() == TokenRefStrength::Weak {
@@ -64,7 +153,10 @@ impl TokenRegistry {
         }
 
         let loaded = loader(reference.id()).ok_or(RegistryError::NotFound(reference.id()))?;
-        Ok(Some(self.insert(loaded)))
+        let entry = self.insert_entry(Arc::new(loaded));
+        entry.check_live(reference)?;
+        entry.check_pin(reference)?;
+        Ok(Some(entry.token))
     }
 
     pub fn ensure_loaded_and_acyclic<F>(
@@ -84,10 +176,21 @@ impl TokenRegistry {
     }
 
     fn insert(&self, token: Token) -> Arc<Token> {
-        let token = Arc::new(token);
-        let id = token.id();
-        self.cache.write().insert(id, Arc::clone(&token));
-        token
+        self.insert_shared(Arc::new(token))
+    }
+
+    pub(crate) fn insert_shared(&self, token: Arc<Token>) -> Arc<Token> {
+        self.insert_entry(token).token
+    }
+
+    fn insert_entry(&self, token: Arc<Token>) -> Entry {
+        let entry = Entry {
+            token,
+            shared: Arc::new(OnceLock::new()),
+            checksum: Arc::new(OnceLock::new()),
+        };
+        self.cache.write().insert(entry.token.id(), entry.clone());
+        entry
     }
 }
 
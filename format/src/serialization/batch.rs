@@ -0,0 +1,145 @@
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use crc32fast::Hasher;
+
+use crate::collections::HashMap;
+use crate::{constants, Token, Value};
+
+use super::encoder::encode_token;
+use super::serializer::SerializeError;
+use super::writer::ByteWriter;
+
+/// Serializes a set of tokens into one artifact with a shared string
+/// dictionary.
+///
+/// Layout: `BATCH_MAGIC`, version, dictionary (count, then length-prefixed
+/// UTF-8 entries), token count, then per token the id, type marker, payload
+/// length and payload, and a CRC32 of everything before it. The dictionary
+/// holds every object key, plus frequent string values when enabled; objects
+/// are written as `TYPE_OBJECT_DICT` and any string found in the dictionary
+/// as `TYPE_STRING_DICT`, both referring to entries by `u32` index.
+pub struct BatchSerializer {
+    min_value_occurrences: Option<usize>,
+}
+
+impl Default for BatchSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatchSerializer {
+    pub fn new() -> Self {
+        Self {
+            min_value_occurrences: None,
+        }
+    }
+
+    /// Also stores string values seen at least `min_occurrences` times across
+    /// the batch in the dictionary, when indexing them makes the batch smaller.
+    pub fn with_value_dictionary(mut self, min_occurrences: usize) -> Self {
+        self.min_value_occurrences = Some(min_occurrences.max(1));
+        self
+    }
+
+    pub fn serialize(&self, tokens: &[Token]) -> Result<Vec<u8>, SerializeError> {
+        self.serialize_tokens(tokens)
+    }
+
+    pub(crate) fn serialize_tokens<T: Borrow<Token>>(
+        &self,
+        tokens: &[T],
+    ) -> Result<Vec<u8>, SerializeError> {
+        let entries = self.build_dictionary(tokens);
+        let index: HashMap<&str, u32> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.as_str(), i as u32))
+            .collect();
+
+        let dictionary_len =
+            u32::try_from(entries.len()).map_err(|_| SerializeError::LengthOverflow)?;
+        let token_count =
+            u32::try_from(tokens.len()).map_err(|_| SerializeError::LengthOverflow)?;
+
+        let mut writer = ByteWriter::with_capacity(64);
+        writer.write_bytes(&constants::BATCH_MAGIC);
+        writer.write_u8(constants::FORMAT_VERSION);
+
+        writer.write_u32_le(dictionary_len);
+        for entry in &entries {
+            let len = u32::try_from(entry.len()).map_err(|_| SerializeError::LengthOverflow)?;
+            writer.write_u32_le(len);
+            writer.write_bytes(entry.as_bytes());
+        }
+
+        writer.write_u32_le(token_count);
+        for token in tokens {
+            let token = token.borrow();
+            let encoded = encode_token(token, Some(&index))?;
+            let payload_len =
+                u32::try_from(encoded.payload.len()).map_err(|_| SerializeError::LengthOverflow)?;
+
+            writer.write_bytes(token.id().as_bytes());
+            writer.write_u8(encoded.type_marker);
+            writer.write_u32_le(payload_len);
+            writer.write_bytes(&encoded.payload);
+        }
+
+        let mut hasher = Hasher::new();
+        hasher.update(writer.as_slice());
+        writer.write_u32_le(hasher.finalize());
+
+        Ok(writer.into_inner())
+    }
+
+    fn build_dictionary<T: Borrow<Token>>(&self, tokens: &[T]) -> Vec<String> {
+        let mut keys = BTreeSet::new();
+        let mut values = HashMap::new();
+        for token in tokens {
+            collect_strings(token.borrow().value(), &mut keys, &mut values);
+        }
+
+        if let Some(min) = self.min_value_occurrences {
+            keys.extend(
+                values
+                    .into_iter()
+                    .filter(|(s, count)| *count >= min && saves_space(s, *count))
+                    .map(|(s, _)| s),
+            );
+        }
+
+        keys.into_iter().map(str::to_string).collect()
+    }
+}
+
+fn collect_strings<'a>(
+    value: &'a Value,
+    keys: &mut BTreeSet<&'a str>,
+    values: &mut HashMap<&'a str, usize>,
+) {
+    match value {
+        Value::String(s) => *values.entry(s.as_str()).or_default() += 1,
+        Value::Array(items) => {
+            for item in items {
+                collect_strings(item, keys, values);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map {
+                keys.insert(key.as_str());
+                collect_strings(item, keys, values);
+            }
+        }
+        _ => {}
+    }
+}
+
+// An indexed string costs a 4-byte index per use plus one length-prefixed
+// dictionary entry; an inline string costs its bytes per use.
+fn saves_space(s: &str, count: usize) -> bool {
+    count.saturating_mul(s.len()) > count.saturating_mul(4).saturating_add(4 + s.len())
+}
@@ -1,4 +1,9 @@
-use crate::{constants, Value};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::collections::HashMap;
+use crate::types::sorted_entries;
+use crate::{constants, parallel, Metadata, MetadataFlags, Token, Value};
 
 use super::serializer::SerializeError;
 use super::writer::ByteWriter;
@@ -8,7 +13,92 @@ pub struct EncodedValue {
     pub payload: Vec<u8>,
 }
 
-pub fn encode_value(value: &Value) -> Result<EncodedValue, SerializeError> {
+/// Encodes the top-level payload of `token`: its value, or the deletion time
+/// of a tombstone, wrapped in a `TYPE_METADATA` section when the token has
+/// metadata beyond what the bare frame implies.
+pub(crate) fn encode_token(
+    token: &Token,
+    dictionary: Option<&HashMap<&str, u32>>,
+) -> Result<EncodedValue, SerializeError> {
+    let inner = match token.deleted_at_ms() {
+        Some(deleted_at_ms) => EncodedValue {
+            type_marker: constants::TYPE_TOMBSTONE,
+            payload: deleted_at_ms.to_le_bytes().to_vec(),
+        },
+        None => encode_value_with(token.value(), dictionary)?,
+    };
+    if !has_metadata_section(token) {
+        return Ok(inner);
+    }
+
+    let metadata = token.metadata();
+    let mut payload =
+        ByteWriter::with_capacity(metadata_section_len(metadata) + 1 + inner.payload.len());
+    payload.write_u32_le(metadata.flags.bits());
+    payload.write_u64_le(metadata.created_at_ms);
+    let count =
+        u32::try_from(metadata.attributes.len()).map_err(|_| SerializeError::LengthOverflow)?;
+    payload.write_u32_le(count);
+    for (key, value) in &metadata.attributes {
+        for s in [key, value] {
+            let len = u32::try_from(s.len()).map_err(|_| SerializeError::LengthOverflow)?;
+            payload.write_u32_le(len);
+            payload.write_bytes(s.as_bytes());
+        }
+    }
+    payload.write_u8(inner.type_marker);
+    payload.write_bytes(&inner.payload);
+
+    Ok(EncodedValue {
+        type_marker: constants::TYPE_METADATA,
+        payload: payload.into_inner(),
+    })
+}
+
+/// Length of `encode_token(token, None)`'s payload, computed without encoding.
+pub(crate) fn encoded_token_len(token: &Token) -> usize {
+    let inner = if token.is_tombstone() {
+        8
+    } else {
+        token.value().encoded_len()
+    };
+    if has_metadata_section(token) {
+        metadata_section_len(token.metadata()) + 1 + inner
+    } else {
+        inner
+    }
+}
+
+/// The section is only written for tokens with flags or attributes, so a
+/// token whose metadata is just a creation time keeps the bare frame it had
+/// before sections existed; that time is not serialized. A tombstone's
+/// deletion time and `TOMBSTONE` flag are implied by its bare frame.
+fn has_metadata_section(token: &Token) -> bool {
+    let metadata = token.metadata();
+    let implied = if token.is_tombstone() {
+        MetadataFlags::TOMBSTONE
+    } else {
+        MetadataFlags::empty()
+    };
+    metadata.flags != implied || !metadata.attributes.is_empty()
+}
+
+fn metadata_section_len(metadata: &Metadata) -> usize {
+    4 + 8
+        + 4
+        + metadata
+            .attributes
+            .iter()
+            .map(|(key, value)| 4 + key.len() + 4 + value.len())
+            .sum::<usize>()
+}
+
+/// Encodes `value`, replacing object keys and any string found in
+/// `dictionary` by its index. Every object key must be present.
+pub(crate) fn encode_value_with(
+    value: &Value,
+    dictionary: Option<&HashMap<&str, u32>>,
+) -> Result<EncodedValue, SerializeError> {
     match value {
         Value::Null => Ok(EncodedValue {
             type_marker: constants::TYPE_NULL,
@@ -38,18 +128,35 @@ pub fn encode_value(value: &Value) -> Result<EncodedValue, SerializeError> {
                 payload: payload.into_inner(),
             })
         }
-        Value::String(s) => Ok(EncodedValue {
-            type_marker: constants::TYPE_STRING,
-            payload: s.as_bytes().to_vec(),
-        }),
+        Value::String(s) => match dictionary.and_then(|d| d.get(s.as_str())) {
+            Some(index) => Ok(EncodedValue {
+                type_marker: constants::TYPE_STRING_DICT,
+                payload: index.to_le_bytes().to_vec(),
+            }),
+            None => Ok(EncodedValue {
+                type_marker: constants::TYPE_STRING,
+                payload: s.as_bytes().to_vec(),
+            }),
+        },
         Value::Ref(r) => {
-            let mut payload = ByteWriter::with_capacity(1 + 16);
-            let strength = match r.strength() {
+            let fragment = r.fragment().unwrap_or_default();
+            let mut payload = ByteWriter::with_capacity(1 + 16 + 4 + fragment.len());
+            let mut flags = match r.strength() {
                 crate::TokenRefStrength::Strong => 0u8,
-                crate::TokenRefStrength::Weak => 1u8,
+                crate::TokenRefStrength::Weak => constants::REF_WEAK,
             };
-            payload.write_u8(strength);
+            if r.pin().is_some() {
+                flags |= constants::REF_PINNED;
+            }
+            if r.fragment().is_some() {
+                flags |= constants::REF_FRAGMENT;
+            }
+            payload.write_u8(flags);
             payload.write_bytes(r.id().as_bytes());
+            if let Some(pin) = r.pin() {
+                payload.write_u32_le(pin);
+            }
+            payload.write_bytes(fragment.as_bytes());
 
             Ok(EncodedValue {
                 type_marker: constants::TYPE_REF,
@@ -57,30 +164,26 @@ pub fn encode_value(value: &Value) -> Result<EncodedValue, SerializeError> {
             })
         }
         Value::Array(items) => {
-            let mut encoded_items = Vec::with_capacity(items.len());
+            let encoded_items =
+                parallel::try_map(items, |item| encode_value_with(item, dictionary))?;
             let mut payload_len = 4usize;
 
-            for item in items {
-                let encoded = encode_value(item)?;
+            for encoded in &encoded_items {
                 let item_len_u32 = u32::try_from(encoded.payload.len())
                     .map_err(|_| SerializeError::LengthOverflow)?;
 
                 payload_len = payload_len
                     .checked_add(1 + 4 + item_len_u32 as usize)
                     .ok_or(SerializeError::LengthOverflow)?;
-
-                encoded_items.push((encoded.type_marker, encoded.payload));
             }
 
             let mut payload = ByteWriter::with_capacity(payload_len);
             payload.write_u32_le(items.len() as u32);
 
-            for (type_marker, item_payload) in encoded_items {
-                let item_len_u32 = u32::try_from(item_payload.len())
-                    .map_err(|_| SerializeError::LengthOverflow)?;
-                payload.write_u8(type_marker);
-                payload.write_u32_le(item_len_u32);
-                payload.write_bytes(&item_payload);
+            for encoded in encoded_items {
+                payload.write_u8(encoded.type_marker);
+                payload.write_u32_le(encoded.payload.len() as u32);
+                payload.write_bytes(&encoded.payload);
             }
 
             Ok(EncodedValue {
@@ -89,39 +192,36 @@ pub fn encode_value(value: &Value) -> Result<EncodedValue, SerializeError> {
             })
         }
         Value::Object(map) => {
-            let mut entries = Vec::with_capacity(map.len());
+            if let Some(dictionary) = dictionary {
+                return encode_object_with_dictionary(map, dictionary);
+            }
+
+            // Entries are written in key order so equal objects encode identically.
+            let entries = parallel::try_map(&sorted_entries(map), |(key, value)| {
+                encode_value_with(value, dictionary).map(|encoded| (key.as_bytes(), encoded))
+            })?;
             let mut payload_len = 4usize;
 
-            for (key, value) in map {
-                let key_bytes = key.as_bytes();
+            for (key_bytes, encoded) in &entries {
                 let key_len_u32 =
                     u32::try_from(key_bytes.len()).map_err(|_| SerializeError::LengthOverflow)?;
-
-                let encoded = encode_value(value)?;
                 let val_len_u32 = u32::try_from(encoded.payload.len())
                     .map_err(|_| SerializeError::LengthOverflow)?;
 
                 payload_len = payload_len
                     .checked_add(4 + key_len_u32 as usize + 1 + 4 + val_len_u32 as usize)
                     .ok_or(SerializeError::LengthOverflow)?;
-
-                entries.push((key_bytes.to_vec(), encoded.type_marker, encoded.payload));
             }
 
             let mut payload = ByteWriter::with_capacity(payload_len);
             payload.write_u32_le(map.len() as u32);
 
-            for (key_bytes, type_marker, val_payload) in entries {
-                let key_len_u32 =
-                    u32::try_from(key_bytes.len()).map_err(|_| SerializeError::LengthOverflow)?;
-                let val_len_u32 =
-                    u32::try_from(val_payload.len()).map_err(|_| SerializeError::LengthOverflow)?;
-
-                payload.write_u32_le(key_len_u32);
-                payload.write_bytes(&key_bytes);
-                payload.write_u8(type_marker);
-                payload.write_u32_le(val_len_u32);
-                payload.write_bytes(&val_payload);
+            for (key_bytes, encoded) in entries {
+                payload.write_u32_le(key_bytes.len() as u32);
+                payload.write_bytes(key_bytes);
+                payload.write_u8(encoded.type_marker);
+                payload.write_u32_le(encoded.payload.len() as u32);
+                payload.write_bytes(&encoded.payload);
             }
 
             Ok(EncodedValue {
@@ -131,3 +231,65 @@ pub fn encode_value(value: &Value) -> Result<EncodedValue, SerializeError> {
         }
     }
 }
+
+impl Value {
+    /// Length in bytes of this value's encoded payload, as written between a
+    /// token's header and checksum, computed without encoding it.
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            Value::Null | Value::Bool(_) => 0,
+            Value::Int(_) | Value::Float(_) => 8,
+            Value::String(s) => s.len(),
+            Value::Ref(r) => 1 + 16 + r.pin().map_or(0, |_| 4) + r.fragment().map_or(0, str::len),
+            Value::Array(items) => {
+                4 + items
+                    .iter()
+                    .map(|item| 1 + 4 + item.encoded_len())
+                    .sum::<usize>()
+            }
+            Value::Object(map) => {
+                4 + map
+                    .iter()
+                    .map(|(key, value)| 4 + key.len() + 1 + 4 + value.encoded_len())
+                    .sum::<usize>()
+            }
+        }
+    }
+}
+
+fn encode_object_with_dictionary(
+    map: &HashMap<String, Value>,
+    dictionary: &HashMap<&str, u32>,
+) -> Result<EncodedValue, SerializeError> {
+    let entries = parallel::try_map(&sorted_entries(map), |(key, value)| {
+        let key_index = *dictionary
+            .get(key.as_str())
+            .expect("batch dictionary contains every object key");
+        encode_value_with(value, Some(dictionary)).map(|encoded| (key_index, encoded))
+    })?;
+    let mut payload_len = 4usize;
+
+    for (_, encoded) in &entries {
+        let val_len_u32 =
+            u32::try_from(encoded.payload.len()).map_err(|_| SerializeError::LengthOverflow)?;
+
+        payload_len = payload_len
+            .checked_add(4 + 1 + 4 + val_len_u32 as usize)
+            .ok_or(SerializeError::LengthOverflow)?;
+    }
+
+    let mut payload = ByteWriter::with_capacity(payload_len);
+    payload.write_u32_le(map.len() as u32);
+
+    for (key_index, encoded) in entries {
+        payload.write_u32_le(key_index);
+        payload.write_u8(encoded.type_marker);
+        payload.write_u32_le(encoded.payload.len() as u32);
+        payload.write_bytes(&encoded.payload);
+    }
+
+    Ok(EncodedValue {
+        type_marker: constants::TYPE_OBJECT_DICT,
+        payload: payload.into_inner(),
+    })
+}
@@ -1,5 +1,7 @@
+mod batch;
 mod encoder;
 mod serializer;
 mod writer;
 
+pub use batch::BatchSerializer;
 pub use serializer::{SerializeError, Serializer};
@@ -1,15 +1,26 @@
+use alloc::vec::Vec;
+
 use crc32fast::Hasher;
 use thiserror::Error;
 
-use crate::{constants, Token};
+use crate::{constants, delta, fec, Token, TokenId};
 
-use super::encoder::encode_value;
+use super::encoder::{encode_token, encode_value_with, encoded_token_len};
 use super::writer::ByteWriter;
 
 #[derive(Debug, Error)]
 pub enum SerializeError {
     #[error("payload length does not fit in u32")]
     LengthOverflow,
+
+    #[error("no key available for key id {0}")]
+    MissingKey(u32),
+
+    #[error("payload encryption failed")]
+    EncryptionFailed,
+
+    #[error("parity length {0} is outside the supported range")]
+    InvalidParityLength(u8),
 }
 
 pub struct Serializer;
@@ -26,24 +37,109 @@ impl Serializer {
     }
 
     pub fn serialize(&self, token: &Token) -> Result<Vec<u8>, SerializeError> {
-        let encoded = encode_value(token.value())?;
-        let payload_len_u32 =
-            u32::try_from(encoded.payload.len()).map_err(|_| SerializeError::LengthOverflow)?;
+        let encoded = encode_token(token, None)?;
+        write_frame(token.id(), encoded.type_marker, &encoded.payload)
+    }
 
-        let total_len = 1usize + 16 + 1 + 4 + encoded.payload.len() + 4;
-        let mut writer = ByteWriter::with_capacity(total_len);
+    /// Exact length of `serialize(token)`'s output, computed without
+    /// allocating. Fails like `serialize` if the payload does not fit in u32.
+    pub fn encoded_len(&self, token: &Token) -> Result<usize, SerializeError> {
+        let payload_len = encoded_token_len(token);
+        u32::try_from(payload_len).map_err(|_| SerializeError::LengthOverflow)?;
+        Ok(1 + 16 + 1 + 4 + payload_len + 4)
+    }
 
-        writer.write_u8(constants::FORMAT_VERSION);
-        writer.write_bytes(token.id().as_bytes());
-        writer.write_u8(encoded.type_marker);
-        writer.write_u32_le(payload_len_u32);
-        writer.write_bytes(&encoded.payload);
+    /// Serializes `token` followed by a Reed-Solomon parity section of
+    /// `parity_len` bytes per 255-byte block (2 to 128), letting
+    /// `Deserializer::deserialize_repaired` correct up to `parity_len / 2`
+    /// corrupted bytes per block. `Deserializer::deserialize` reads the result
+    /// unchanged but does not repair it; the stream decoders do not accept it.
+    pub fn serialize_with_parity(
+        &self,
+        token: &Token,
+        parity_len: u8,
+    ) -> Result<Vec<u8>, SerializeError> {
+        if !(fec::MIN_PARITY..=fec::MAX_PARITY).contains(&parity_len) {
+            return Err(SerializeError::InvalidParityLength(parity_len));
+        }
+        let mut frame = self.serialize(token)?;
+        let parity = fec::encode(&frame, parity_len);
+        frame.extend_from_slice(&parity);
+        Ok(frame)
+    }
+
+    /// Serializes `target` as a delta against `base`: the frame carries the
+    /// base id and checksum plus the instructions that rebuild the target's
+    /// payload from the base's. Decode with `Deserializer::deserialize_with_base`.
+    pub fn serialize_delta(&self, base: &Token, target: &Token) -> Result<Vec<u8>, SerializeError> {
+        let base_frame = self.serialize(base)?;
+        let (base_body, base_checksum) = base_frame.split_at(base_frame.len() - 4);
+        let encoded = encode_token(target, None)?;
 
-        let checksum = crc32(writer.as_slice());
-        writer.write_u32_le(checksum);
+        let instructions = delta::compute(&base_body[22..], &encoded.payload);
+        let mut payload = Vec::with_capacity(16 + 4 + 1 + instructions.len());
+        payload.extend_from_slice(base.id().as_bytes());
+        payload.extend_from_slice(base_checksum);
+        payload.push(encoded.type_marker);
+        payload.extend_from_slice(&instructions);
 
-        Ok(writer.into_inner())
+        write_frame(target.id(), constants::TYPE_DELTA, &payload)
     }
+
+    #[cfg(feature = "encryption")]
+    pub fn serialize_encrypted<P>(
+        &self,
+        token: &Token,
+        provider: &P,
+    ) -> Result<Vec<u8>, SerializeError>
+    where
+        P: crate::encryption::KeyProvider + ?Sized,
+    {
+        let encoded = encode_token(token, None)?;
+
+        let mut header = [0u8; 1 + 16 + 1];
+        header[0] = constants::FORMAT_VERSION;
+        header[1..17].copy_from_slice(token.id().as_bytes());
+        header[17] = constants::TYPE_ENCRYPTED;
+
+        let envelope =
+            crate::encryption::seal(provider, &header, encoded.type_marker, &encoded.payload)?;
+        write_frame(token.id(), constants::TYPE_ENCRYPTED, &envelope)
+    }
+}
+
+impl Token {
+    /// CRC32 of the value's type marker and encoded payload, which identifies
+    /// this version of the value. Used to pin references with
+    /// `TokenRef::pinned`. Neither the id nor the metadata is covered, so
+    /// re-stamping a token keeps pins to it valid. Like the frame checksum it
+    /// detects accidental changes only and is not a cryptographic hash.
+    pub fn checksum(&self) -> Result<u32, SerializeError> {
+        let encoded = encode_value_with(self.value(), None)?;
+        let mut hasher = Hasher::new();
+        hasher.update(&[encoded.type_marker]);
+        hasher.update(&encoded.payload);
+        Ok(hasher.finalize())
+    }
+}
+
+fn write_frame(id: TokenId, type_marker: u8, payload: &[u8]) -> Result<Vec<u8>, SerializeError> {
+    let payload_len_u32 =
+        u32::try_from(payload.len()).map_err(|_| SerializeError::LengthOverflow)?;
+
+    let total_len = 1usize + 16 + 1 + 4 + payload.len() + 4;
+    let mut writer = ByteWriter::with_capacity(total_len);
+
+    writer.write_u8(constants::FORMAT_VERSION);
+    writer.write_bytes(id.as_bytes());
+    writer.write_u8(type_marker);
+    writer.write_u32_le(payload_len_u32);
+    writer.write_bytes(payload);
+
+    let checksum = crc32(writer.as_slice());
+    writer.write_u32_le(checksum);
+
+    Ok(writer.into_inner())
 }
 
 fn crc32(bytes: &[u8]) -> u32 {
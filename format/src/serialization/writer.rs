@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 pub struct ByteWriter {
     buf: Vec<u8>,
 }
@@ -25,6 +27,10 @@ impl ByteWriter {
         self.buf.extend_from_slice(&value.to_le_bytes());
     }
 
+    pub fn write_u64_le(&mut self, value: u64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
     pub fn write_i64_le(&mut self, value: i64) {
         self.buf.extend_from_slice(&value.to_le_bytes());
     }
@@ -9,6 +9,28 @@ pub const TYPE_BOOL_TRUE: u8 = 0x02;
 pub const TYPE_INT64: u8 = 0x10;
 pub const TYPE_F64: u8 = 0x11;
 pub const TYPE_STRING: u8 = 0x20;
+pub const TYPE_STRING_DICT: u8 = 0x21;
 pub const TYPE_ARRAY: u8 = 0x30;
 pub const TYPE_OBJECT: u8 = 0x31;
+pub const TYPE_OBJECT_DICT: u8 = 0x32;
 pub const TYPE_REF: u8 = 0x40;
+pub const TYPE_ENCRYPTED: u8 = 0x50;
+pub const TYPE_DELTA: u8 = 0x51;
+/// A deleted token. Only valid as a frame's type; the payload is the deletion
+/// time in milliseconds since the Unix epoch (u64).
+pub const TYPE_TOMBSTONE: u8 = 0x52;
+/// A token with metadata. Only valid as a frame's type; the payload is the
+/// flags (u32), creation time (u64), attribute count (u32) and each attribute
+/// as a length-prefixed UTF-8 key and value in ascending key order, followed
+/// by the inner type marker and payload. Written only for tokens with flags or
+/// attributes, so other frames are unchanged by it.
+pub const TYPE_METADATA: u8 = 0x53;
+
+pub const BATCH_MAGIC: [u8; 4] = *b"TNBT";
+
+/// Flag bits of the first byte of a `TYPE_REF` payload. The id follows, then
+/// with `REF_PINNED` the target's `Token::checksum` (u32), then with `REF_FRAGMENT` a
+/// non-empty UTF-8 JSON Pointer fragment.
+pub const REF_WEAK: u8 = 0x01;
+pub const REF_PINNED: u8 = 0x40;
+pub const REF_FRAGMENT: u8 = 0x80;
@@ -0,0 +1,149 @@
+//! `arbitrary::Arbitrary` impls for the core types.
+
+use std::collections::BTreeMap;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use uuid::Uuid;
+
+use crate::collections::HashMap;
+use crate::{Metadata, MetadataFlags, Token, TokenId, TokenRef, Value};
+
+use super::{
+    build_graph, graph_ids, make_ref, make_tombstone, serializable, GraphConfig, ValueConfig,
+};
+
+impl<'a> Arbitrary<'a> for TokenId {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(TokenId::from(Uuid::from_bytes(u.arbitrary()?)))
+    }
+}
+
+impl<'a> Arbitrary<'a> for TokenRef {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(make_ref(
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+        ))
+    }
+}
+
+impl<'a> Arbitrary<'a> for MetadataFlags {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(MetadataFlags::from_bits_retain(u.arbitrary()?))
+    }
+}
+
+/// The creation time is only kept when it survives serialization.
+impl<'a> Arbitrary<'a> for Metadata {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(serializable(Metadata {
+            created_at_ms: u.arbitrary()?,
+            flags: MetadataFlags::arbitrary(u)?,
+            attributes: BTreeMap::arbitrary(u)?,
+        }))
+    }
+}
+
+/// Bounded by `ValueConfig::default()`; use `arbitrary_value` for others.
+impl<'a> Arbitrary<'a> for Value {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_value(u, &ValueConfig::default())
+    }
+}
+
+/// About one in ten is a tombstone.
+impl<'a> Arbitrary<'a> for Token {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let id = u.arbitrary()?;
+        if u.ratio(1u8, 10)? {
+            return Ok(make_tombstone(id, u.arbitrary()?, u.arbitrary()?));
+        }
+        Ok(Token::new(id, u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+/// A value bounded by `config`, with references to random ids.
+pub fn arbitrary_value(u: &mut Unstructured<'_>, config: &ValueConfig) -> Result<Value> {
+    arbitrary_value_with(u, config, None)
+}
+
+/// Like `arbitrary_value`, but references point only at `targets`: strong or
+/// weak, without pin or fragment. With no targets no references are made.
+pub fn arbitrary_value_with(
+    u: &mut Unstructured<'_>,
+    config: &ValueConfig,
+    targets: Option<&[TokenId]>,
+) -> Result<Value> {
+    let mut gen = ValueGen {
+        config,
+        targets,
+        nodes: 0,
+    };
+    gen.value(u, 0)
+}
+
+/// A token graph shaped by `config`, root first. See `GraphConfig`.
+pub fn arbitrary_graph(u: &mut Unstructured<'_>, config: &GraphConfig) -> Result<Vec<Token>> {
+    let ids = graph_ids(u.arbitrary()?, config.tokens);
+    let values = (0..ids.len())
+        .map(|i| arbitrary_value_with(u, &config.value, Some(&ids[i + 1..])))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(build_graph(&ids, values, config.cycle))
+}
+
+struct ValueGen<'c> {
+    config: &'c ValueConfig,
+    targets: Option<&'c [TokenId]>,
+    nodes: u32,
+}
+
+impl ValueGen<'_> {
+    fn value(&mut self, u: &mut Unstructured<'_>, depth: u32) -> Result<Value> {
+        self.nodes += 1;
+
+        let weight = self.config.ref_weight();
+        let can_ref = weight > 0 && self.targets.is_none_or(|targets| !targets.is_empty());
+        if can_ref && u.ratio(weight, 1000)? {
+            return self.reference(u).map(Value::Ref);
+        }
+
+        let nested = depth < self.config.max_depth && self.nodes < self.config.max_nodes;
+        let kinds = if nested { 7 } else { 5 };
+        Ok(match u.choose_index(kinds)? {
+            0 => Value::Null,
+            1 => Value::Bool(u.arbitrary()?),
+            2 => Value::Int(u.arbitrary()?),
+            3 => Value::Float(u.arbitrary()?),
+            4 => Value::String(u.arbitrary()?),
+            5 => {
+                let len = u.int_in_range(0..=self.config.max_len)?;
+                let mut items = Vec::with_capacity(len);
+                while items.len() < len && self.nodes < self.config.max_nodes {
+                    items.push(self.value(u, depth + 1)?);
+                }
+                Value::Array(items)
+            }
+            _ => {
+                let len = u.int_in_range(0..=self.config.max_len)?;
+                let mut entries = HashMap::with_capacity(len);
+                for _ in 0..len {
+                    if self.nodes >= self.config.max_nodes {
+                        break;
+                    }
+                    let key: String = u.arbitrary()?;
+                    entries.insert(key, self.value(u, depth + 1)?);
+                }
+                Value::Object(entries)
+            }
+        })
+    }
+
+    fn reference(&mut self, u: &mut Unstructured<'_>) -> Result<TokenRef> {
+        match self.targets {
+            Some(targets) => Ok(make_ref(*u.choose(targets)?, u.arbitrary()?, None, None)),
+            None => u.arbitrary(),
+        }
+    }
+}
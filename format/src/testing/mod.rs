@@ -0,0 +1,140 @@
+//! Generators for property tests and fuzzing: `proptest` strategies in
+//! `strategy` (feature `proptest`) and `arbitrary::Arbitrary` impls for the
+//! core types (feature `arbitrary`).
+//!
+//! Both are shaped by `ValueConfig`, and both can generate graphs of tokens
+//! whose references stay inside the graph. A graph is acyclic unless
+//! `GraphConfig::cycle` is set, so the outcome of cycle detection is known in
+//! advance.
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+
+#[cfg(feature = "arbitrary")]
+pub use self::arbitrary::{arbitrary_graph, arbitrary_value, arbitrary_value_with};
+
+use uuid::Uuid;
+
+use crate::{Metadata, Token, TokenId, TokenRef, Value};
+
+/// Bounds for generated values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValueConfig {
+    /// Maximum nesting of arrays and objects; 0 generates only scalars.
+    pub max_depth: u32,
+    /// Maximum number of elements in one array or object.
+    pub max_len: usize,
+    /// Target number of nodes in one value. Containers stop growing once it
+    /// is reached.
+    pub max_nodes: u32,
+    /// Probability, from 0.0 to 1.0, that a leaf is a reference.
+    pub ref_density: f64,
+}
+
+impl Default for ValueConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 4,
+            max_len: 8,
+            max_nodes: 64,
+            ref_density: 0.1,
+        }
+    }
+}
+
+impl ValueConfig {
+    /// `ref_density` in thousandths, clamped to `0..=1000`.
+    fn ref_weight(&self) -> u32 {
+        (self.ref_density.clamp(0.0, 1.0) * 1000.0).round() as u32
+    }
+}
+
+/// Shape of a generated token graph.
+///
+/// Tokens come root first and references point only at later tokens, so the
+/// graph is acyclic. With `cycle` set, the root also holds a strong reference
+/// to the last token and the last token one back to the root: that is the
+/// graph's only back edge, so every cycle passes through it. Graph references
+/// carry no pin or fragment, so they resolve in a `TokenRegistry`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GraphConfig {
+    /// Number of tokens, at least 1.
+    pub tokens: usize,
+    pub cycle: bool,
+    pub value: ValueConfig,
+}
+
+impl Default for GraphConfig {
+    fn default() -> Self {
+        Self {
+            tokens: 8,
+            cycle: false,
+            value: ValueConfig {
+                ref_density: 0.3,
+                ..ValueConfig::default()
+            },
+        }
+    }
+}
+
+/// `count` distinct ids starting at `base`.
+fn graph_ids(base: u128, count: usize) -> Vec<TokenId> {
+    (0..count.max(1))
+        .map(|i| TokenId::from(Uuid::from_u128(base.wrapping_add(i as u128))))
+        .collect()
+}
+
+/// Pairs `ids` with `values`, closing the cycle if requested.
+fn build_graph(ids: &[TokenId], mut values: Vec<Value>, cycle: bool) -> Vec<Token> {
+    if cycle {
+        let last = values.len() - 1;
+        let close = |value: &mut Value, target: TokenId| {
+            let inner = core::mem::replace(value, Value::Null);
+            *value = Value::Array(vec![inner, Value::Ref(TokenRef::strong(target))]);
+        };
+        close(&mut values[0], ids[last]);
+        if last != 0 {
+            close(&mut values[last], ids[0]);
+        }
+    }
+    ids.iter()
+        .zip(values)
+        .map(|(&id, value)| Token::new(id, value, Metadata::new(0, 0)))
+        .collect()
+}
+
+fn make_ref(id: TokenId, weak: bool, pin: Option<u32>, fragment: Option<String>) -> TokenRef {
+    let mut r = if weak {
+        TokenRef::weak(id)
+    } else {
+        TokenRef::strong(id)
+    };
+    if let Some(pin) = pin {
+        r = r.pinned(pin);
+    }
+    if let Some(fragment) = fragment {
+        r = r.with_fragment(fragment);
+    }
+    r
+}
+
+/// Drops the creation time of metadata without flags or attributes, which
+/// is not serialized.
+fn serializable(metadata: Metadata) -> Metadata {
+    if metadata.flags.is_empty() && metadata.attributes.is_empty() {
+        Metadata::new(0, 0)
+    } else {
+        metadata
+    }
+}
+
+/// A tombstone carrying `metadata`'s attributes and flags.
+fn make_tombstone(id: TokenId, deleted_at_ms: u64, metadata: Metadata) -> Token {
+    let metadata = Metadata {
+        created_at_ms: deleted_at_ms,
+        ..metadata
+    };
+    Token::tombstone_with_metadata(id, metadata)
+}
@@ -0,0 +1,122 @@
+//! `proptest` strategies for the core types.
+
+use proptest::collection::{btree_map, hash_map, vec};
+use proptest::prelude::*;
+use proptest::sample::select;
+use proptest::string::string_regex;
+use uuid::Uuid;
+
+use crate::{Metadata, MetadataFlags, Token, TokenId, TokenRef, Value};
+
+use super::{
+    build_graph, graph_ids, make_ref, make_tombstone, serializable, GraphConfig, ValueConfig,
+};
+
+pub fn token_id() -> impl Strategy<Value = TokenId> {
+    any::<[u8; 16]>().prop_map(|bytes| TokenId::from(Uuid::from_bytes(bytes)))
+}
+
+/// References to random ids, with random strength, pin and fragment.
+pub fn token_ref() -> impl Strategy<Value = TokenRef> {
+    (
+        token_id(),
+        any::<bool>(),
+        proptest::option::of(any::<u32>()),
+        proptest::option::of(string_regex(r"(/[a-z0-9~]{0,8}){1,3}").unwrap()),
+    )
+        .prop_map(|(id, weak, pin, fragment)| make_ref(id, weak, pin, fragment))
+}
+
+/// Values bounded by `config`, with references to random ids.
+pub fn value(config: ValueConfig) -> BoxedStrategy<Value> {
+    value_with_refs(config, Some(token_ref().boxed()))
+}
+
+/// Metadata with any flags and up to four attributes, and a creation time
+/// only when it survives serialization.
+pub fn metadata() -> impl Strategy<Value = Metadata> {
+    (
+        any::<u64>(),
+        any::<u32>(),
+        btree_map(key(), string_regex(r"[ -~]{0,16}").unwrap(), 0..4),
+    )
+        .prop_map(|(created_at_ms, flags, attributes)| {
+            serializable(Metadata {
+                created_at_ms,
+                flags: MetadataFlags::from_bits_retain(flags),
+                attributes,
+            })
+        })
+}
+
+/// Tokens with values bounded by `config`; about one in ten is a tombstone.
+pub fn token(config: ValueConfig) -> impl Strategy<Value = Token> {
+    prop_oneof![
+        9 => (token_id(), value(config), metadata())
+            .prop_map(|(id, value, metadata)| Token::new(id, value, metadata)),
+        1 => (token_id(), any::<u64>(), metadata())
+            .prop_map(|(id, deleted_at_ms, metadata)| make_tombstone(id, deleted_at_ms, metadata)),
+    ]
+}
+
+/// Token graphs shaped by `config`, root first. See `GraphConfig`.
+pub fn token_graph(config: GraphConfig) -> impl Strategy<Value = Vec<Token>> {
+    any::<u128>().prop_flat_map(move |base| {
+        let ids = graph_ids(base, config.tokens);
+        let values: Vec<_> = (0..ids.len())
+            .map(|i| {
+                let targets = &ids[i + 1..];
+                let refs = (!targets.is_empty()).then(|| {
+                    (select(targets.to_vec()), any::<bool>())
+                        .prop_map(|(id, weak)| make_ref(id, weak, None, None))
+                        .boxed()
+                });
+                value_with_refs(config.value, refs)
+            })
+            .collect();
+        values.prop_map(move |values| build_graph(&ids, values, config.cycle))
+    })
+}
+
+fn value_with_refs(
+    config: ValueConfig,
+    refs: Option<BoxedStrategy<TokenRef>>,
+) -> BoxedStrategy<Value> {
+    let scalar = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(Value::Int),
+        any::<f64>().prop_map(Value::Float),
+        string_regex(r"[ -~]{0,32}")
+            .unwrap()
+            .prop_map(Value::String),
+    ];
+    let leaf = match (refs, config.ref_weight()) {
+        (Some(refs), weight) if weight >= 1000 => refs.prop_map(Value::Ref).boxed(),
+        (Some(refs), weight) if weight > 0 => prop_oneof![
+            1000 - weight => scalar,
+            weight => refs.prop_map(Value::Ref),
+        ]
+        .boxed(),
+        _ => scalar.boxed(),
+    };
+
+    let max_len = config.max_len;
+    leaf.prop_recursive(
+        config.max_depth,
+        config.max_nodes,
+        max_len.max(1) as u32,
+        move |inner| {
+            prop_oneof![
+                vec(inner.clone(), 0..=max_len).prop_map(Value::Array),
+                hash_map(key(), inner, 0..=max_len)
+                    .prop_map(|entries| Value::Object(entries.into_iter().collect())),
+            ]
+        },
+    )
+    .boxed()
+}
+
+fn key() -> impl Strategy<Value = String> {
+    string_regex(r"[a-zA-Z0-9_]{0,16}").unwrap()
+}
@@ -0,0 +1,5 @@
+mod parser;
+mod printer;
+
+pub use parser::{parse, ParseError, ParseErrorKind, MAX_DEPTH};
+pub use printer::{to_string, to_string_pretty};
@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{TokenId, TokenRef, Value};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{line}:{column}: {kind}")]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseErrorKind {
+    #[error("unexpected end of input")]
+    UnexpectedEnd,
+
+    #[error("unexpected character {0:?}")]
+    UnexpectedChar(char),
+
+    #[error("invalid number")]
+    InvalidNumber,
+
+    #[error("integer out of range")]
+    IntegerOverflow,
+
+    #[error("invalid escape sequence")]
+    InvalidEscape,
+
+    #[error("unterminated string")]
+    UnterminatedString,
+
+    #[error("unterminated comment")]
+    UnterminatedComment,
+
+    #[error("invalid reference")]
+    InvalidReference,
+
+    #[error("duplicate object key {0:?}")]
+    DuplicateKey(String),
+
+    #[error("trailing characters after value")]
+    TrailingCharacters,
+
+    #[error("arrays and objects nested more than {MAX_DEPTH} deep")]
+    TooDeep,
+}
+
+/// Deepest nesting of arrays and objects that `parse` accepts.
+pub const MAX_DEPTH: usize = 128;
+
+/// Parses a single value in text notation.
+///
+/// The syntax is JSON with a few additions: integers and floats are distinct
+/// (`1` vs `1.0`), `nan`, `inf` and `-inf` are floats, references are written
+/// `&strong:<uuid>` or `&weak:<uuid>`, optionally followed by a pin
+/// (`@<8 hex digits>`) and a fragment (`#"<pointer>"`), trailing commas are
+/// allowed, and `//` and `/* */` comments may appear anywhere whitespace can.
+/// Arrays and objects may be nested at most `MAX_DEPTH` deep.
+pub fn parse(input: &str) -> Result<Value, ParseError> {
+    let mut parser = Parser::new(input);
+    parser.skip_trivia()?;
+    let value = parser.parse_value()?;
+    parser.skip_trivia()?;
+    if parser.peek().is_some() {
+        return Err(parser.error(ParseErrorKind::TrailingCharacters));
+    }
+    Ok(value)
+}
+
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    line: usize,
+    column: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            chars: input.char_indices().peekable(),
+            line: 1,
+            column: 1,
+            depth: 0,
+        }
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            kind,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn offset(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map(|&(i, _)| i)
+            .unwrap_or(self.input.len())
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let (_, c) = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.bump();
+                Ok(())
+            }
+            Some(c) => Err(self.error(ParseErrorKind::UnexpectedChar(c))),
+            None => Err(self.error(ParseErrorKind::UnexpectedEnd)),
+        }
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), ParseError> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('/') => {
+                    let start = self.error(ParseErrorKind::UnterminatedComment);
+                    self.bump();
+                    match self.bump() {
+                        Some('/') => {
+                            while let Some(c) = self.bump() {
+                                if c == '\n' {
+                                    break;
+                                }
+                            }
+                        }
+                        Some('*') => loop {
+                            match self.bump() {
+                                Some('*') if self.peek() == Some('/') => {
+                                    self.bump();
+                                    break;
+                                }
+                                Some(_) => {}
+                                None => return Err(start),
+                            }
+                        },
+                        Some(c) => return Err(self.error(ParseErrorKind::UnexpectedChar(c))),
+                        None => return Err(self.error(ParseErrorKind::UnexpectedEnd)),
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, ParseError> {
+        match self.peek() {
+            None => Err(self.error(ParseErrorKind::UnexpectedEnd)),
+            Some('{') => self.nested(Self::parse_object),
+            Some('[') => self.nested(Self::parse_array),
+            Some('"') => self.parse_string().map(Value::String),
+            Some('&') => self.parse_ref(),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) if c.is_ascii_alphabetic() => self.parse_keyword(),
+            Some(c) => Err(self.error(ParseErrorKind::UnexpectedChar(c))),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Value, ParseError>,
+    ) -> Result<Value, ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(ParseErrorKind::TooDeep));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn parse_keyword(&mut self) -> Result<Value, ParseError> {
+        let start = self.error(ParseErrorKind::UnexpectedEnd);
+        let word = self.take_while(|c| c.is_ascii_alphanumeric());
+        match word {
+            "null" => Ok(Value::Null),
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            "nan" => Ok(Value::Float(f64::NAN)),
+            "inf" => Ok(Value::Float(f64::INFINITY)),
+            _ => {
+                let c = word.chars().next().unwrap_or_default();
+                Err(ParseError {
+                    kind: ParseErrorKind::UnexpectedChar(c),
+                    ..start
+                })
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value, ParseError> {
+        let start = self.error(ParseErrorKind::InvalidNumber);
+        let text = self.take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.'));
+
+        if text == "-inf" {
+            return Ok(Value::Float(f64::NEG_INFINITY));
+        }
+
+        let digits = text.strip_prefix('-').unwrap_or(text);
+        if !digits.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(start);
+        }
+
+        if digits.bytes().all(|b| b.is_ascii_digit()) {
+            return text.parse::<i64>().map(Value::Int).map_err(|_| ParseError {
+                kind: ParseErrorKind::IntegerOverflow,
+                ..start
+            });
+        }
+
+        text.parse::<f64>().map(Value::Float).map_err(|_| start)
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        let start = self.error(ParseErrorKind::UnterminatedString);
+        self.expect('"')?;
+
+        let mut out = String::new();
+        loop {
+            let escape_at = self.error(ParseErrorKind::InvalidEscape);
+            match self.bump() {
+                None => return Err(start),
+                Some('"') => return Ok(out),
+                Some('\\') => {
+                    let c = match self.bump() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('u') => self.parse_unicode_escape().ok_or(escape_at)?,
+                        _ => return Err(escape_at),
+                    };
+                    out.push(c);
+                }
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn parse_unicode_escape(&mut self) -> Option<char> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high);
+        }
+        if self.bump()? != '\\' || self.bump()? != 'u' {
+            return None;
+        }
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return None;
+        }
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let mut v = 0;
+        for _ in 0..4 {
+            v = v * 16 + self.bump()?.to_digit(16)?;
+        }
+        Some(v)
+    }
+
+    fn parse_ref(&mut self) -> Result<Value, ParseError> {
+        let start = self.error(ParseErrorKind::InvalidReference);
+        self.expect('&')?;
+
+        let strength = self.take_while(|c| c.is_ascii_alphabetic());
+        let make: fn(TokenId) -> TokenRef = match strength {
+            "strong" => TokenRef::strong,
+            "weak" => TokenRef::weak,
+            _ => return Err(start),
+        };
+        if self.peek() != Some(':') {
+            return Err(start);
+        }
+        self.bump();
+
+        let text = self.take_while(|c| c.is_ascii_hexdigit() || c == '-');
+        let uuid = Uuid::parse_str(text).map_err(|_| start.clone())?;
+        let mut reference = make(TokenId::from(uuid));
+
+        if self.peek() == Some('@') {
+            self.bump();
+            let pin = self.take_while(|c| c.is_ascii_hexdigit());
+            if pin.len() != 8 {
+                return Err(start);
+            }
+            reference = reference.pinned(u32::from_str_radix(pin, 16).expect("eight hex digits"));
+        }
+
+        if self.peek() != Some('#') {
+            return Ok(Value::Ref(reference));
+        }
+        self.bump();
+        let fragment = self.parse_string()?;
+        Ok(Value::Ref(reference.with_fragment(fragment)))
+    }
+
+    fn parse_array(&mut self) -> Result<Value, ParseError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(']') {
+                self.bump();
+                return Ok(Value::Array(items));
+            }
+
+            items.push(self.parse_value()?);
+
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(']') => {}
+                Some(c) => return Err(self.error(ParseErrorKind::UnexpectedChar(c))),
+                None => return Err(self.error(ParseErrorKind::UnexpectedEnd)),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value, ParseError> {
+        self.expect('{')?;
+        let mut map = HashMap::new();
+
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some('}') {
+                self.bump();
+                return Ok(Value::Object(map));
+            }
+
+            let key_at = self.error(ParseErrorKind::UnexpectedEnd);
+            let key = self.parse_string()?;
+            self.skip_trivia()?;
+            self.expect(':')?;
+            self.skip_trivia()?;
+            let value = self.parse_value()?;
+
+            if map.contains_key(&key) {
+                return Err(ParseError {
+                    kind: ParseErrorKind::DuplicateKey(key),
+                    ..key_at
+                });
+            }
+            map.insert(key, value);
+
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some('}') => {}
+                Some(c) => return Err(self.error(ParseErrorKind::UnexpectedChar(c))),
+                None => return Err(self.error(ParseErrorKind::UnexpectedEnd)),
+            }
+        }
+    }
+
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &'a str {
+        let start = self.offset();
+        while matches!(self.peek(), Some(c) if pred(c)) {
+            self.bump();
+        }
+        let end = self.offset();
+        &self.input[start..end]
+    }
+}
@@ -0,0 +1,126 @@
+use std::fmt::{self, Write};
+
+use uuid::Uuid;
+
+use crate::types::sorted_entries;
+use crate::{TokenRefStrength, Value};
+
+pub fn to_string(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, None, 0).expect("writing to a String cannot fail");
+    out
+}
+
+pub fn to_string_pretty(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, Some(2), 0).expect("writing to a String cannot fail");
+    out
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let indent = if f.alternate() { Some(2) } else { None };
+        write_value(f, self, indent, 0)
+    }
+}
+
+fn write_value<W: Write>(
+    out: &mut W,
+    value: &Value,
+    indent: Option<usize>,
+    depth: usize,
+) -> fmt::Result {
+    match value {
+        Value::Null => out.write_str("null"),
+        Value::Bool(b) => write!(out, "{b}"),
+        Value::Int(v) => write!(out, "{v}"),
+        Value::Float(v) => write_float(out, *v),
+        Value::String(s) => write_string(out, s),
+        Value::Ref(r) => {
+            let strength = match r.strength() {
+                TokenRefStrength::Strong => "strong",
+                TokenRefStrength::Weak => "weak",
+            };
+            write!(out, "&{strength}:{}", Uuid::from(r.id()))?;
+            if let Some(pin) = r.pin() {
+                write!(out, "@{pin:08x}")?;
+            }
+            match r.fragment() {
+                Some(fragment) => {
+                    out.write_char('#')?;
+                    write_string(out, fragment)
+                }
+                None => Ok(()),
+            }
+        }
+        Value::Array(items) => {
+            if items.is_empty() {
+                return out.write_str("[]");
+            }
+            out.write_char('[')?;
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.write_char(',')?;
+                }
+                write_break(out, indent, depth + 1)?;
+                write_value(out, item, indent, depth + 1)?;
+            }
+            write_break(out, indent, depth)?;
+            out.write_char(']')
+        }
+        Value::Object(map) => {
+            if map.is_empty() {
+                return out.write_str("{}");
+            }
+            out.write_char('{')?;
+            for (i, (key, item)) in sorted_entries(map).into_iter().enumerate() {
+                if i > 0 {
+                    out.write_char(',')?;
+                }
+                write_break(out, indent, depth + 1)?;
+                write_string(out, key)?;
+                out.write_str(if indent.is_some() { ": " } else { ":" })?;
+                write_value(out, item, indent, depth + 1)?;
+            }
+            write_break(out, indent, depth)?;
+            out.write_char('}')
+        }
+    }
+}
+
+fn write_break<W: Write>(out: &mut W, indent: Option<usize>, depth: usize) -> fmt::Result {
+    if let Some(width) = indent {
+        out.write_char('\n')?;
+        for _ in 0..width * depth {
+            out.write_char(' ')?;
+        }
+    }
+    Ok(())
+}
+
+// Floats always carry a '.', exponent or special name so they never re-parse as ints.
+fn write_float<W: Write>(out: &mut W, v: f64) -> fmt::Result {
+    if v.is_nan() {
+        out.write_str("nan")
+    } else if v.is_infinite() {
+        out.write_str(if v > 0.0 { "inf" } else { "-inf" })
+    } else {
+        write!(out, "{v:?}")
+    }
+}
+
+fn write_string<W: Write>(out: &mut W, s: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
@@ -0,0 +1,258 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Index;
+
+use crate::collections::HashMap;
+
+use super::{Path, PathSegment, TokenRef, Value};
+
+static NULL: Value = Value::Null;
+
+/// Types that can index into a `Value`: `str`/`String` for object members and
+/// `usize` for array elements.
+pub trait ValueIndex: private::Sealed {
+    #[doc(hidden)]
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value>;
+
+    #[doc(hidden)]
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value>;
+}
+
+impl ValueIndex for str {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        value.as_object()?.get(self)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        value.as_object_mut()?.get_mut(self)
+    }
+}
+
+impl ValueIndex for String {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        self.as_str().index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        self.as_str().index_into_mut(value)
+    }
+}
+
+impl ValueIndex for usize {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        value.as_array()?.get(*self)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        value.as_array_mut()?.get_mut(*self)
+    }
+}
+
+impl ValueIndex for PathSegment {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        match self {
+            PathSegment::Key(key) => key.index_into(value),
+            PathSegment::Index(index) => index.index_into(value),
+        }
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        match self {
+            PathSegment::Key(key) => key.index_into_mut(value),
+            PathSegment::Index(index) => index.index_into_mut(value),
+        }
+    }
+}
+
+impl<T: ValueIndex + ?Sized> ValueIndex for &T {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        (**self).index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        (**self).index_into_mut(value)
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for str {}
+    impl Sealed for super::String {}
+    impl Sealed for usize {}
+    impl Sealed for super::PathSegment {}
+    impl<T: Sealed + ?Sized> Sealed for &T {}
+}
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a `Float`; ints are not converted.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_token_ref(&self) -> Option<TokenRef> {
+        match self {
+            Value::Ref(v) => Some(v.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&HashMap<String, Value>> {
+        match self {
+            Value::Object(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_object_mut(&mut self) -> Option<&mut HashMap<String, Value>> {
+        match self {
+            Value::Object(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the member or element at `index`, or `None` if it does not
+    /// exist or `self` is not of the matching kind.
+    pub fn get<I: ValueIndex>(&self, index: I) -> Option<&Value> {
+        index.index_into(self)
+    }
+
+    pub fn get_mut<I: ValueIndex>(&mut self, index: I) -> Option<&mut Value> {
+        index.index_into_mut(self)
+    }
+
+    pub fn get_path(&self, path: &Path) -> Option<&Value> {
+        path.segments()
+            .iter()
+            .try_fold(self, |current, segment| current.get(segment))
+    }
+
+    pub fn get_path_mut(&mut self, path: &Path) -> Option<&mut Value> {
+        path.segments()
+            .iter()
+            .try_fold(self, |current, segment| current.get_mut(segment))
+    }
+
+    /// Looks up a value by JSON Pointer (`""`, `"/a/0"`, with `~0`/`~1`
+    /// escapes). Numeric segments index arrays and are plain keys in objects.
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        pointer_segments(pointer)?
+            .into_iter()
+            .try_fold(self, |current, segment| match current {
+                Value::Array(items) => items.get(parse_index(&segment)?),
+                _ => current.get(segment.as_str()),
+            })
+    }
+
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        pointer_segments(pointer)?
+            .into_iter()
+            .try_fold(self, |current, segment| match current {
+                Value::Array(items) => items.get_mut(parse_index(&segment)?),
+                _ => current.get_mut(segment.as_str()),
+            })
+    }
+}
+
+/// Resolves a JSON Pointer against `value` into a `Path`, with the same rules
+/// as `Value::pointer`. On failure returns the shortest prefix of `pointer`
+/// that does not exist, or all of it if it is not a valid pointer.
+#[cfg(feature = "std")]
+pub(crate) fn pointer_path<'p>(value: &Value, pointer: &'p str) -> Result<Path, &'p str> {
+    let segments = pointer_segments(pointer).ok_or(pointer)?;
+    let mut path = Path::root();
+    let mut current = value;
+    let mut end = 0;
+    for segment in segments {
+        end = pointer[end + 1..]
+            .find('/')
+            .map_or(pointer.len(), |i| end + 1 + i);
+        current = match current {
+            Value::Array(items) => {
+                let index = parse_index(&segment).ok_or(&pointer[..end])?;
+                path.push_index(index);
+                items.get(index)
+            }
+            _ => {
+                let next = current.get(segment.as_str());
+                path.push_key(segment);
+                next
+            }
+        }
+        .ok_or(&pointer[..end])?;
+    }
+    Ok(path)
+}
+
+fn pointer_segments(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    let rest = pointer.strip_prefix('/')?;
+    Some(
+        rest.split('/')
+            .map(|s| s.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    )
+}
+
+// Leading zeros and signs are rejected, as in RFC 6901.
+fn parse_index(segment: &str) -> Option<usize> {
+    if segment.is_empty()
+        || (segment.len() > 1 && segment.starts_with('0'))
+        || !segment.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    segment.parse().ok()
+}
+
+impl<I: ValueIndex> Index<I> for Value {
+    type Output = Value;
+
+    /// Returns `Value::Null` when the member or element does not exist.
+    fn index(&self, index: I) -> &Value {
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
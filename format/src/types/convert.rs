@@ -0,0 +1,193 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use thiserror::Error;
+
+use crate::collections::HashMap;
+
+use super::{TokenRef, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("expected {expected}, found {found}")]
+pub struct TypeMismatch {
+    pub expected: &'static str,
+    pub found: &'static str,
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::String(_) => "string",
+            Value::Bool(_) => "bool",
+            Value::Null => "null",
+            Value::Ref(_) => "ref",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    fn mismatch(&self, expected: &'static str) -> TypeMismatch {
+        TypeMismatch {
+            expected,
+            found: self.type_name(),
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(v: $t) -> Self {
+                    Value::Int(i64::from(v))
+                }
+            }
+        )*
+    };
+}
+
+from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+impl From<f32> for Value {
+    fn from(v: f32) -> Self {
+        Value::Float(f64::from(v))
+    }
+}
+
+impl From<f64> for Value {
+    fn from(v: f64) -> Self {
+        Value::Float(v)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(v: bool) -> Self {
+        Value::Bool(v)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Value::String(v.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Value::String(v)
+    }
+}
+
+impl From<&String> for Value {
+    fn from(v: &String) -> Self {
+        Value::String(v.clone())
+    }
+}
+
+impl From<TokenRef> for Value {
+    fn from(v: TokenRef) -> Self {
+        Value::Ref(v)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(v: Vec<T>) -> Self {
+        Value::Array(v.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Clone + Into<Value>> From<&[T]> for Value {
+    fn from(v: &[T]) -> Self {
+        Value::Array(v.iter().cloned().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Value>> FromIterator<T> for Value {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Value::Array(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> From<HashMap<K, V>> for Value {
+    fn from(v: HashMap<K, V>) -> Self {
+        Value::Object(v.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> From<BTreeMap<K, V>> for Value {
+    fn from(v: BTreeMap<K, V>) -> Self {
+        Value::Object(v.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+    }
+}
+
+macro_rules! try_from_value {
+    ($t:ty, $expected:literal, $pat:pat => $out:expr) => {
+        impl TryFrom<Value> for $t {
+            type Error = TypeMismatch;
+
+            fn try_from(value: Value) -> Result<Self, Self::Error> {
+                match value {
+                    $pat => Ok($out),
+                    other => Err(other.mismatch($expected)),
+                }
+            }
+        }
+    };
+}
+
+try_from_value!(i64, "int", Value::Int(v) => v);
+try_from_value!(f64, "float", Value::Float(v) => v);
+try_from_value!(bool, "bool", Value::Bool(v) => v);
+try_from_value!(String, "string", Value::String(v) => v);
+try_from_value!(TokenRef, "ref", Value::Ref(v) => v);
+try_from_value!(Vec<Value>, "array", Value::Array(v) => v);
+try_from_value!(HashMap<String, Value>, "object", Value::Object(v) => v);
+
+impl<'a> TryFrom<&'a Value> for &'a str {
+    type Error = TypeMismatch;
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        value.as_str().ok_or_else(|| value.mismatch("string"))
+    }
+}
+
+impl TryFrom<&Value> for i64 {
+    type Error = TypeMismatch;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value.as_i64().ok_or_else(|| value.mismatch("int"))
+    }
+}
+
+impl TryFrom<&Value> for f64 {
+    type Error = TypeMismatch;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value.as_f64().ok_or_else(|| value.mismatch("float"))
+    }
+}
+
+impl TryFrom<&Value> for bool {
+    type Error = TypeMismatch;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value.as_bool().ok_or_else(|| value.mismatch("bool"))
+    }
+}
+
+impl TryFrom<&Value> for TokenRef {
+    type Error = TypeMismatch;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value.as_token_ref().ok_or_else(|| value.mismatch("ref"))
+    }
+}
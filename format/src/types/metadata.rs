@@ -1,30 +1,84 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+bitflags::bitflags! {
+    /// Reserved `Metadata` flags. Bits without a name here have no defined
+    /// meaning but are preserved.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct MetadataFlags: u32 {
+        /// The value was compressed by the application.
+        const COMPRESSED = 1 << 0;
+        /// The payload is encrypted, as in `TYPE_ENCRYPTED` frames.
+        const ENCRYPTED = 1 << 1;
+        /// The token carries a signature, typically in an attribute.
+        const SIGNED = 1 << 2;
+        /// Set on tombstones, whose `created_at_ms` is their deletion time.
+        /// Only `Token::tombstone` makes a tombstone; on a live token the
+        /// bit is carried like any other.
+        const TOMBSTONE = 1 << 3;
+        /// The value is in a canonical form chosen by the application.
+        const CANONICAL = 1 << 4;
+
+        const _ = !0;
+    }
+}
+
+/// Token metadata: creation time, flags and free-form string attributes such
+/// as a content type, author or labels.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Metadata {
     pub created_at_ms: u64,
-    pub flags: u32,
+    pub flags: MetadataFlags,
+    pub attributes: BTreeMap<String, String>,
 }
 
 impl Metadata {
+    /// Creates metadata without attributes from raw flag bits.
     pub fn new(created_at_ms: u64, flags: u32) -> Self {
         Self {
             created_at_ms,
-            flags,
+            flags: MetadataFlags::from_bits_retain(flags),
+            attributes: BTreeMap::new(),
         }
     }
+
+    pub fn with_flags(mut self, flags: MetadataFlags) -> Self {
+        self.flags |= flags;
+        self
+    }
+
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(String::as_str)
+    }
+
+    /// True when nothing is set, which is how tokens without a metadata
+    /// section deserialize.
+    pub fn is_empty(&self) -> bool {
+        self.created_at_ms == 0 && self.flags.is_empty() && self.attributes.is_empty()
+    }
 }
 
+/// Stamps the current wall-clock time; requires the `std` feature.
+#[cfg(feature = "std")]
 impl Default for Metadata {
     fn default() -> Self {
         Self {
             created_at_ms: now_unix_ms(),
-            flags: 0,
+            flags: MetadataFlags::empty(),
+            attributes: BTreeMap::new(),
         }
     }
 }
 
+#[cfg(feature = "std")]
 fn now_unix_ms() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
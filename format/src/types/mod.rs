@@ -1,9 +1,21 @@
+mod access;
+mod convert;
 mod metadata;
+mod ordering;
+mod path;
 mod reference;
+mod shared;
 mod token;
 mod value;
 
-pub use metadata::Metadata;
+#[cfg(feature = "std")]
+pub(crate) use access::pointer_path;
+pub use access::ValueIndex;
+pub use convert::TypeMismatch;
+pub use metadata::{Metadata, MetadataFlags};
+pub(crate) use ordering::sorted_entries;
+pub use path::{Path, PathSegment};
 pub use reference::{TokenRef, TokenRefStrength};
+pub use shared::SharedValue;
 pub use token::{Token, TokenId};
 pub use value::Value;
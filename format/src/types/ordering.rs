@@ -0,0 +1,106 @@
+use alloc::{string::String, vec::Vec};
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+use crate::collections::HashMap;
+use crate::constants;
+
+use super::{TokenRef, TokenRefStrength, Value};
+
+// Equality, ordering and hashing agree with the canonical encoding: two values
+// are equal exactly when they serialize to the same bytes. Variants order by
+// type marker, floats by IEEE 754 total order (so `NaN == NaN` and
+// `-0.0 < 0.0`), objects as their entries sorted by key, and references by
+// strength, then id bytes, then pin, then fragment.
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Null, Value::Null) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Ref(a), Value::Ref(b)) => a == b,
+            (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Ref(a), Value::Ref(b)) => cmp_refs(a, b),
+            (Value::Array(a), Value::Array(b)) => a.cmp(b),
+            (Value::Object(a), Value::Object(b)) => sorted_entries(a).cmp(&sorted_entries(b)),
+            _ => type_rank(self).cmp(&type_rank(other)),
+        }
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        type_rank(self).hash(state);
+        match self {
+            Value::Null | Value::Bool(_) => {}
+            Value::Int(v) => v.hash(state),
+            Value::Float(v) => v.to_bits().hash(state),
+            Value::String(v) => v.hash(state),
+            Value::Ref(v) => v.hash(state),
+            Value::Array(v) => v.hash(state),
+            Value::Object(map) => {
+                map.len().hash(state);
+                for (key, value) in sorted_entries(map) {
+                    key.hash(state);
+                    value.hash(state);
+                }
+            }
+        }
+    }
+}
+
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => constants::TYPE_NULL,
+        Value::Bool(false) => constants::TYPE_BOOL_FALSE,
+        Value::Bool(true) => constants::TYPE_BOOL_TRUE,
+        Value::Int(_) => constants::TYPE_INT64,
+        Value::Float(_) => constants::TYPE_F64,
+        Value::String(_) => constants::TYPE_STRING,
+        Value::Array(_) => constants::TYPE_ARRAY,
+        Value::Object(_) => constants::TYPE_OBJECT,
+        Value::Ref(_) => constants::TYPE_REF,
+    }
+}
+
+fn cmp_refs(a: &TokenRef, b: &TokenRef) -> Ordering {
+    let strength = |r: &TokenRef| match r.strength() {
+        TokenRefStrength::Strong => 0u8,
+        TokenRefStrength::Weak => 1u8,
+    };
+    strength(a)
+        .cmp(&strength(b))
+        .then_with(|| a.id().as_bytes().cmp(b.id().as_bytes()))
+        .then_with(|| a.pin().cmp(&b.pin()))
+        .then_with(|| a.fragment().cmp(&b.fragment()))
+}
+
+pub(crate) fn sorted_entries(map: &HashMap<String, Value>) -> Vec<(&String, &Value)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    entries
+}
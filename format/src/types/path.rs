@@ -0,0 +1,83 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Location of a sub-value, as a sequence of object keys and array indices.
+///
+/// Displays as a JSON Pointer (`/orders/0/sku`); the segments themselves keep
+/// the key/index distinction that the pointer text loses.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Path(Vec<PathSegment>);
+
+impl Path {
+    pub fn root() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    pub fn push_key(&mut self, key: impl Into<String>) {
+        self.0.push(PathSegment::Key(key.into()));
+    }
+
+    pub fn push_index(&mut self, index: usize) {
+        self.0.push(PathSegment::Index(index));
+    }
+
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.0.pop()
+    }
+
+    pub fn key(&self, key: impl Into<String>) -> Self {
+        let mut out = self.clone();
+        out.push_key(key);
+        out
+    }
+
+    pub fn index(&self, index: usize) -> Self {
+        let mut out = self.clone();
+        out.push_index(index);
+        out
+    }
+
+    pub fn split_last(&self) -> Option<(Path, &PathSegment)> {
+        let (last, parent) = self.0.split_last()?;
+        Some((Path(parent.to_vec()), last))
+    }
+}
+
+impl From<Vec<PathSegment>> for Path {
+    fn from(segments: Vec<PathSegment>) -> Self {
+        Self(segments)
+    }
+}
+
+impl FromIterator<PathSegment> for Path {
+    fn from_iter<I: IntoIterator<Item = PathSegment>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for segment in &self.0 {
+            f.write_str("/")?;
+            match segment {
+                PathSegment::Key(key) => f.write_str(&key.replace('~', "~0").replace('/', "~1"))?,
+                PathSegment::Index(index) => write!(f, "{index}")?,
+            }
+        }
+        Ok(())
+    }
+}
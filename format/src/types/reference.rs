@@ -1,3 +1,5 @@
+use alloc::string::String;
+
 use super::TokenId;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -6,10 +8,12 @@ pub enum TokenRefStrength {
     Weak,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TokenRef {
     id: TokenId,
     strength: TokenRefStrength,
+    pin: Option<u32>,
+    fragment: Option<String>,
 }
 
 impl TokenRef {
@@ -17,6 +21,8 @@ impl TokenRef {
         Self {
             id,
             strength: TokenRefStrength::Strong,
+            pin: None,
+            fragment: None,
         }
     }
 
@@ -24,6 +30,8 @@ impl TokenRef {
         Self {
             id,
             strength: TokenRefStrength::Strong,
+            pin: None,
+            fragment: None,
         }
     }
 
@@ -31,9 +39,27 @@ impl TokenRef {
         Self {
             id,
             strength: TokenRefStrength::Weak,
+            pin: None,
+            fragment: None,
         }
     }
 
+    /// Points the reference at the sub-value found by the JSON Pointer
+    /// `fragment` (`/chapters/3`) instead of the whole token. An empty pointer
+    /// refers to the whole token.
+    pub fn with_fragment(mut self, fragment: impl Into<String>) -> Self {
+        let fragment = fragment.into();
+        self.fragment = (!fragment.is_empty()).then_some(fragment);
+        self
+    }
+
+    /// Pins the reference to one version of its target, identified by
+    /// `Token::checksum`. Resolving it fails once the target has changed.
+    pub fn pinned(mut self, checksum: u32) -> Self {
+        self.pin = Some(checksum);
+        self
+    }
+
     pub fn id(&self) -> TokenId {
         self.id
     }
@@ -41,4 +67,14 @@ impl TokenRef {
     pub fn strength(&self) -> TokenRefStrength {
         self.strength
     }
+
+    /// The checksum of the pinned target version, if any.
+    pub fn pin(&self) -> Option<u32> {
+        self.pin
+    }
+
+    /// The JSON Pointer into the target token, if any.
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
 }
@@ -0,0 +1,216 @@
+use alloc::string::ToString;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::collections::HashMap;
+
+use super::{Path, PathSegment, TokenRef, Value};
+
+/// A `Value` whose strings, arrays and objects are reference counted, so
+/// cloning any subtree is O(1). Mutation goes through `make_array_mut` and
+/// `make_object_mut`, which copy a container only while it is shared.
+///
+/// Equality follows `Value`: floats compare by bits and objects ignore entry
+/// order.
+#[derive(Debug, Clone)]
+pub enum SharedValue {
+    Int(i64),
+    Float(f64),
+    String(Arc<str>),
+    Bool(bool),
+    Null,
+    Ref(TokenRef),
+    Array(Arc<Vec<SharedValue>>),
+    Object(Arc<HashMap<Arc<str>, SharedValue>>),
+}
+
+impl SharedValue {
+    pub fn is_null(&self) -> bool {
+        matches!(self, SharedValue::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            SharedValue::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            SharedValue::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            SharedValue::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SharedValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_token_ref(&self) -> Option<TokenRef> {
+        match self {
+            SharedValue::Ref(r) => Some(r.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[SharedValue]> {
+        match self {
+            SharedValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&HashMap<Arc<str>, SharedValue>> {
+        match self {
+            SharedValue::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Returns the array for mutation, first copying it if it is shared.
+    pub fn make_array_mut(&mut self) -> Option<&mut Vec<SharedValue>> {
+        match self {
+            SharedValue::Array(items) => Some(Arc::make_mut(items)),
+            _ => None,
+        }
+    }
+
+    /// Returns the object for mutation, first copying it if it is shared.
+    pub fn make_object_mut(&mut self) -> Option<&mut HashMap<Arc<str>, SharedValue>> {
+        match self {
+            SharedValue::Object(map) => Some(Arc::make_mut(map)),
+            _ => None,
+        }
+    }
+
+    /// Looks up an object member.
+    pub fn get(&self, key: &str) -> Option<&SharedValue> {
+        self.as_object()?.get(key)
+    }
+
+    /// Looks up an array element.
+    pub fn index(&self, index: usize) -> Option<&SharedValue> {
+        self.as_array()?.get(index)
+    }
+
+    pub fn get_path(&self, path: &Path) -> Option<&SharedValue> {
+        path.segments()
+            .iter()
+            .try_fold(self, |value, segment| match segment {
+                PathSegment::Key(key) => value.get(key),
+                PathSegment::Index(index) => value.index(*index),
+            })
+    }
+
+    /// Copies the value into an owned `Value`.
+    pub fn to_value(&self) -> Value {
+        match self {
+            SharedValue::Int(v) => Value::Int(*v),
+            SharedValue::Float(v) => Value::Float(*v),
+            SharedValue::String(s) => Value::String(s.to_string()),
+            SharedValue::Bool(v) => Value::Bool(*v),
+            SharedValue::Null => Value::Null,
+            SharedValue::Ref(r) => Value::Ref(r.clone()),
+            SharedValue::Array(items) => Value::Array(items.iter().map(Self::to_value).collect()),
+            SharedValue::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| (key.to_string(), value.to_value()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<&Value> for SharedValue {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Int(v) => SharedValue::Int(*v),
+            Value::Float(v) => SharedValue::Float(*v),
+            Value::String(s) => SharedValue::String(Arc::from(s.as_str())),
+            Value::Bool(v) => SharedValue::Bool(*v),
+            Value::Null => SharedValue::Null,
+            Value::Ref(r) => SharedValue::Ref(r.clone()),
+            Value::Array(items) => {
+                SharedValue::Array(Arc::new(items.iter().map(SharedValue::from).collect()))
+            }
+            Value::Object(map) => SharedValue::Object(Arc::new(
+                map.iter()
+                    .map(|(key, value)| (Arc::from(key.as_str()), SharedValue::from(value)))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+impl From<Value> for SharedValue {
+    fn from(value: Value) -> Self {
+        SharedValue::from(&value)
+    }
+}
+
+impl From<&SharedValue> for Value {
+    fn from(value: &SharedValue) -> Self {
+        value.to_value()
+    }
+}
+
+impl From<SharedValue> for Value {
+    fn from(value: SharedValue) -> Self {
+        value.to_value()
+    }
+}
+
+impl PartialEq for SharedValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SharedValue::Null, SharedValue::Null) => true,
+            (SharedValue::Bool(a), SharedValue::Bool(b)) => a == b,
+            (SharedValue::Int(a), SharedValue::Int(b)) => a == b,
+            (SharedValue::Float(a), SharedValue::Float(b)) => a.to_bits() == b.to_bits(),
+            (SharedValue::String(a), SharedValue::String(b)) => a == b,
+            (SharedValue::Ref(a), SharedValue::Ref(b)) => a == b,
+            (SharedValue::Array(a), SharedValue::Array(b)) => Arc::ptr_eq(a, b) || a == b,
+            (SharedValue::Object(a), SharedValue::Object(b)) => Arc::ptr_eq(a, b) || a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for SharedValue {}
+
+impl PartialEq<Value> for SharedValue {
+    fn eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (SharedValue::Null, Value::Null) => true,
+            (SharedValue::Bool(a), Value::Bool(b)) => a == b,
+            (SharedValue::Int(a), Value::Int(b)) => a == b,
+            (SharedValue::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+            (SharedValue::String(a), Value::String(b)) => **a == **b,
+            (SharedValue::Ref(a), Value::Ref(b)) => a == b,
+            (SharedValue::Array(a), Value::Array(b)) => a.iter().eq(b.iter()),
+            (SharedValue::Object(a), Value::Object(b)) => {
+                a.len() == b.len()
+                    && b.iter()
+                        .all(|(key, value)| a.get(key.as_str()).is_some_and(|v| v == value))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<SharedValue> for Value {
+    fn eq(&self, other: &SharedValue) -> bool {
+        other == self
+    }
+}
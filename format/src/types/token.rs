@@ -1,11 +1,13 @@
 use uuid::Uuid;
 
-use super::{Metadata, Value};
+use super::{Metadata, MetadataFlags, Value};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TokenId(Uuid);
 
 impl TokenId {
+    /// Generates a random (v4) id; requires the `std` feature.
+    #[cfg(feature = "std")]
     pub fn new() -> Self {
         Self(Uuid::new_v4())
     }
@@ -15,6 +17,7 @@ impl TokenId {
     }
 }
 
+#[cfg(feature = "std")]
 impl Default for TokenId {
     fn default() -> Self {
         Self::new()
@@ -33,19 +36,23 @@ impl From<TokenId> for Uuid {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Token {
     id: TokenId,
     value: Value,
     metadata: Metadata,
+    tombstone: bool,
 }
 
 impl Token {
+    /// A live token. A `TOMBSTONE` flag in `metadata` is kept as-is and does
+    /// not make it a tombstone; use `Token::tombstone` for that.
     pub fn new(id: TokenId, value: Value, metadata: Metadata) -> Self {
         Self {
             id,
             value,
             metadata,
+            tombstone: false,
         }
     }
 
@@ -60,4 +67,30 @@ impl Token {
     pub fn metadata(&self) -> &Metadata {
         &self.metadata
     }
+
+    /// A marker recording that the token `id` was deleted at `deleted_at_ms`.
+    /// Its value is `Null`, and it serializes as a `TYPE_TOMBSTONE` frame.
+    pub fn tombstone(id: TokenId, deleted_at_ms: u64) -> Self {
+        Self::tombstone_with_metadata(id, Metadata::new(deleted_at_ms, 0))
+    }
+
+    /// A tombstone keeping `metadata`'s flags and attributes. Its deletion
+    /// time is `metadata.created_at_ms`, and the `TOMBSTONE` flag is added.
+    pub fn tombstone_with_metadata(id: TokenId, metadata: Metadata) -> Self {
+        Self {
+            id,
+            value: Value::Null,
+            metadata: metadata.with_flags(MetadataFlags::TOMBSTONE),
+            tombstone: true,
+        }
+    }
+
+    pub fn is_tombstone(&self) -> bool {
+        self.tombstone
+    }
+
+    /// The deletion time of a tombstone.
+    pub fn deleted_at_ms(&self) -> Option<u64> {
+        self.is_tombstone().then_some(self.metadata.created_at_ms)
+    }
 }
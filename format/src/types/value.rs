@@ -1,8 +1,11 @@
-use std::collections::HashMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::collections::HashMap;
 
 use super::TokenRef;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum Value {
     Int(i64),
     Float(f64),
@@ -0,0 +1,94 @@
+use arbitrary::{Arbitrary, Unstructured};
+
+use toon_format::testing::{arbitrary_graph, arbitrary_value, GraphConfig, ValueConfig};
+use toon_format::{Deserializer, RegistryError, Serializer, Token, TokenRegistry, Value};
+
+/// Deterministic pseudo-random input for `Unstructured`.
+fn input(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+    (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as u8
+        })
+        .collect()
+}
+
+fn depth(value: &Value) -> u32 {
+    match value {
+        Value::Array(items) => 1 + items.iter().map(depth).max().unwrap_or(0),
+        Value::Object(map) => 1 + map.values().map(depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+fn count_refs(value: &Value) -> usize {
+    match value {
+        Value::Ref(_) => 1,
+        Value::Array(items) => items.iter().map(count_refs).sum(),
+        Value::Object(map) => map.values().map(count_refs).sum(),
+        _ => 0,
+    }
+}
+
+#[test]
+fn arbitrary_tokens_round_trip() {
+    for seed in 0..200 {
+        let bytes = input(seed, 1024);
+        let token = Token::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+
+        let encoded = Serializer::new().serialize(&token).unwrap();
+        assert_eq!(Deserializer::new(&encoded).deserialize().unwrap(), token);
+    }
+}
+
+#[test]
+fn value_config_bounds_depth_and_refs() {
+    let config = ValueConfig {
+        max_depth: 2,
+        ref_density: 0.0,
+        ..ValueConfig::default()
+    };
+    for seed in 0..200 {
+        let bytes = input(seed, 1024);
+        let value = arbitrary_value(&mut Unstructured::new(&bytes), &config).unwrap();
+        assert!(depth(&value) <= 2);
+        assert_eq!(count_refs(&value), 0);
+    }
+
+    let refs_only = ValueConfig {
+        ref_density: 1.0,
+        ..ValueConfig::default()
+    };
+    let bytes = input(7, 1024);
+    let value = arbitrary_value(&mut Unstructured::new(&bytes), &refs_only).unwrap();
+    assert!(matches!(value, Value::Ref(_)));
+}
+
+#[test]
+fn graphs_have_cycles_only_when_asked() {
+    for seed in 0..50 {
+        let bytes = input(seed, 4096);
+        for cycle in [false, true] {
+            let config = GraphConfig {
+                cycle,
+                ..GraphConfig::default()
+            };
+            let tokens = arbitrary_graph(&mut Unstructured::new(&bytes), &config).unwrap();
+            assert_eq!(tokens.len(), config.tokens);
+
+            let registry = TokenRegistry::new();
+            for token in &tokens {
+                registry.register(token.clone());
+            }
+            let result = registry.ensure_loaded_and_acyclic(tokens[0].id(), |_| None);
+            if cycle {
+                assert!(matches!(result, Err(RegistryError::CircularReference(_))));
+            } else {
+                assert_eq!(result, Ok(()));
+            }
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use uuid::Uuid;
+
+use toon_format::bumpalo::Bump;
+use toon_format::{
+    toon, ArenaDecoder, ArenaRef, ArenaValue, DeserializeError, Deserializer, Metadata, Serializer,
+    Token, TokenId, TokenRef, TokenRefStrength, Value,
+};
+
+fn token(value: Value) -> Token {
+    Token::new(
+        TokenId::from(Uuid::from_bytes([8; 16])),
+        value,
+        Metadata::new(0, 0),
+    )
+}
+
+#[test]
+fn decodes_into_the_arena() {
+    let target = TokenId::from(Uuid::from_bytes([9; 16]));
+    let token = token(toon! {
+        "name": "sensor-7",
+        "reading": 21.5,
+        "count": -3,
+        "ok": true,
+        "missing": null,
+        "source": TokenRef::weak(target),
+        "samples": [1, 2, [3]],
+    });
+    let bytes = Serializer::new().serialize(&token).unwrap();
+
+    let arena = Bump::new();
+    let decoded = Deserializer::new(&bytes).deserialize_in(&arena).unwrap();
+
+    assert_eq!(decoded.id(), token.id());
+    assert_eq!(decoded.to_token(), token);
+
+    let value = decoded.value();
+    assert_eq!(value.get("name"), Some(&ArenaValue::String("sensor-7")));
+    assert_eq!(value.get("reading"), Some(&ArenaValue::Float(21.5)));
+    assert_eq!(
+        value.get("source"),
+        Some(&ArenaValue::Ref(ArenaRef {
+            id: target,
+            strength: TokenRefStrength::Weak,
+            pin: None,
+            fragment: None,
+        }))
+    );
+    assert_eq!(
+        value
+            .get("samples")
+            .and_then(|s| s.index(2))
+            .and_then(|s| s.index(0)),
+        Some(&ArenaValue::Int(3))
+    );
+    assert_eq!(value.get("absent"), None);
+}
+
+#[test]
+fn strings_borrow_from_the_input() {
+    let bytes = Serializer::new()
+        .serialize(&token(Value::from("borrowed")))
+        .unwrap();
+
+    let arena = Bump::new();
+    let decoded = Deserializer::new(&bytes).deserialize_in(&arena).unwrap();
+
+    let ArenaValue::String(s) = *decoded.value() else {
+        panic!("expected a string");
+    };
+    assert_eq!(s.as_ptr(), bytes[22..].as_ptr());
+}
+
+#[test]
+fn reference_fragments_borrow_from_the_input() {
+    let target = TokenId::from(Uuid::from_bytes([9; 16]));
+    let reference = TokenRef::strong(target).with_fragment("/rows/2");
+    let bytes = Serializer::new()
+        .serialize(&token(Value::Ref(reference.clone())))
+        .unwrap();
+
+    let arena = Bump::new();
+    let decoded = Deserializer::new(&bytes).deserialize_in(&arena).unwrap();
+    let ArenaValue::Ref(r) = *decoded.value() else {
+        panic!("expected a reference");
+    };
+    assert_eq!(r.fragment, Some("/rows/2"));
+    assert_eq!(r.to_token_ref(), reference);
+}
+
+#[test]
+fn reference_pins_are_decoded() {
+    let target = TokenId::from(Uuid::from_bytes([9; 16]));
+    let reference = TokenRef::weak(target)
+        .pinned(0xDEAD_BEEF)
+        .with_fragment("/a");
+    let bytes = Serializer::new()
+        .serialize(&token(Value::Ref(reference.clone())))
+        .unwrap();
+
+    let arena = Bump::new();
+    let decoded = Deserializer::new(&bytes).deserialize_in(&arena).unwrap();
+    let ArenaValue::Ref(r) = *decoded.value() else {
+        panic!("expected a reference");
+    };
+    assert_eq!(r.pin, Some(0xDEAD_BEEF));
+    assert_eq!(r.to_token_ref(), reference);
+}
+
+#[test]
+fn decoder_reuses_its_arena() {
+    let frames: Vec<Vec<u8>> = (0..100)
+        .map(|i| {
+            let items: Vec<Value> = (0..50).map(|j| Value::Int(i * j)).collect();
+            Serializer::new()
+                .serialize(&token(toon! { "i": i, "items": items }))
+                .unwrap()
+        })
+        .collect();
+
+    let mut decoder = ArenaDecoder::new();
+    let mut high_water = 0;
+    for (i, bytes) in frames.iter().enumerate() {
+        let decoded = decoder.decode(bytes).unwrap();
+        assert_eq!(decoded.value().get("i"), Some(&ArenaValue::Int(i as i64)));
+        high_water = high_water.max(decoder.allocated_bytes());
+    }
+    // Resetting keeps the arena at the size of one token, not all of them.
+    let total: usize = frames.iter().map(Vec::len).sum();
+    assert!(high_water < total / 10, "{high_water} of {total}");
+}
+
+#[test]
+fn later_duplicate_keys_win() {
+    // {"k": 1, "k": 2}, built by hand since `Value` cannot hold duplicates.
+    let mut payload = 2u32.to_le_bytes().to_vec();
+    for n in [1i64, 2] {
+        payload.extend_from_slice(&1u32.to_le_bytes());
+        payload.push(b'k');
+        payload.push(toon_format::constants::TYPE_INT64);
+        payload.extend_from_slice(&8u32.to_le_bytes());
+        payload.extend_from_slice(&n.to_le_bytes());
+    }
+    let mut bytes = Serializer::new().serialize(&token(Value::Null)).unwrap();
+    bytes.truncate(17);
+    bytes.push(toon_format::constants::TYPE_OBJECT);
+    bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&bytes);
+    bytes.extend_from_slice(&hasher.finalize().to_le_bytes());
+
+    let arena = Bump::new();
+    let decoded = Deserializer::new(&bytes).deserialize_in(&arena).unwrap();
+    assert_eq!(decoded.value().get("k"), Some(&ArenaValue::Int(2)));
+    assert_eq!(
+        decoded.to_token().value(),
+        Deserializer::new(&bytes).deserialize().unwrap().value()
+    );
+}
+
+#[test]
+fn rejects_what_deserialize_rejects() {
+    let mut bytes = Serializer::new()
+        .serialize(&token(toon! { "a": "b" }))
+        .unwrap();
+    let arena = Bump::new();
+
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize_in(&arena),
+        Err(DeserializeError::ChecksumMismatch)
+    );
+
+    let bytes = Serializer::new()
+        .serialize_delta(&token(Value::Int(1)), &token(Value::Int(2)))
+        .unwrap();
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize_in(&arena),
+        Err(DeserializeError::Delta)
+    );
+}
+
+#[test]
+fn tombstones_decode_without_a_value() {
+    let id = TokenId::from(Uuid::from_bytes([10; 16]));
+    let tombstone = Token::tombstone(id, 1234);
+    let bytes = Serializer::new().serialize(&tombstone).unwrap();
+
+    let arena = Bump::new();
+    let decoded = Deserializer::new(&bytes).deserialize_in(&arena).unwrap();
+    assert_eq!(decoded.deleted_at_ms(), Some(1234));
+    assert_eq!(decoded.value(), &ArenaValue::Null);
+    assert_eq!(decoded.to_token(), tombstone);
+}
+
+#[test]
+fn metadata_is_decoded_alongside_the_value() {
+    let metadata = Metadata::new(12, 0).with_attribute("author", "ops");
+    let token = Token::new(
+        TokenId::from(Uuid::from_bytes([11; 16])),
+        Value::from("hello"),
+        metadata.clone(),
+    );
+    let bytes = Serializer::new().serialize(&token).unwrap();
+
+    let arena = Bump::new();
+    let decoded = Deserializer::new(&bytes).deserialize_in(&arena).unwrap();
+    assert_eq!(decoded.metadata(), &metadata);
+    assert_eq!(decoded.value(), &ArenaValue::String("hello"));
+    assert_eq!(decoded.to_token(), token);
+}
@@ -0,0 +1,168 @@
+use uuid::Uuid;
+
+use toon_format::{
+    constants, toon, BatchDeserializer, BatchSerializer, DeserializeError, Metadata, Serializer,
+    Token, TokenId,
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(bytes);
+    hasher.finalize()
+}
+
+fn similar_tokens(count: u8) -> Vec<Token> {
+    (0..count)
+        .map(|i| {
+            let id = TokenId::from(Uuid::from_bytes([i; 16]));
+            let value = toon! {
+                "customer_id": i64::from(i),
+                "status": if i % 2 == 0 { "pending" } else { "shipped" },
+                "shipping_address": { "country": "CH", "postal_code": format!("80{i:02}") },
+                "line_items": [{ "sku": "A-1", "quantity": 2 }, { "sku": "B-2", "quantity": 1 }],
+                "parent": &weak TokenId::from(Uuid::from_bytes([200; 16])),
+            };
+            Token::new(id, value, Metadata::new(0, 0))
+        })
+        .collect()
+}
+
+#[test]
+fn batch_round_trip() {
+    let tokens = similar_tokens(20);
+    let bytes = BatchSerializer::new().serialize(&tokens).unwrap();
+    let decoded = BatchDeserializer::new(&bytes).deserialize().unwrap();
+    assert_eq!(decoded, tokens);
+
+    let mut with_tombstone = similar_tokens(3);
+    with_tombstone.push(Token::new(
+        TokenId::from(Uuid::from_bytes([8; 16])),
+        toon! { "status": "pending" },
+        Metadata::new(3, 0).with_attribute("status", "draft"),
+    ));
+    with_tombstone.push(Token::tombstone(
+        TokenId::from(Uuid::from_bytes([9; 16])),
+        7,
+    ));
+    let bytes = BatchSerializer::new().serialize(&with_tombstone).unwrap();
+    let decoded = BatchDeserializer::new(&bytes).deserialize().unwrap();
+    assert_eq!(decoded, with_tombstone);
+
+    let empty = BatchSerializer::new().serialize(&[]).unwrap();
+    assert!(BatchDeserializer::new(&empty)
+        .deserialize()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn dictionary_shrinks_repeated_keys() {
+    let tokens = similar_tokens(50);
+    let individual: usize = tokens
+        .iter()
+        .map(|t| Serializer::new().serialize(t).unwrap().len())
+        .sum();
+
+    let keys_only = BatchSerializer::new().serialize(&tokens).unwrap();
+    let with_values = BatchSerializer::new()
+        .with_value_dictionary(2)
+        .serialize(&tokens)
+        .unwrap();
+
+    assert!(keys_only.len() < individual);
+    assert!(with_values.len() < keys_only.len());
+    assert_eq!(
+        BatchDeserializer::new(&with_values).deserialize().unwrap(),
+        tokens
+    );
+
+    let dictionary = BatchDeserializer::new(&with_values).dictionary().unwrap();
+    assert!(dictionary.contains(&"customer_id".to_string()));
+    assert!(dictionary.contains(&"pending".to_string()));
+    assert!(!dictionary.contains(&"8001".to_string()));
+
+    let keys = BatchDeserializer::new(&keys_only).dictionary().unwrap();
+    assert!(!keys.contains(&"pending".to_string()));
+}
+
+#[test]
+fn batch_layout_starts_with_magic_and_dictionary() {
+    let id = TokenId::from(Uuid::from_bytes([1u8; 16]));
+    let token = Token::new(id, toon! { "k": "v" }, Metadata::new(0, 0));
+    let bytes = BatchSerializer::new().serialize(&[token]).unwrap();
+
+    assert_eq!(&bytes[..4], &constants::BATCH_MAGIC);
+    assert_eq!(bytes[4], constants::FORMAT_VERSION);
+    assert_eq!(u32::from_le_bytes(bytes[5..9].try_into().unwrap()), 1);
+    assert_eq!(u32::from_le_bytes(bytes[9..13].try_into().unwrap()), 1);
+    assert_eq!(&bytes[13..14], b"k");
+    assert_eq!(u32::from_le_bytes(bytes[14..18].try_into().unwrap()), 1);
+    assert_eq!(&bytes[18..34], id.as_bytes());
+    assert_eq!(bytes[34], constants::TYPE_OBJECT_DICT);
+}
+
+#[test]
+fn batch_rejects_corruption() {
+    let tokens = similar_tokens(3);
+    let bytes = BatchSerializer::new().serialize(&tokens).unwrap();
+
+    let mut flipped = bytes.clone();
+    flipped[10] ^= 0x40;
+    assert_eq!(
+        BatchDeserializer::new(&flipped).deserialize().unwrap_err(),
+        DeserializeError::ChecksumMismatch
+    );
+
+    let mut magic = bytes.clone();
+    magic[0] = b'X';
+    assert_eq!(
+        BatchDeserializer::new(&magic).deserialize().unwrap_err(),
+        DeserializeError::InvalidMagic
+    );
+
+    assert_eq!(
+        BatchDeserializer::new(&bytes[..6])
+            .deserialize()
+            .unwrap_err(),
+        DeserializeError::Truncated
+    );
+}
+
+#[test]
+fn batch_rejects_out_of_range_dictionary_index() {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&constants::BATCH_MAGIC);
+    bytes.push(constants::FORMAT_VERSION);
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    bytes.extend_from_slice(&[3u8; 16]);
+    bytes.push(constants::TYPE_STRING_DICT);
+    bytes.extend_from_slice(&4u32.to_le_bytes());
+    bytes.extend_from_slice(&7u32.to_le_bytes());
+    let checksum = crc32(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+
+    assert_eq!(
+        BatchDeserializer::new(&bytes).deserialize().unwrap_err(),
+        DeserializeError::InvalidDictionaryIndex(7)
+    );
+}
+
+#[test]
+fn dictionary_markers_are_rejected_outside_batches() {
+    let mut bytes = Vec::new();
+    bytes.push(constants::FORMAT_VERSION);
+    bytes.extend_from_slice(&[4u8; 16]);
+    bytes.push(constants::TYPE_STRING_DICT);
+    bytes.extend_from_slice(&4u32.to_le_bytes());
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    let checksum = crc32(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+
+    assert_eq!(
+        toon_format::Deserializer::new(&bytes)
+            .deserialize()
+            .unwrap_err(),
+        DeserializeError::UnknownTypeMarker(constants::TYPE_STRING_DICT)
+    );
+}
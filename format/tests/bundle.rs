@@ -0,0 +1,158 @@
+use uuid::Uuid;
+
+use toon_format::{
+    toon, Bundle, DeserializeError, Metadata, RegistryError, Token, TokenId, TokenRef,
+    TokenRegistry, Value,
+};
+
+fn id(n: u8) -> TokenId {
+    TokenId::from(Uuid::from_bytes([n; 16]))
+}
+
+fn token(n: u8, value: Value) -> Token {
+    Token::new(id(n), value, Metadata::new(0, 0))
+}
+
+/// 1 -> 2 -> 4, 1 -> 3 -> 4 strongly; 3 ~> 5 weakly; 6 is unrelated.
+fn document_registry() -> TokenRegistry {
+    let registry = TokenRegistry::new();
+    registry.register(token(
+        1,
+        toon! { "title": "doc", "parts": [TokenRef::new(id(2)), TokenRef::new(id(3))] },
+    ));
+    registry.register(token(
+        2,
+        toon! { "body": "intro", "style": TokenRef::new(id(4)) },
+    ));
+    registry.register(token(
+        3,
+        toon! { "body": "outro", "style": TokenRef::new(id(4)), "see": TokenRef::weak(id(5)) },
+    ));
+    registry.register(token(4, toon! { "font": "serif" }));
+    registry.register(token(5, Value::from("weakly referenced")));
+    registry.register(token(6, Value::from("unrelated")));
+    registry
+}
+
+fn ids(bundle: &Bundle) -> Vec<TokenId> {
+    bundle.tokens().iter().map(|t| t.id()).collect()
+}
+
+#[test]
+fn gathers_the_strong_closure_root_first() {
+    let bundle = Bundle::from_registry(id(1), &document_registry()).unwrap();
+
+    assert_eq!(bundle.root(), id(1));
+    let mut gathered = ids(&bundle);
+    assert_eq!(gathered[0], id(1));
+    gathered.sort_by_key(|id| *id.as_bytes());
+    assert_eq!(gathered, vec![id(1), id(2), id(3), id(4)]);
+}
+
+#[test]
+fn round_trips_into_another_registry() {
+    let source = document_registry();
+    let bundle = Bundle::from_registry(id(1), &source).unwrap();
+    let bytes = bundle.serialize().unwrap();
+
+    let imported = Bundle::deserialize(&bytes).unwrap();
+    assert_eq!(ids(&imported), ids(&bundle));
+
+    let target = TokenRegistry::new();
+    assert_eq!(imported.register_into(&target), Ok(id(1)));
+    for n in 1..=4 {
+        assert_eq!(target.get(id(n)), source.get(id(n)));
+    }
+    assert!(target.get(id(5)).is_none());
+    assert!(target.ensure_loaded_and_acyclic(id(1), |_| None).is_ok());
+}
+
+#[test]
+fn fails_to_gather_missing_or_cyclic_tokens() {
+    let registry = document_registry();
+    assert_eq!(
+        Bundle::from_registry(id(9), &registry).unwrap_err(),
+        RegistryError::NotFound(id(9))
+    );
+
+    registry.register(token(4, toon! { "back": TokenRef::new(id(2)) }));
+    assert!(matches!(
+        Bundle::from_registry(id(1), &registry),
+        Err(RegistryError::CircularReference(_))
+    ));
+}
+
+#[test]
+fn import_rejects_dangling_strong_refs_without_registering() {
+    let source = document_registry();
+    let bundle = Bundle::from_registry(id(2), &source).unwrap();
+    let bytes = bundle.serialize().unwrap();
+
+    // Drop token 4 from the artifact by re-bundling only token 2's frame.
+    let partial = toon_format::BatchSerializer::new()
+        .serialize(&[Token::clone(&bundle.tokens()[0])])
+        .unwrap();
+
+    let target = TokenRegistry::new();
+    let imported = Bundle::deserialize(&partial).unwrap();
+    assert_eq!(
+        imported.register_into(&target),
+        Err(RegistryError::NotFound(id(4)))
+    );
+    assert!(target.get(id(2)).is_none());
+
+    // With the target already holding token 4, the partial bundle is complete.
+    target.register(token(4, toon! { "font": "mono" }));
+    assert_eq!(imported.register_into(&target), Ok(id(2)));
+
+    assert_eq!(
+        Bundle::deserialize(&bytes)
+            .unwrap()
+            .register_into(&TokenRegistry::new()),
+        Ok(id(2))
+    );
+}
+
+#[test]
+fn pinned_refs_must_match_the_bundled_version() {
+    let style = token(7, toon! { "font": "serif" });
+    let pinned = TokenRef::new(id(7)).pinned(style.checksum().unwrap());
+    let registry = TokenRegistry::new();
+    registry.register(style.clone());
+    registry.register(token(8, toon! { "style": pinned }));
+    let bundle = Bundle::from_registry(id(8), &registry).unwrap();
+    assert_eq!(ids(&bundle), vec![id(8), id(7)]);
+
+    registry.register(token(7, toon! { "font": "mono" }));
+    assert_eq!(
+        Bundle::from_registry(id(8), &registry).unwrap_err(),
+        RegistryError::VersionMismatch(id(7))
+    );
+
+    // Importing only the pinning token checks the pin against the target.
+    let partial = Bundle::deserialize(
+        &toon_format::BatchSerializer::new()
+            .serialize(&[Token::clone(&bundle.tokens()[0])])
+            .unwrap(),
+    )
+    .unwrap();
+    let target = TokenRegistry::new();
+    target.register(token(7, toon! { "font": "mono" }));
+    assert_eq!(
+        partial.register_into(&target),
+        Err(RegistryError::VersionMismatch(id(7)))
+    );
+    assert!(target.get(id(8)).is_none());
+
+    target.register(style);
+    assert_eq!(partial.register_into(&target), Ok(id(8)));
+}
+
+#[test]
+fn rejects_empty_artifacts() {
+    let bytes = toon_format::BatchSerializer::new().serialize(&[]).unwrap();
+    assert_eq!(
+        Bundle::deserialize(&bytes).unwrap_err(),
+        DeserializeError::Truncated
+    );
+}
@@ -0,0 +1,99 @@
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+use uuid::Uuid;
+
+use toon_format::codec::{CodecError, TokenCodec};
+use toon_format::{toon, DeserializeError, Metadata, Token, TokenId};
+
+fn token(id: u8) -> Token {
+    Token::new(
+        TokenId::from(Uuid::from_bytes([id; 16])),
+        toon! { "seq": i64::from(id), "payload": "x".repeat(64) },
+        Metadata::new(0, 0),
+    )
+}
+
+#[test]
+fn codec_round_trip_across_partial_reads() {
+    let mut codec = TokenCodec::new();
+    let mut wire = BytesMut::new();
+    for i in 0..3 {
+        codec.encode(token(i), &mut wire).unwrap();
+    }
+
+    let mut src = BytesMut::new();
+    let mut decoded = Vec::new();
+    for chunk in wire.chunks(5) {
+        src.extend_from_slice(chunk);
+        while let Some(token) = codec.decode(&mut src).unwrap() {
+            decoded.push(token);
+        }
+    }
+
+    assert_eq!(decoded, (0..3).map(token).collect::<Vec<_>>());
+    assert!(src.is_empty());
+    assert_eq!(codec.decode_eof(&mut src).unwrap(), None);
+}
+
+#[test]
+fn codec_enforces_max_frame_len() {
+    let mut wire = BytesMut::new();
+    TokenCodec::new().encode(&token(1), &mut wire).unwrap();
+    let len = wire.len();
+
+    let mut small = TokenCodec::with_max_frame_len(len - 1);
+    assert!(matches!(
+        small.encode(&token(1), &mut BytesMut::new()),
+        Err(CodecError::FrameTooLarge(n)) if n == len
+    ));
+
+    let mut header_only = BytesMut::from(&wire[..22]);
+    assert!(matches!(
+        small.decode(&mut header_only),
+        Err(CodecError::FrameTooLarge(n)) if n == len
+    ));
+}
+
+#[test]
+fn codec_verifies_checksums() {
+    let mut codec = TokenCodec::new();
+    let mut wire = BytesMut::new();
+    codec.encode(&token(1), &mut wire).unwrap();
+    codec.encode(&token(2), &mut wire).unwrap();
+    wire[30] ^= 0x01;
+
+    assert!(matches!(
+        codec.decode(&mut wire),
+        Err(CodecError::Deserialize(DeserializeError::ChecksumMismatch))
+    ));
+    assert_eq!(codec.decode(&mut wire).unwrap(), Some(token(2)));
+}
+
+#[test]
+fn codec_reports_truncated_stream_at_eof() {
+    let mut codec = TokenCodec::new();
+    let mut wire = BytesMut::new();
+    codec.encode(&token(1), &mut wire).unwrap();
+    wire.truncate(wire.len() - 3);
+
+    assert!(matches!(
+        codec.decode_eof(&mut wire),
+        Err(CodecError::Io(_))
+    ));
+}
+
+#[test]
+fn codec_refuses_overflowing_payload_len() {
+    let mut wire = BytesMut::new();
+    TokenCodec::new().encode(&token(1), &mut wire).unwrap();
+    wire[18..22].copy_from_slice(&u32::MAX.to_le_bytes());
+
+    assert!(matches!(
+        TokenCodec::new().decode(&mut wire),
+        Err(CodecError::FrameTooLarge(_))
+    ));
+    assert_eq!(
+        TokenCodec::DEFAULT_MAX_FRAME_LEN,
+        toon_format::TokenDecoder::DEFAULT_MAX_FRAME_LEN
+    );
+}
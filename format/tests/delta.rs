@@ -0,0 +1,167 @@
+use uuid::Uuid;
+
+use toon_format::{
+    constants, toon, DeserializeError, Deserializer, Metadata, Serializer, Token, TokenId,
+    TokenRegistry, Value,
+};
+
+fn order(id: u8, status: &str, note: &str) -> Token {
+    let value = toon! {
+        "customer_id": 42,
+        "status": status,
+        "note": note,
+        "line_items": [
+            { "sku": "A-1", "quantity": 2, "description": "walnut side table, oiled finish" },
+            { "sku": "B-2", "quantity": 1, "description": "linen cushion cover, natural" },
+        ],
+        "shipping_address": { "street": "Bahnhofstrasse 1", "city": "Zurich", "country": "CH" },
+    };
+    Token::new(
+        TokenId::from(Uuid::from_bytes([id; 16])),
+        value,
+        Metadata::new(0, 0),
+    )
+}
+
+#[test]
+fn delta_round_trip_through_registry() {
+    let base = order(1, "pending", "");
+    let target = order(2, "shipped", "left at the front door");
+
+    let serializer = Serializer::new();
+    let bytes = serializer.serialize_delta(&base, &target).unwrap();
+    let full = serializer.serialize(&target).unwrap();
+    assert_eq!(bytes[17], constants::TYPE_DELTA);
+    assert!(bytes.len() < full.len() / 2);
+
+    let registry = TokenRegistry::new();
+    registry.register(base.clone());
+
+    let decoded = Deserializer::new(&bytes)
+        .deserialize_with_base(&registry)
+        .unwrap();
+    assert_eq!(decoded, target);
+}
+
+#[test]
+fn delta_base_is_readable_without_decoding() {
+    let base = order(1, "pending", "");
+    let target = order(2, "shipped", "");
+    let bytes = Serializer::new().serialize_delta(&base, &target).unwrap();
+    let base_frame = Serializer::new().serialize(&base).unwrap();
+    let base_checksum = u32::from_le_bytes(base_frame[base_frame.len() - 4..].try_into().unwrap());
+
+    let (id, checksum) = Deserializer::new(&bytes).delta_base().unwrap().unwrap();
+    assert_eq!(id, base.id());
+    assert_eq!(checksum, base_checksum);
+
+    assert_eq!(Deserializer::new(&base_frame).delta_base().unwrap(), None);
+}
+
+#[test]
+fn delta_output_is_capped() {
+    let base = order(1, "pending", "");
+    let target = order(2, "shipped", "left at the front door");
+    let bytes = Serializer::new().serialize_delta(&base, &target).unwrap();
+    let payload_len = Serializer::new().serialize(&target).unwrap().len() - 26;
+
+    let capped = Deserializer::new(&bytes).with_max_delta_len(payload_len - 1);
+    assert_eq!(
+        capped.deserialize_against(&base),
+        Err(DeserializeError::InvalidDelta)
+    );
+    let exact = Deserializer::new(&bytes).with_max_delta_len(payload_len);
+    assert_eq!(exact.deserialize_against(&base).unwrap(), target);
+}
+
+#[test]
+fn delta_tombstones_are_not_read_without_the_base() {
+    let base = order(1, "pending", "");
+    let target = Token::tombstone(TokenId::from(Uuid::from_bytes([3; 16])), 17);
+    let bytes = Serializer::new().serialize_delta(&base, &target).unwrap();
+
+    let deserializer = Deserializer::new(&bytes);
+    assert_eq!(deserializer.tombstone(), Err(DeserializeError::Delta));
+    assert_eq!(deserializer.metadata(), Err(DeserializeError::Delta));
+
+    let registry = TokenRegistry::new();
+    registry.register(base);
+    let decoded = deserializer.deserialize_with_base(&registry).unwrap();
+    assert_eq!(decoded.deleted_at_ms(), Some(17));
+}
+
+#[test]
+fn delta_requires_a_base() {
+    let base = order(1, "pending", "");
+    let target = order(2, "shipped", "");
+    let bytes = Serializer::new().serialize_delta(&base, &target).unwrap();
+
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize(),
+        Err(DeserializeError::Delta)
+    );
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize_with_base(&TokenRegistry::new()),
+        Err(DeserializeError::BaseNotFound(base.id()))
+    );
+}
+
+#[test]
+fn delta_rejects_a_changed_base() {
+    let base = order(1, "pending", "");
+    let target = order(2, "shipped", "");
+    let bytes = Serializer::new().serialize_delta(&base, &target).unwrap();
+
+    let registry = TokenRegistry::new();
+    registry.register(order(1, "cancelled", ""));
+
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize_with_base(&registry),
+        Err(DeserializeError::BaseChecksumMismatch)
+    );
+}
+
+#[test]
+fn delta_handles_unrelated_and_scalar_values() {
+    let id = |b| TokenId::from(Uuid::from_bytes([b; 16]));
+    let cases = [
+        (Value::Null, Value::from("hello")),
+        (
+            Value::from("a long string that shares nothing"),
+            Value::from(7),
+        ),
+        (toon!([1, 2, 3]), toon!([])),
+        (toon!([]), toon!([1, 2, 3])),
+    ];
+
+    for (base, target) in cases {
+        let base = Token::new(id(1), base, Metadata::new(0, 0));
+        let target = Token::new(id(2), target, Metadata::new(0, 0));
+        let bytes = Serializer::new().serialize_delta(&base, &target).unwrap();
+        let decoded = Deserializer::new(&bytes)
+            .deserialize_against(&base)
+            .unwrap();
+        assert_eq!(decoded, target);
+    }
+}
+
+#[test]
+fn delta_rejects_out_of_range_copies() {
+    let base = order(1, "pending", "");
+    let target = order(2, "shipped", "");
+    let mut bytes = Serializer::new().serialize_delta(&base, &target).unwrap();
+
+    // First instruction is a copy from offset 0; push its offset past the base.
+    assert_eq!(bytes[22 + 21], 0x01);
+    bytes[22 + 22..22 + 26].copy_from_slice(&u32::MAX.to_le_bytes());
+    let end = bytes.len() - 4;
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&bytes[..end]);
+    let checksum = hasher.finalize();
+    bytes[end..].copy_from_slice(&checksum.to_le_bytes());
+
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize_against(&base),
+        Err(DeserializeError::InvalidDelta)
+    );
+}
@@ -1,10 +1,9 @@
-use std::collections::HashMap;
-
 use crc32fast::Hasher;
 use uuid::Uuid;
 
+use toon_format::collections::HashMap;
 use toon_format::{
-    DeserializeError, Deserializer, Metadata, Serializer, Token, TokenId, TokenRef,
+    DeserializeError, Deserializer, Metadata, MetadataFlags, Serializer, Token, TokenId, TokenRef,
     TokenRefStrength, Value,
 };
 
@@ -26,22 +25,22 @@ fn round_trip_all_value_types() {
     let meta = Metadata::new(0, 0);
 
     let tokens = vec![
-        Token::new(id, Value::Null, meta),
-        Token::new(id, Value::Bool(false), meta),
-        Token::new(id, Value::Bool(true), meta),
-        Token::new(id, Value::Int(-123), meta),
-        Token::new(id, Value::Float(1.25), meta),
-        Token::new(id, Value::String("".to_string()), meta),
-        Token::new(id, Value::Ref(TokenRef::strong(ref_id)), meta),
+        Token::new(id, Value::Null, meta.clone()),
+        Token::new(id, Value::Bool(false), meta.clone()),
+        Token::new(id, Value::Bool(true), meta.clone()),
+        Token::new(id, Value::Int(-123), meta.clone()),
+        Token::new(id, Value::Float(1.25), meta.clone()),
+        Token::new(id, Value::String("".to_string()), meta.clone()),
+        Token::new(id, Value::Ref(TokenRef::strong(ref_id)), meta.clone()),
         Token::new(
             id,
             Value::Array(vec![Value::Int(1), Value::String("x".to_string())]),
-            meta,
+            meta.clone(),
         ),
         {
             let mut map = HashMap::new();
             map.insert("k".to_string(), Value::Int(7));
-            Token::new(id, Value::Object(map), meta)
+            Token::new(id, Value::Object(map), meta.clone())
         },
     ];
 
@@ -164,3 +163,230 @@ fn deserialize_rejects_invalid_reference_strength() {
     let err = Deserializer::new(&bytes).deserialize().unwrap_err();
     assert_eq!(err, DeserializeError::InvalidReferenceStrength);
 }
+
+#[test]
+fn deserialize_reads_reference_fragments() {
+    let target = TokenId::from(Uuid::from_bytes([56u8; 16]));
+    let mut payload = vec![toon_format::constants::REF_WEAK | toon_format::constants::REF_FRAGMENT];
+    payload.extend_from_slice(target.as_bytes());
+    payload.extend_from_slice(b"/chapters/3");
+
+    let bytes = build_bytes(toon_format::constants::TYPE_REF, &payload);
+    let token = Deserializer::new(&bytes).deserialize().unwrap();
+    assert_eq!(
+        token.value(),
+        &Value::Ref(TokenRef::weak(target).with_fragment("/chapters/3"))
+    );
+}
+
+#[test]
+fn deserialize_reads_pinned_references() {
+    let target = TokenId::from(Uuid::from_bytes([58u8; 16]));
+    let mut payload =
+        vec![toon_format::constants::REF_PINNED | toon_format::constants::REF_FRAGMENT];
+    payload.extend_from_slice(target.as_bytes());
+    payload.extend_from_slice(&0x0102_0304u32.to_le_bytes());
+    payload.extend_from_slice(b"/a");
+
+    let bytes = build_bytes(toon_format::constants::TYPE_REF, &payload);
+    let token = Deserializer::new(&bytes).deserialize().unwrap();
+    assert_eq!(
+        token.value(),
+        &Value::Ref(
+            TokenRef::strong(target)
+                .pinned(0x0102_0304)
+                .with_fragment("/a")
+        )
+    );
+}
+
+#[test]
+fn deserialize_rejects_truncated_reference_pins() {
+    let mut payload = vec![toon_format::constants::REF_PINNED];
+    payload.extend_from_slice(&[59u8; 16]);
+    payload.extend_from_slice(&[1, 2, 3]);
+
+    let bytes = build_bytes(toon_format::constants::TYPE_REF, &payload);
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize().unwrap_err(),
+        DeserializeError::InvalidLength
+    );
+}
+
+#[test]
+fn deserialize_rejects_malformed_reference_fragments() {
+    let fragment_flag = toon_format::constants::REF_FRAGMENT;
+    let cases: [(u8, &[u8], DeserializeError); 3] = [
+        (fragment_flag, b"", DeserializeError::InvalidLength),
+        (0, b"/a", DeserializeError::InvalidLength),
+        (fragment_flag, &[0xFF], DeserializeError::InvalidUtf8),
+    ];
+    for (flags, fragment, expected) in cases {
+        let mut payload = vec![flags];
+        payload.extend_from_slice(&[57u8; 16]);
+        payload.extend_from_slice(fragment);
+
+        let bytes = build_bytes(toon_format::constants::TYPE_REF, &payload);
+        assert_eq!(
+            Deserializer::new(&bytes).deserialize().unwrap_err(),
+            expected
+        );
+    }
+}
+
+#[test]
+fn deserialize_round_trips_tombstones() {
+    let id = TokenId::from(Uuid::from_bytes([60u8; 16]));
+    let tombstone = Token::tombstone(id, 42);
+    let bytes = Serializer::new().serialize(&tombstone).unwrap();
+
+    let deserializer = Deserializer::new(&bytes);
+    assert_eq!(deserializer.tombstone(), Ok(Some(42)));
+    assert_eq!(deserializer.deserialize().unwrap(), tombstone);
+
+    let live = Serializer::new()
+        .serialize(&Token::new(id, Value::Int(42), Metadata::new(0, 0)))
+        .unwrap();
+    assert_eq!(Deserializer::new(&live).tombstone(), Ok(None));
+}
+
+#[test]
+fn deserialize_rejects_malformed_tombstones() {
+    let bytes = build_bytes(toon_format::constants::TYPE_TOMBSTONE, &[0; 7]);
+    assert_eq!(
+        Deserializer::new(&bytes).tombstone(),
+        Err(DeserializeError::InvalidLength)
+    );
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize().unwrap_err(),
+        DeserializeError::InvalidLength
+    );
+
+    // Tombstones are frames, not values.
+    let mut payload = 1u32.to_le_bytes().to_vec();
+    payload.push(toon_format::constants::TYPE_TOMBSTONE);
+    payload.extend_from_slice(&8u32.to_le_bytes());
+    payload.extend_from_slice(&[0; 8]);
+    let bytes = build_bytes(toon_format::constants::TYPE_ARRAY, &payload);
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize().unwrap_err(),
+        DeserializeError::UnknownTypeMarker(toon_format::constants::TYPE_TOMBSTONE)
+    );
+}
+
+#[test]
+fn deserialize_round_trips_metadata() {
+    let id = TokenId::from(Uuid::from_bytes([61u8; 16]));
+    let metadata = Metadata::new(1_700_000_000_000, 0)
+        .with_flags(MetadataFlags::COMPRESSED | MetadataFlags::CANONICAL)
+        .with_attribute("content-type", "text/plain")
+        .with_attribute("labels", "a,b");
+    let token = Token::new(id, Value::from("body"), metadata.clone());
+    let bytes = Serializer::new().serialize(&token).unwrap();
+
+    let deserializer = Deserializer::new(&bytes);
+    assert_eq!(
+        deserializer.header().unwrap().type_marker,
+        toon_format::constants::TYPE_METADATA
+    );
+    assert_eq!(
+        deserializer.value_marker(),
+        Ok(toon_format::constants::TYPE_STRING)
+    );
+    assert_eq!(deserializer.metadata(), Ok(metadata));
+    assert_eq!(deserializer.tombstone(), Ok(None));
+    assert_eq!(deserializer.deserialize().unwrap(), token);
+
+    let bare = Serializer::new()
+        .serialize(&Token::new(id, Value::Null, Metadata::new(0, 0)))
+        .unwrap();
+    let deserializer = Deserializer::new(&bare);
+    assert_eq!(
+        deserializer.header().unwrap().type_marker,
+        toon_format::constants::TYPE_NULL
+    );
+    assert_eq!(
+        deserializer.value_marker(),
+        Ok(toon_format::constants::TYPE_NULL)
+    );
+    assert_eq!(deserializer.metadata(), Ok(Metadata::new(0, 0)));
+}
+
+#[test]
+fn tombstones_keep_their_metadata() {
+    let id = TokenId::from(Uuid::from_bytes([62u8; 16]));
+    let bare = Serializer::new()
+        .serialize(&Token::tombstone(id, 7))
+        .unwrap();
+    assert_eq!(
+        Deserializer::new(&bare).metadata().unwrap().flags,
+        MetadataFlags::TOMBSTONE
+    );
+
+    let tombstone =
+        Token::tombstone_with_metadata(id, Metadata::new(8, 0).with_attribute("deleted-by", "gc"));
+    let bytes = Serializer::new().serialize(&tombstone).unwrap();
+    let deserializer = Deserializer::new(&bytes);
+    assert_eq!(
+        deserializer.header().unwrap().type_marker,
+        toon_format::constants::TYPE_METADATA
+    );
+    assert_eq!(deserializer.tombstone(), Ok(Some(8)));
+    assert_eq!(deserializer.deserialize().unwrap(), tombstone);
+}
+
+#[test]
+fn tombstone_flag_does_not_make_a_tombstone() {
+    let id = TokenId::from(Uuid::from_bytes([63u8; 16]));
+    for value in [Value::Int(5), Value::Null] {
+        let token = Token::new(id, value, Metadata::new(9, 0x08));
+        assert!(!token.is_tombstone());
+
+        let bytes = Serializer::new().serialize(&token).unwrap();
+        let deserializer = Deserializer::new(&bytes);
+        assert_eq!(deserializer.tombstone(), Ok(None));
+        let decoded = deserializer.deserialize().unwrap();
+        assert!(!decoded.is_tombstone());
+        assert_eq!(decoded, token);
+    }
+}
+
+#[test]
+fn deserialize_rejects_malformed_metadata_sections() {
+    let mut section = 0u32.to_le_bytes().to_vec();
+    section.extend_from_slice(&0u64.to_le_bytes());
+    section.extend_from_slice(&1u32.to_le_bytes());
+
+    let mut truncated = section.clone();
+    truncated.extend_from_slice(&5u32.to_le_bytes());
+    truncated.extend_from_slice(b"ab");
+
+    let mut bad_utf8 = section.clone();
+    bad_utf8.extend_from_slice(&1u32.to_le_bytes());
+    bad_utf8.push(0xFF);
+    bad_utf8.extend_from_slice(&0u32.to_le_bytes());
+    bad_utf8.push(toon_format::constants::TYPE_NULL);
+
+    let mut nested = 0u32.to_le_bytes().to_vec();
+    nested.extend_from_slice(&0u64.to_le_bytes());
+    nested.extend_from_slice(&0u32.to_le_bytes());
+    nested.push(toon_format::constants::TYPE_METADATA);
+    nested.extend_from_slice(&section);
+
+    let cases = [
+        (&section[..4], DeserializeError::InvalidLength),
+        (&truncated[..], DeserializeError::InvalidLength),
+        (&bad_utf8[..], DeserializeError::InvalidUtf8),
+        (
+            &nested[..],
+            DeserializeError::UnknownTypeMarker(toon_format::constants::TYPE_METADATA),
+        ),
+    ];
+    for (payload, expected) in cases {
+        let bytes = build_bytes(toon_format::constants::TYPE_METADATA, payload);
+        assert_eq!(
+            Deserializer::new(&bytes).deserialize().unwrap_err(),
+            expected
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use toon_format::encryption::{KeyProvider, KeyRing};
+use toon_format::{
+    constants, DeserializeError, Deserializer, Metadata, Serializer, Token, TokenId, Value,
+};
+
+fn sample_token() -> Token {
+    let id = TokenId::from(Uuid::from_bytes([60u8; 16]));
+    let mut map = HashMap::new();
+    map.insert(
+        "email".to_string(),
+        Value::String("a@example.com".to_string()),
+    );
+    map.insert("age".to_string(), Value::Int(42));
+    Token::new(id, Value::Object(map), Metadata::new(0, 0))
+}
+
+#[test]
+fn encrypted_round_trip() {
+    let keys = KeyRing::new(1, [7u8; 32]);
+    let token = sample_token();
+
+    let bytes = Serializer::new()
+        .serialize_encrypted(&token, &keys)
+        .unwrap();
+    let decoded = Deserializer::new(&bytes)
+        .deserialize_decrypted(&keys)
+        .unwrap();
+
+    assert_eq!(decoded.id(), token.id());
+    assert_eq!(decoded.value(), token.value());
+}
+
+#[test]
+fn header_stays_readable_and_payload_is_opaque() {
+    let keys = KeyRing::new(1, [7u8; 32]);
+    let token = sample_token();
+
+    let bytes = Serializer::new()
+        .serialize_encrypted(&token, &keys)
+        .unwrap();
+    let deser = Deserializer::new(&bytes);
+
+    let header = deser.header().unwrap();
+    assert_eq!(header.id, [60u8; 16]);
+    assert_eq!(header.type_marker, constants::TYPE_ENCRYPTED);
+    assert_eq!(deser.key_id().unwrap(), Some(1));
+
+    let needle = b"a@example.com";
+    assert!(!bytes.windows(needle.len()).any(|w| w == needle));
+
+    assert_eq!(
+        deser.deserialize().unwrap_err(),
+        DeserializeError::Encrypted
+    );
+}
+
+#[test]
+fn plaintext_tokens_decode_through_decrypting_path() {
+    let keys = KeyRing::new(1, [7u8; 32]);
+    let token = sample_token();
+
+    let bytes = Serializer::new().serialize(&token).unwrap();
+    let deser = Deserializer::new(&bytes);
+
+    assert_eq!(deser.key_id().unwrap(), None);
+    assert_eq!(deser.deserialize_decrypted(&keys).unwrap(), token);
+}
+
+#[test]
+fn wrong_key_fails_authentication() {
+    let keys = KeyRing::new(1, [7u8; 32]);
+    let other = KeyRing::new(1, [8u8; 32]);
+
+    let bytes = Serializer::new()
+        .serialize_encrypted(&sample_token(), &keys)
+        .unwrap();
+
+    let err = Deserializer::new(&bytes)
+        .deserialize_decrypted(&other)
+        .unwrap_err();
+    assert_eq!(err, DeserializeError::DecryptionFailed);
+}
+
+#[test]
+fn tampered_header_fails_authentication() {
+    let keys = KeyRing::new(1, [7u8; 32]);
+    let mut bytes = Serializer::new()
+        .serialize_encrypted(&sample_token(), &keys)
+        .unwrap();
+
+    // Re-point the token at another id and fix up the CRC; only the AEAD can notice.
+    bytes[1] ^= 0x01;
+    let checksum_offset = bytes.len() - 4;
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&bytes[..checksum_offset]);
+    let checksum = hasher.finalize();
+    bytes[checksum_offset..].copy_from_slice(&checksum.to_le_bytes());
+
+    let err = Deserializer::new(&bytes)
+        .deserialize_decrypted(&keys)
+        .unwrap_err();
+    assert_eq!(err, DeserializeError::DecryptionFailed);
+}
+
+#[test]
+fn rotation_keeps_old_tokens_readable() {
+    let mut keys = KeyRing::new(1, [7u8; 32]);
+    let token = sample_token();
+    let old = Serializer::new()
+        .serialize_encrypted(&token, &keys)
+        .unwrap();
+
+    keys.rotate(2, [9u8; 32]);
+    assert_eq!(keys.current_key_id(), 2);
+    assert_eq!(Deserializer::new(&old).key_id().unwrap(), Some(1));
+
+    let decoded = Deserializer::new(&old)
+        .deserialize_decrypted(&keys)
+        .unwrap();
+    let rotated = Serializer::new()
+        .serialize_encrypted(&decoded, &keys)
+        .unwrap();
+    assert_eq!(Deserializer::new(&rotated).key_id().unwrap(), Some(2));
+
+    assert!(keys.retire(1));
+    assert!(!keys.retire(2));
+    assert_eq!(
+        Deserializer::new(&old)
+            .deserialize_decrypted(&keys)
+            .unwrap_err(),
+        DeserializeError::MissingKey(1)
+    );
+    assert_eq!(
+        Deserializer::new(&rotated)
+            .deserialize_decrypted(&keys)
+            .unwrap(),
+        token
+    );
+}
+
+#[test]
+fn metadata_is_sealed_with_the_value() {
+    let keys = KeyRing::new(1, [7u8; 32]);
+    let sample = sample_token();
+    let token = Token::new(
+        sample.id(),
+        sample.value().clone(),
+        Metadata::new(5, 0).with_attribute("author", "ops"),
+    );
+
+    let bytes = Serializer::new()
+        .serialize_encrypted(&token, &keys)
+        .unwrap();
+    let deser = Deserializer::new(&bytes);
+    assert_eq!(
+        deser.header().unwrap().type_marker,
+        constants::TYPE_ENCRYPTED
+    );
+    assert_eq!(deser.metadata(), Err(DeserializeError::Encrypted));
+    assert_eq!(deser.deserialize_decrypted(&keys).unwrap(), token);
+}
+
+#[test]
+fn encrypted_tombstones_are_not_read_without_the_key() {
+    let keys = KeyRing::new(1, [7u8; 32]);
+    let token = Token::tombstone(sample_token().id(), 9);
+    let bytes = Serializer::new()
+        .serialize_encrypted(&token, &keys)
+        .unwrap();
+    let deser = Deserializer::new(&bytes);
+    assert_eq!(deser.tombstone(), Err(DeserializeError::Encrypted));
+    assert_eq!(deser.deserialize_decrypted(&keys).unwrap(), token);
+}
@@ -24,7 +24,7 @@ fn value_strategy() -> impl Strategy<Value = Value> {
                 inner,
                 0..8,
             )
-            .prop_map(Value::Object),
+            .prop_map(|entries| Value::Object(entries.into_iter().collect())),
         ]
     })
 }
@@ -43,6 +43,16 @@ proptest! {
         prop_assert_eq!(decoded.value(), &value);
     }
 
+    #[test]
+    fn proptest_encoded_len_matches_serialize(value in value_strategy()) {
+        let token = Token::new(TokenId::from(Uuid::from_bytes([0; 16])), value, Metadata::new(0, 0));
+
+        let bytes = Serializer::new().serialize(&token).unwrap();
+
+        prop_assert_eq!(Serializer::new().encoded_len(&token).unwrap(), bytes.len());
+        prop_assert_eq!(token.value().encoded_len(), bytes.len() - 26);
+    }
+
     #[test]
     fn proptest_deserialize_never_panics(input in proptest::collection::vec(any::<u8>(), 0..256)) {
         let bytes = input;
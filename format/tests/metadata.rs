@@ -1,14 +1,37 @@
-use toon_format::Metadata;
+use toon_format::{Metadata, MetadataFlags};
 
 #[test]
 fn metadata_default_flags_are_zero() {
     let m = Metadata::default();
-    assert_eq!(m.flags, 0);
+    assert!(m.flags.is_empty());
 }
 
 #[test]
 fn metadata_can_be_constructed_explicitly() {
     let m = Metadata::new(123, 7);
     assert_eq!(m.created_at_ms, 123);
-    assert_eq!(m.flags, 7);
+    assert_eq!(m.flags.bits(), 7);
+}
+
+#[test]
+fn metadata_flags_keep_unknown_bits() {
+    let m = Metadata::new(0, 0x8000_0004);
+    assert!(m.flags.contains(MetadataFlags::SIGNED));
+    assert_eq!(m.flags.bits(), 0x8000_0004);
+}
+
+#[test]
+fn metadata_attributes() {
+    let m = Metadata::new(5, 0)
+        .with_flags(MetadataFlags::SIGNED | MetadataFlags::CANONICAL)
+        .with_attribute("content-type", "application/json")
+        .with_attribute("author", "ops");
+    assert_eq!(m.attribute("content-type"), Some("application/json"));
+    assert_eq!(m.attribute("missing"), None);
+    assert_eq!(
+        m.attributes.keys().collect::<Vec<_>>(),
+        ["author", "content-type"]
+    );
+    assert!(!m.is_empty());
+    assert!(Metadata::new(0, 0).is_empty());
 }
@@ -0,0 +1,129 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashSet};
+use std::hash::{Hash, Hasher};
+
+use proptest::prelude::*;
+use uuid::Uuid;
+
+use toon_format::collections::HashMap;
+use toon_format::{toon, Deserializer, Metadata, Serializer, Token, TokenId, TokenRef, Value};
+
+fn hash_of(value: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn encode(value: &Value) -> Vec<u8> {
+    let id = TokenId::from(Uuid::from_bytes([80u8; 16]));
+    Serializer::new()
+        .serialize(&Token::new(id, value.clone(), Metadata::new(0, 0)))
+        .unwrap()
+}
+
+#[test]
+fn floats_use_total_order() {
+    assert_eq!(Value::Float(f64::NAN), Value::Float(f64::NAN));
+    assert_ne!(Value::Float(0.0), Value::Float(-0.0));
+    assert!(Value::Float(-0.0) < Value::Float(0.0));
+    assert!(Value::Float(f64::INFINITY) < Value::Float(f64::NAN));
+    assert!(Value::Float(f64::NEG_INFINITY) < Value::Float(-1e300));
+}
+
+#[test]
+fn nan_round_trips_equal() {
+    let value = toon!([f64::NAN, { "x": f64::NAN }]);
+    let bytes = encode(&value);
+    let decoded = Deserializer::new(&bytes).deserialize().unwrap();
+    assert_eq!(decoded.value(), &value);
+}
+
+#[test]
+fn variants_order_by_type_marker() {
+    let id = TokenId::from(Uuid::from_bytes([81u8; 16]));
+    let sorted = vec![
+        Value::Null,
+        Value::Bool(false),
+        Value::Bool(true),
+        Value::Int(i64::MIN),
+        Value::Int(5),
+        Value::Float(-1.0),
+        Value::String(String::new()),
+        Value::String("a".to_string()),
+        toon!([]),
+        toon!([1]),
+        toon!([1, 0]),
+        toon!({}),
+        toon!({ "a": 2 }),
+        toon!({ "b": 1 }),
+        Value::Ref(TokenRef::strong(id)),
+        Value::Ref(TokenRef::weak(id)),
+    ];
+
+    let mut shuffled = sorted.clone();
+    shuffled.reverse();
+    shuffled.sort();
+    assert_eq!(shuffled, sorted);
+
+    let set: BTreeSet<Value> = sorted.iter().cloned().collect();
+    assert_eq!(set.len(), sorted.len());
+}
+
+#[test]
+fn objects_compare_and_hash_independently_of_insertion_order() {
+    let mut a = HashMap::new();
+    let mut b = HashMap::new();
+    for i in 0..64 {
+        a.insert(format!("k{i}"), Value::Int(i));
+    }
+    for i in (0..64).rev() {
+        b.insert(format!("k{i}"), Value::Int(i));
+    }
+    let (a, b) = (Value::Object(a), Value::Object(b));
+
+    assert_eq!(a, b);
+    assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
+    assert_eq!(hash_of(&a), hash_of(&b));
+    assert_eq!(encode(&a), encode(&b));
+
+    let set: HashSet<Value> = [a, b, toon!(f64::NAN), toon!(f64::NAN)]
+        .into_iter()
+        .collect();
+    assert_eq!(set.len(), 2);
+}
+
+fn value_strategy() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        (-2i64..2).prop_map(Value::Int),
+        prop_oneof![Just(0.0), Just(-0.0), Just(f64::NAN), Just(1.5)].prop_map(Value::Float),
+        proptest::string::string_regex("[ab]{0,2}")
+            .unwrap()
+            .prop_map(Value::String),
+    ];
+
+    leaf.prop_recursive(3, 24, 4, |inner| {
+        prop_oneof![
+            proptest::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+            proptest::collection::hash_map(
+                proptest::string::string_regex("[a-c]").unwrap(),
+                inner,
+                0..4
+            )
+            .prop_map(|entries| Value::Object(entries.into_iter().collect())),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn proptest_equality_matches_canonical_encoding(a in value_strategy(), b in value_strategy()) {
+        prop_assert_eq!(a == b, encode(&a) == encode(&b));
+        prop_assert_eq!(a == b, a.cmp(&b) == std::cmp::Ordering::Equal);
+        prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        if a == b {
+            prop_assert_eq!(hash_of(&a), hash_of(&b));
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Large containers cross the parallel threshold when the `rayon` feature is
+//! enabled. These tests pin their encodings so the sequential and parallel
+//! builds are checked against the same bytes.
+
+use crc32fast::Hasher;
+use uuid::Uuid;
+
+use toon_format::collections::HashMap;
+use toon_format::{
+    constants, toon, BatchDeserializer, BatchSerializer, DeserializeError, Deserializer, Metadata,
+    Serializer, Token, TokenId, Value,
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut hasher = Hasher::new();
+    hasher.update(bytes);
+    hasher.finalize()
+}
+
+fn large_value() -> Value {
+    let rows: Vec<Value> = (0..3000i64)
+        .map(|i| {
+            toon! {
+                "id": i,
+                "name": format!("row-{i}"),
+                "score": i as f64 / 7.0,
+                "flags": [i % 2 == 0, i % 3 == 0],
+            }
+        })
+        .collect();
+    let index: HashMap<String, Value> = (0..2000i64)
+        .map(|i| {
+            (
+                format!("key-{i:05}"),
+                Value::Array((0..i % 5).map(Value::Int).collect()),
+            )
+        })
+        .collect();
+    toon! { "rows": rows, "index": Value::Object(index) }
+}
+
+fn token(value: Value) -> Token {
+    Token::new(
+        TokenId::from(Uuid::from_bytes([4; 16])),
+        value,
+        Metadata::new(0, 0),
+    )
+}
+
+#[test]
+fn large_values_encode_to_pinned_bytes() {
+    let token = token(large_value());
+    let bytes = Serializer::new().serialize(&token).unwrap();
+
+    assert_eq!(
+        (bytes.len(), crc32(&bytes[..bytes.len() - 4])),
+        (391955, 2900576307)
+    );
+    assert_eq!(Deserializer::new(&bytes).deserialize().unwrap(), token);
+}
+
+#[test]
+fn large_batches_encode_to_pinned_bytes() {
+    let tokens = vec![token(large_value()), token(toon! { "rows": [] })];
+    let bytes = BatchSerializer::new().serialize(&tokens).unwrap();
+
+    assert_eq!(
+        (bytes.len(), crc32(&bytes[..bytes.len() - 4])),
+        (352045, 2686948628)
+    );
+    assert_eq!(
+        BatchDeserializer::new(&bytes).deserialize().unwrap(),
+        tokens
+    );
+}
+
+#[test]
+fn large_arrays_match_the_wire_layout() {
+    let items: Vec<Value> = (0..5000).map(Value::Int).collect();
+    let bytes = Serializer::new()
+        .serialize(&token(Value::Array(items)))
+        .unwrap();
+
+    let mut expected = 5000u32.to_le_bytes().to_vec();
+    for i in 0..5000i64 {
+        expected.push(constants::TYPE_INT64);
+        expected.extend_from_slice(&8u32.to_le_bytes());
+        expected.extend_from_slice(&i.to_le_bytes());
+    }
+    assert_eq!(&bytes[22..bytes.len() - 4], &expected[..]);
+}
+
+#[test]
+fn reports_the_first_bad_child_in_order() {
+    let mut items: Vec<Value> = (0..4000).map(|i| Value::String(format!("s{i}"))).collect();
+    items[3000] = Value::Int(1);
+    let bytes = Serializer::new()
+        .serialize(&token(Value::Array(items)))
+        .unwrap();
+
+    // Break item 10's UTF-8 and item 3000's marker; item 10 comes first.
+    let mut bytes = bytes;
+    let offset = |n: usize| 22 + 4 + (0..n).map(|i| 5 + format!("s{i}").len()).sum::<usize>();
+    bytes[offset(10) + 5] = 0xFF;
+    bytes[offset(3000)] = constants::TYPE_NULL;
+    let checksum_offset = bytes.len() - 4;
+    let checksum = crc32(&bytes[..checksum_offset]);
+    bytes[checksum_offset..].copy_from_slice(&checksum.to_le_bytes());
+
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize(),
+        Err(DeserializeError::InvalidUtf8)
+    );
+}
@@ -0,0 +1,176 @@
+use uuid::Uuid;
+
+use toon_format::{
+    toon, DeserializeError, Deserializer, Metadata, SerializeError, Serializer, Token, TokenId,
+    Value,
+};
+
+fn archived(size: usize) -> Token {
+    let lines: Vec<Value> = (0..size)
+        .map(|i| toon! { "line": i as i64, "text": format!("entry number {i}") })
+        .collect();
+    Token::new(
+        TokenId::from(Uuid::from_bytes([3; 16])),
+        toon! { "kind": "ledger", "lines": lines },
+        Metadata::new(0, 0),
+    )
+}
+
+/// Deterministic positions spread over `len` bytes.
+fn positions(len: usize, count: usize, seed: u64) -> Vec<usize> {
+    let mut state = seed;
+    let mut out = Vec::new();
+    while out.len() < count {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let pos = (state >> 33) as usize % len;
+        if !out.contains(&pos) {
+            out.push(pos);
+        }
+    }
+    out
+}
+
+#[test]
+fn parity_frames_read_like_plain_frames() {
+    let token = archived(3);
+    let plain = Serializer::new().serialize(&token).unwrap();
+    let bytes = Serializer::new().serialize_with_parity(&token, 16).unwrap();
+
+    assert_eq!(&bytes[..plain.len()], &plain[..]);
+    assert_eq!(bytes.len(), plain.len() + 16 + 27);
+
+    let deserializer = Deserializer::new(&bytes);
+    let layout = deserializer.layout().unwrap();
+    assert_eq!(layout.checksum_range, plain.len() - 4..plain.len());
+    assert_eq!(layout.parity_range, Some(plain.len()..bytes.len()));
+    assert_eq!(deserializer.deserialize().unwrap(), token);
+    assert_eq!(deserializer.deserialize_repaired().unwrap(), (token, 0));
+
+    assert_eq!(
+        Deserializer::new(&plain).layout().unwrap().parity_range,
+        None
+    );
+}
+
+#[test]
+fn repairs_scattered_errors_anywhere_in_the_artifact() {
+    let token = archived(2);
+    let clean = Serializer::new().serialize_with_parity(&token, 16).unwrap();
+    assert!(clean.len() <= 255, "expected a single block");
+
+    for seed in 0..20 {
+        let mut bytes = clean.clone();
+        for pos in positions(bytes.len(), 8, seed) {
+            bytes[pos] ^= 0x5A;
+        }
+
+        let (repaired, corrected) = Deserializer::new(&bytes).deserialize_repaired().unwrap();
+        assert_eq!(repaired, token, "seed {seed}");
+        assert_eq!(corrected, 8, "seed {seed}");
+    }
+}
+
+#[test]
+fn repairs_header_checksum_and_descriptor() {
+    let token = archived(2);
+    let mut bytes = Serializer::new().serialize_with_parity(&token, 8).unwrap();
+    let len = bytes.len();
+    bytes[0] = 0xEE; // version
+    bytes[19] ^= 0xFF; // payload length
+    bytes[len - 8 - 27 - 2] ^= 0x01; // checksum
+    bytes[len - 5] ^= 0xFF; // nsym of one descriptor copy
+
+    assert!(Deserializer::new(&bytes).deserialize().is_err());
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize_repaired().unwrap(),
+        (token, 4)
+    );
+}
+
+#[test]
+fn interleaving_spreads_bursts_across_blocks() {
+    let token = archived(40);
+    let clean = Serializer::new().serialize_with_parity(&token, 8).unwrap();
+    let blocks = (clean.len() - 27) / 255 + 1;
+    assert!(blocks >= 4);
+
+    // A burst of `blocks * 4` consecutive bytes puts 4 errors in each block.
+    let mut bytes = clean.clone();
+    let burst = blocks * 4;
+    for byte in &mut bytes[100..100 + burst] {
+        *byte = !*byte;
+    }
+
+    let (repaired, corrected) = Deserializer::new(&bytes).deserialize_repaired().unwrap();
+    assert_eq!(repaired, token);
+    assert_eq!(corrected, burst);
+}
+
+#[test]
+fn reports_unrepairable_damage() {
+    let token = archived(2);
+    let mut bytes = Serializer::new().serialize_with_parity(&token, 4).unwrap();
+    for pos in positions(bytes.len() - 27, 12, 7) {
+        bytes[pos] ^= 0xA5;
+    }
+
+    assert!(matches!(
+        Deserializer::new(&bytes).deserialize_repaired(),
+        Err(DeserializeError::Unrepairable | DeserializeError::ChecksumMismatch)
+    ));
+}
+
+#[test]
+fn plain_frames_pass_through_repair() {
+    let token = archived(1);
+    let bytes = Serializer::new().serialize(&token).unwrap();
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize_repaired().unwrap(),
+        (token, 0)
+    );
+}
+
+#[test]
+fn plain_frames_are_never_taken_for_parity() {
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        state
+    };
+    for _ in 0..20_000 {
+        let id = TokenId::from(Uuid::from_u64_pair(next(), next()));
+        let text: String = (0..next() % 24)
+            .map(|_| (b'a' + (next() % 26) as u8) as char)
+            .collect();
+        let token = Token::new(
+            id,
+            toon! { "n": next() as i64, "text": text },
+            Metadata::new(0, 0),
+        );
+        let bytes = Serializer::new().serialize(&token).unwrap();
+
+        assert_eq!(
+            Deserializer::new(&bytes).layout().unwrap().parity_range,
+            None
+        );
+        assert_eq!(
+            Deserializer::new(&bytes).deserialize_repaired().unwrap(),
+            (token, 0)
+        );
+    }
+}
+
+#[test]
+fn rejects_unsupported_parity_lengths() {
+    let token = archived(1);
+    for parity_len in [0, 1, 129, 255] {
+        assert!(matches!(
+            Serializer::new().serialize_with_parity(&token, parity_len),
+            Err(SerializeError::InvalidParityLength(n)) if n == parity_len
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+use proptest::prelude::*;
+
+use toon_format::patch::{apply, diff};
+use toon_format::text;
+use toon_format::{
+    Deserializer, Metadata, Patch, PatchError, PatchOp, Path, Serializer, Token, TokenId, Value,
+};
+
+fn v(src: &str) -> Value {
+    text::parse(src).unwrap()
+}
+
+#[test]
+fn diff_of_equal_values_is_empty() {
+    let a = v(r#"{"a": [1, 2, {"b": null}]}"#);
+    assert!(diff(&a, &a.clone()).is_empty());
+}
+
+#[test]
+fn diff_produces_keyed_operations() {
+    let old = v(r#"{"name": "x", "gone": 1, "tags": ["a", "b"]}"#);
+    let new = v(r#"{"name": "y", "tags": ["a", "b", "c"], "extra": true}"#);
+
+    let patch = diff(&old, &new);
+    assert_eq!(
+        patch.ops(),
+        &[
+            PatchOp::Remove {
+                path: Path::root().key("gone"),
+                old: Value::Int(1),
+            },
+            PatchOp::Add {
+                path: Path::root().key("extra"),
+                value: Value::Bool(true),
+            },
+            PatchOp::Replace {
+                path: Path::root().key("name"),
+                old: Value::String("x".to_string()),
+                new: Value::String("y".to_string()),
+            },
+            PatchOp::Add {
+                path: Path::root().key("tags").index(2),
+                value: Value::String("c".to_string()),
+            },
+        ]
+    );
+
+    let mut target = old.clone();
+    apply(&mut target, &patch).unwrap();
+    assert_eq!(target, new);
+}
+
+#[test]
+fn array_middle_insertion_and_removal_are_single_ops() {
+    let old = v("[1, 2, 3, 4]");
+
+    let inserted = diff(&old, &v("[1, 2, 9, 3, 4]"));
+    assert_eq!(
+        inserted.ops(),
+        &[PatchOp::Add {
+            path: Path::root().index(2),
+            value: Value::Int(9),
+        }]
+    );
+
+    let removed = diff(&old, &v("[1, 3, 4]"));
+    assert_eq!(
+        removed.ops(),
+        &[PatchOp::Remove {
+            path: Path::root().index(1),
+            old: Value::Int(2),
+        }]
+    );
+}
+
+#[test]
+fn nested_array_elements_are_patched_in_place() {
+    let old = v(r#"[{"qty": 1}, {"qty": 2}]"#);
+    let new = v(r#"[{"qty": 1}, {"qty": 3}]"#);
+
+    let patch = diff(&old, &new);
+    assert_eq!(patch.len(), 1);
+    assert_eq!(patch.ops()[0].path().to_string(), "/1/qty");
+}
+
+#[test]
+fn apply_detects_conflicts_and_leaves_target_untouched() {
+    let base = v(r#"{"a": 1, "b": [1, 2]}"#);
+    let patch = diff(&base, &v(r#"{"a": 2, "b": [1]}"#));
+
+    let mut drifted = v(r#"{"a": 1, "b": [1, 5]}"#);
+    let before = drifted.clone();
+    let err = apply(&mut drifted, &patch).unwrap_err();
+    assert_eq!(err, PatchError::Conflict(Path::root().key("b").index(1)));
+    assert_eq!(drifted, before);
+
+    let mut missing = v(r#"{"b": [1, 2]}"#);
+    assert_eq!(
+        apply(&mut missing, &patch).unwrap_err(),
+        PatchError::PathNotFound(Path::root().key("a"))
+    );
+
+    let add = Patch::new(vec![PatchOp::Add {
+        path: Path::root().key("a"),
+        value: Value::Null,
+    }]);
+    assert_eq!(
+        apply(&mut v(r#"{"a": 1}"#), &add).unwrap_err(),
+        PatchError::AlreadyExists(Path::root().key("a"))
+    );
+}
+
+#[test]
+fn patch_round_trips_through_a_token() {
+    let old = v(r#"{"items": [{"sku": "a"}, {"sku": "b"}], "n": 1}"#);
+    let new = v(r#"{"items": [{"sku": "a"}, {"sku": "c"}, {"sku": "d"}], "m": 1.5}"#);
+    let patch = diff(&old, &new);
+
+    let token = Token::new(TokenId::new(), patch.to_value(), Metadata::new(0, 0));
+    let bytes = Serializer::new().serialize(&token).unwrap();
+    let decoded = Deserializer::new(&bytes).deserialize().unwrap();
+
+    let restored = Patch::from_value(decoded.value()).unwrap();
+    assert_eq!(restored, patch);
+
+    let mut target = old;
+    apply(&mut target, &restored).unwrap();
+    assert_eq!(target, new);
+}
+
+#[test]
+fn from_value_rejects_malformed_patches() {
+    assert!(matches!(
+        Patch::from_value(&v("{}")),
+        Err(PatchError::InvalidEncoding(_))
+    ));
+    assert!(matches!(
+        Patch::from_value(&v(r#"[{"op": "move", "path": []}]"#)),
+        Err(PatchError::InvalidEncoding(_))
+    ));
+    assert!(matches!(
+        Patch::from_value(&v(r#"[{"op": "add", "path": [-1], "value": 1}]"#)),
+        Err(PatchError::InvalidEncoding(_))
+    ));
+}
+
+fn value_strategy() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        (0i64..4).prop_map(Value::Int),
+        proptest::string::string_regex("[a-c]{0,2}")
+            .unwrap()
+            .prop_map(Value::String),
+    ];
+
+    leaf.prop_recursive(3, 32, 5, |inner| {
+        prop_oneof![
+            proptest::collection::vec(inner.clone(), 0..5).prop_map(Value::Array),
+            proptest::collection::hash_map(
+                proptest::string::string_regex("[a-d]").unwrap(),
+                inner,
+                0..4
+            )
+            .prop_map(Value::Object),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn proptest_apply_diff_reproduces_target(old in value_strategy(), new in value_strategy()) {
+        let patch = diff(&old, &new);
+        let mut target = old.clone();
+        apply(&mut target, &patch).unwrap();
+        prop_assert_eq!(&target, &new);
+
+        let decoded = Patch::from_value(&patch.to_value()).unwrap();
+        prop_assert_eq!(decoded, patch);
+    }
+}
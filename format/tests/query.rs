@@ -0,0 +1,262 @@
+use std::borrow::Cow;
+
+use uuid::Uuid;
+
+use toon_format::text;
+use toon_format::{
+    Metadata, Path, Query, QueryError, RegistryError, Token, TokenId, TokenRef, TokenRegistry,
+    Value,
+};
+
+fn v(src: &str) -> Value {
+    text::parse(src).unwrap()
+}
+
+fn orders() -> Value {
+    v(r#"{
+        "orders": [
+            {"id": 1, "items": [{"sku": "a", "qty": 1}, {"sku": "b", "qty": 3}]},
+            {"id": 2, "items": [{"sku": "c", "qty": 5.5}, {"sku": "d"}]}
+        ],
+        "limit": 2
+    }"#)
+}
+
+fn run(query: &str, value: &Value) -> Vec<(String, Value)> {
+    Query::compile(query)
+        .unwrap()
+        .evaluate(value)
+        .into_iter()
+        .map(|m| (m.path.to_string(), m.value.into_owned()))
+        .collect()
+}
+
+#[test]
+fn filter_selects_matching_elements_with_paths() {
+    let value = orders();
+    assert_eq!(
+        run("$.orders[*].items[?(@.qty > 2)].sku", &value),
+        vec![
+            ("/orders/0/items/1/sku".to_string(), v(r#""b""#)),
+            ("/orders/1/items/0/sku".to_string(), v(r#""c""#)),
+        ]
+    );
+}
+
+#[test]
+fn matches_borrow_from_the_input() {
+    let value = orders();
+    let query: Query = "$.orders[0].id".parse().unwrap();
+    let matches = query.evaluate(&value);
+    assert_eq!(matches.len(), 1);
+    assert!(matches!(matches[0].value, Cow::Borrowed(Value::Int(1))));
+    assert_eq!(
+        matches[0].path,
+        Path::root().key("orders").index(0).key("id")
+    );
+}
+
+#[test]
+fn selectors_cover_indices_wildcards_and_descendants() {
+    let value = orders();
+
+    assert_eq!(
+        run("$.orders[-1].id", &value),
+        vec![("/orders/1/id".to_string(), Value::Int(2))]
+    );
+    assert_eq!(
+        run("$['limit']", &value),
+        vec![("/limit".to_string(), Value::Int(2))]
+    );
+    assert!(run("$.orders[5]", &value).is_empty());
+    assert!(run("$.missing.deeper", &value).is_empty());
+
+    let skus: Vec<_> = run("$..sku", &value).into_iter().map(|(_, v)| v).collect();
+    assert_eq!(
+        skus,
+        vec![v(r#""a""#), v(r#""b""#), v(r#""c""#), v(r#""d""#)]
+    );
+
+    assert_eq!(run("$.orders[0].*", &value).len(), 2);
+}
+
+#[test]
+fn filter_expressions_support_logic_existence_and_root_paths() {
+    let value = orders();
+
+    let ids = |q: &str| -> Vec<String> { run(q, &value).into_iter().map(|(p, _)| p).collect() };
+
+    assert_eq!(ids("$..items[?(!@.qty)]"), vec!["/orders/1/items/1"]);
+    assert_eq!(
+        ids("$..items[?(@.qty >= $.limit && @.sku != 'c')]"),
+        vec!["/orders/0/items/1"]
+    );
+    assert_eq!(
+        ids(r#"$..items[?(@.sku == "a" || (@.qty < 6 && @.qty > 5))]"#),
+        vec!["/orders/0/items/0", "/orders/1/items/0"]
+    );
+    assert_eq!(ids("$.orders[?(@.id == 2)].id"), vec!["/orders/1/id"]);
+}
+
+#[test]
+fn compile_reports_syntax_errors() {
+    assert_eq!(
+        Query::compile("orders").unwrap_err(),
+        QueryError::Syntax {
+            offset: 0,
+            message: "query must start with '$'"
+        }
+    );
+    assert!(matches!(
+        Query::compile("$.orders[?(@.qty >)]"),
+        Err(QueryError::Syntax { offset: 18, .. })
+    ));
+    assert!(Query::compile("$.a[").is_err());
+    assert!(Query::compile("$.a[?(1)]").is_err());
+    assert!(Query::compile("$.a b").is_err());
+}
+
+#[test]
+fn compile_limits_nesting() {
+    let too_deep = [
+        format!("$.a[?({}@.b)]", "(".repeat(100_000)),
+        format!("$.a[?({}@.b)]", "!".repeat(100_000)),
+        format!("$.a{}", "[?(@.b".repeat(100_000)),
+    ];
+    for src in &too_deep {
+        assert!(matches!(
+            Query::compile(src),
+            Err(QueryError::Syntax {
+                message: "expression nested too deeply",
+                ..
+            })
+        ));
+    }
+
+    let deepest = format!("$.a[?({}@.b)]", "!".repeat(127));
+    assert!(Query::compile(&deepest).is_ok());
+}
+
+#[test]
+fn registry_evaluation_follows_references() {
+    let registry = TokenRegistry::new();
+    let customer = TokenId::from(Uuid::from_bytes([90u8; 16]));
+    let gone = TokenId::from(Uuid::from_bytes([91u8; 16]));
+    registry.register(Token::new(
+        customer,
+        v(r#"{"name": "Ada", "tier": 3}"#),
+        Metadata::new(0, 0),
+    ));
+
+    let mut order = orders();
+    if let Value::Object(map) = &mut order {
+        map.insert(
+            "customer".to_string(),
+            Value::Ref(TokenRef::strong(customer)),
+        );
+        map.insert("referrer".to_string(), Value::Ref(TokenRef::weak(gone)));
+    }
+
+    let query = Query::compile("$.customer.name").unwrap();
+    assert!(query.evaluate(&order).is_empty());
+
+    let matches = query.evaluate_with_registry(&order, &registry).unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].path.to_string(), "/customer/name");
+    assert!(matches!(&matches[0].value, Cow::Owned(Value::String(s)) if s == "Ada"));
+
+    let filtered = Query::compile("$[?(@.tier > 2)]")
+        .unwrap()
+        .evaluate_with_registry(&order, &registry)
+        .unwrap();
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].path.to_string(), "/customer");
+
+    let weak = Query::compile("$.referrer.name")
+        .unwrap()
+        .evaluate_with_registry(&order, &registry)
+        .unwrap();
+    assert!(weak.is_empty());
+
+    if let Value::Object(map) = &mut order {
+        map.insert("customer".to_string(), Value::Ref(TokenRef::strong(gone)));
+    }
+    assert_eq!(
+        query.evaluate_with_registry(&order, &registry).unwrap_err(),
+        QueryError::Registry(RegistryError::NotFound(gone))
+    );
+}
+
+#[test]
+fn registry_evaluation_follows_reference_fragments() {
+    let registry = TokenRegistry::new();
+    let catalog = TokenId::from(Uuid::from_bytes([92u8; 16]));
+    registry.register(Token::new(
+        catalog,
+        v(r#"{"items": [{"sku": "A-1"}, {"sku": "B-2"}]}"#),
+        Metadata::new(0, 0),
+    ));
+
+    let order = Value::Object(
+        [
+            (
+                "item".to_string(),
+                Value::Ref(TokenRef::strong(catalog).with_fragment("/items/1")),
+            ),
+            (
+                "broken".to_string(),
+                Value::Ref(TokenRef::strong(catalog).with_fragment("/items/5")),
+            ),
+        ]
+        .into_iter()
+        .collect(),
+    );
+
+    let matches = Query::compile("$.item.sku")
+        .unwrap()
+        .evaluate_with_registry(&order, &registry)
+        .unwrap();
+    assert_eq!(matches.len(), 1);
+    assert!(matches!(&matches[0].value, Cow::Owned(Value::String(s)) if s == "B-2"));
+
+    assert_eq!(
+        Query::compile("$.broken.sku")
+            .unwrap()
+            .evaluate_with_registry(&order, &registry)
+            .unwrap_err(),
+        QueryError::Registry(RegistryError::FragmentNotFound(
+            catalog,
+            "/items/5".to_string()
+        ))
+    );
+}
+
+#[test]
+fn registry_evaluation_treats_tombstones_as_deleted() {
+    let registry = TokenRegistry::new();
+    let gone = TokenId::from(Uuid::from_bytes([93u8; 16]));
+    registry.register(Token::tombstone(gone, 1));
+
+    let order = Value::Object(
+        [
+            ("weak".to_string(), Value::Ref(TokenRef::weak(gone))),
+            ("strong".to_string(), Value::Ref(TokenRef::strong(gone))),
+        ]
+        .into_iter()
+        .collect(),
+    );
+
+    let matches = Query::compile("$.weak.name")
+        .unwrap()
+        .evaluate_with_registry(&order, &registry)
+        .unwrap();
+    assert!(matches.is_empty());
+
+    assert_eq!(
+        Query::compile("$.strong.name")
+            .unwrap()
+            .evaluate_with_registry(&order, &registry)
+            .unwrap_err(),
+        QueryError::Registry(RegistryError::Deleted(gone))
+    );
+}
@@ -1,7 +1,10 @@
+use std::sync::Arc;
+
 use uuid::Uuid;
 
 use toon_format::{
-    Metadata, RegistryError, Token, TokenId, TokenRef, TokenRefStrength, TokenRegistry, Value,
+    Metadata, RegistryError, SharedValue, Token, TokenId, TokenRef, TokenRefStrength,
+    TokenRegistry, Value,
 };
 
 #[test]
@@ -293,3 +296,179 @@ fn token_ref_strength_defaults_to_strong() {
     let weak = TokenRef::weak(id);
     assert_eq!(weak.strength(), TokenRefStrength::Weak);
 }
+
+#[test]
+fn get_shared_converts_once_per_registration() {
+    let registry = TokenRegistry::new();
+    let id = TokenId::from(Uuid::from_bytes([30u8; 16]));
+    let value = Value::Array(vec![Value::Int(1), Value::String("two".to_string())]);
+    registry.register(Token::new(id, value.clone(), Metadata::new(0, 0)));
+
+    let first = registry.get_shared(id).unwrap();
+    let second = registry.get_shared(id).unwrap();
+    assert_eq!(first, value);
+    match (&first, &second) {
+        (SharedValue::Array(a), SharedValue::Array(b)) => assert!(Arc::ptr_eq(a, b)),
+        _ => panic!("expected arrays"),
+    }
+
+    registry.register(Token::new(id, Value::Int(3), Metadata::new(0, 0)));
+    assert_eq!(registry.get_shared(id), Some(SharedValue::Int(3)));
+    assert_eq!(
+        registry.get_shared(TokenId::from(Uuid::from_bytes([31u8; 16]))),
+        None
+    );
+}
+
+#[test]
+fn pinned_references_resolve_only_their_version() {
+    let registry = TokenRegistry::new();
+    let id = TokenId::from(Uuid::from_bytes([42u8; 16]));
+    let v1 = Token::new(id, Value::Int(1), Metadata::new(0, 0));
+    let pinned = TokenRef::new(id).pinned(v1.checksum().unwrap());
+    registry.register(v1.clone());
+
+    assert_eq!(registry.resolve_ref(&pinned).unwrap().as_ref(), &v1);
+    assert_eq!(registry.resolve_value(&pinned), Ok(Value::Int(1)));
+
+    registry.register(Token::new(id, Value::Int(2), Metadata::new(0, 0)));
+    assert_eq!(
+        registry.resolve_ref(&pinned).unwrap_err(),
+        RegistryError::VersionMismatch(id)
+    );
+    assert_eq!(
+        registry.resolve_ref_or_load(&pinned, |_| None).unwrap_err(),
+        RegistryError::VersionMismatch(id)
+    );
+    assert!(registry.resolve_ref(&TokenRef::new(id)).is_ok());
+}
+
+#[test]
+fn pinned_references_check_loaded_tokens() {
+    let registry = TokenRegistry::new();
+    let id = TokenId::from(Uuid::from_bytes([43u8; 16]));
+    let v1 = Token::new(id, Value::Int(1), Metadata::new(0, 0));
+    let pinned = TokenRef::new(id).pinned(v1.checksum().unwrap());
+
+    let loaded = registry
+        .resolve_ref_or_load(&pinned, |_| Some(v1.clone()))
+        .unwrap();
+    assert_eq!(loaded.as_deref(), Some(&v1));
+
+    let other = TokenId::from(Uuid::from_bytes([44u8; 16]));
+    let stale = TokenRef::new(other).pinned(v1.checksum().unwrap());
+    assert_eq!(
+        registry
+            .resolve_ref_or_load(&stale, |id| Some(Token::new(
+                id,
+                Value::Int(2),
+                Metadata::new(0, 0)
+            )))
+            .unwrap_err(),
+        RegistryError::VersionMismatch(other)
+    );
+}
+
+#[test]
+fn resolve_value_follows_fragments() {
+    let registry = TokenRegistry::new();
+    let id = TokenId::from(Uuid::from_bytes([40u8; 16]));
+    let book = toon_format::toon! {
+        "title": "Atlas",
+        "chapters": [{ "name": "One" }, { "name": "Two" }],
+        "index": { "3": "three", "a/b": "slash" },
+    };
+    registry.register(Token::new(id, book.clone(), Metadata::new(0, 0)));
+
+    let at = |fragment: &str| registry.resolve_value(&TokenRef::new(id).with_fragment(fragment));
+    assert_eq!(registry.resolve_value(&TokenRef::new(id)), Ok(book));
+    assert_eq!(at("/chapters/1/name"), Ok(Value::from("Two")));
+    assert_eq!(at("/index/3"), Ok(Value::from("three")));
+    assert_eq!(at("/index/a~1b"), Ok(Value::from("slash")));
+
+    let missing = |fragment: &str, prefix: &str| {
+        assert_eq!(
+            at(fragment),
+            Err(RegistryError::FragmentNotFound(id, prefix.to_string()))
+        );
+    };
+    missing("/chapters/7/name", "/chapters/7");
+    missing("/chapters/01", "/chapters/01");
+    missing("/title/x", "/title/x");
+    missing("/nope/deeper", "/nope");
+    missing("chapters", "chapters");
+
+    let other = TokenId::from(Uuid::from_bytes([41u8; 16]));
+    assert_eq!(
+        registry.resolve_value(&TokenRef::new(other).with_fragment("/a")),
+        Err(RegistryError::NotFound(other))
+    );
+}
+
+#[test]
+fn tombstones_fail_strong_refs_and_hide_from_weak_refs() {
+    let registry = TokenRegistry::new();
+    let id = TokenId::from(Uuid::from_bytes([45u8; 16]));
+    registry.register(Token::new(id, Value::Int(1), Metadata::new(0, 0)));
+    registry.register(Token::tombstone(id, 99));
+
+    assert_eq!(registry.get(id).unwrap().deleted_at_ms(), Some(99));
+    assert_eq!(
+        registry.resolve_ref(&TokenRef::strong(id)).unwrap_err(),
+        RegistryError::Deleted(id)
+    );
+    assert_eq!(
+        registry.resolve_ref(&TokenRef::weak(id)).unwrap_err(),
+        RegistryError::NotFound(id)
+    );
+    assert_eq!(
+        registry
+            .resolve_ref_or_load(&TokenRef::strong(id), |_| panic!("cached"))
+            .unwrap_err(),
+        RegistryError::Deleted(id)
+    );
+    assert_eq!(
+        registry
+            .resolve_ref_or_load(&TokenRef::weak(id), |_| panic!("cached"))
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn loaded_tombstones_fail_strong_refs() {
+    let registry = TokenRegistry::new();
+    let id = TokenId::from(Uuid::from_bytes([46u8; 16]));
+    assert_eq!(
+        registry
+            .resolve_ref_or_load(&TokenRef::strong(id), |id| Some(Token::tombstone(id, 5)))
+            .unwrap_err(),
+        RegistryError::Deleted(id)
+    );
+    assert!(registry.get(id).unwrap().is_tombstone());
+}
+
+#[test]
+fn ensure_loaded_and_acyclic_rejects_strong_refs_to_tombstones() {
+    let registry = TokenRegistry::new();
+    let a = TokenId::from(Uuid::from_bytes([47u8; 16]));
+    let b = TokenId::from(Uuid::from_bytes([48u8; 16]));
+    let c = TokenId::from(Uuid::from_bytes([49u8; 16]));
+    registry.register(Token::tombstone(b, 1));
+    registry.register(Token::tombstone(c, 1));
+
+    let with =
+        |r: TokenRef| Value::Object([("r".to_string(), Value::Ref(r))].into_iter().collect());
+    registry.register(Token::new(a, with(TokenRef::weak(c)), Metadata::new(0, 0)));
+    registry.ensure_loaded_and_acyclic(a, |_| None).unwrap();
+
+    registry.register(Token::new(
+        a,
+        with(TokenRef::strong(b)),
+        Metadata::new(0, 0),
+    ));
+    assert_eq!(
+        registry.ensure_loaded_and_acyclic(a, |_| None),
+        Err(RegistryError::Deleted(b))
+    );
+}
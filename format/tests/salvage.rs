@@ -0,0 +1,112 @@
+use uuid::Uuid;
+
+use toon_format::{
+    toon, Damage, DeserializeError, Deserializer, Metadata, Path, Serializer, Token, TokenId, Value,
+};
+
+fn archived() -> Token {
+    Token::new(
+        TokenId::from(Uuid::from_bytes([9; 16])),
+        toon! {
+            "title": "quarterly report",
+            "author": "j.doe",
+            "pages": [1, 2, 3],
+            "sections": [{ "heading": "summary" }, { "heading": "figures" }],
+        },
+        Metadata::new(0, 0),
+    )
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> usize {
+    haystack
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .unwrap()
+}
+
+#[test]
+fn salvage_of_an_intact_token_is_lossless() {
+    let bytes = Serializer::new().serialize(&archived()).unwrap();
+    let salvage = Deserializer::new(&bytes).salvage().unwrap();
+
+    assert!(salvage.is_intact());
+    assert_eq!(salvage.token, archived());
+}
+
+#[test]
+fn salvage_isolates_a_corrupted_string() {
+    let mut bytes = Serializer::new().serialize(&archived()).unwrap();
+    let at = find(&bytes, b"j.doe");
+    bytes[at] = 0xFF;
+
+    assert_eq!(
+        Deserializer::new(&bytes).deserialize(),
+        Err(DeserializeError::ChecksumMismatch)
+    );
+
+    let salvage = Deserializer::new(&bytes).salvage().unwrap();
+    assert!(!salvage.checksum_valid);
+    assert_eq!(
+        salvage.damaged,
+        vec![Damage {
+            path: Path::root().key("author"),
+            error: DeserializeError::InvalidUtf8,
+        }]
+    );
+
+    let mut expected = archived().value().clone();
+    *expected.get_mut("author").unwrap() = Value::Null;
+    assert_eq!(salvage.token.value(), &expected);
+}
+
+#[test]
+fn salvage_keeps_entries_before_broken_framing() {
+    let mut bytes = Serializer::new().serialize(&archived()).unwrap();
+    // Inflate the length of the second section's heading so it overruns its
+    // enclosing object.
+    let at = find(&bytes, b"figures") - 4;
+    bytes[at..at + 4].copy_from_slice(&1000u32.to_le_bytes());
+
+    let salvage = Deserializer::new(&bytes).salvage().unwrap();
+    assert_eq!(
+        salvage.damaged,
+        vec![Damage {
+            path: Path::root().key("sections").index(1),
+            error: DeserializeError::Truncated,
+        }]
+    );
+    assert_eq!(
+        salvage.token.value()["sections"][0]["heading"],
+        Value::from("summary")
+    );
+    assert_eq!(salvage.token.value()["sections"][1], toon! {});
+    assert_eq!(salvage.token.value()["pages"], toon!([1, 2, 3]));
+}
+
+#[test]
+fn salvage_reads_a_truncated_frame() {
+    let bytes = Serializer::new().serialize(&archived()).unwrap();
+    let cut = find(&bytes, b"summary");
+    let truncated = [&bytes[..cut], &bytes[bytes.len() - 4..]].concat();
+
+    let salvage = Deserializer::new(&truncated).salvage().unwrap();
+    assert!(!salvage.checksum_valid);
+    assert_eq!(
+        salvage.damaged[0],
+        Damage {
+            path: Path::root(),
+            error: DeserializeError::Truncated,
+        }
+    );
+    assert_eq!(salvage.token.id(), archived().id());
+    assert_eq!(salvage.token.value()["author"], Value::from("j.doe"));
+    assert_eq!(salvage.token.value()["pages"], toon!([1, 2, 3]));
+}
+
+#[test]
+fn salvage_requires_a_header() {
+    assert_eq!(
+        Deserializer::new(&[1, 2, 3]).salvage(),
+        Err(DeserializeError::Truncated)
+    );
+}
@@ -1,9 +1,10 @@
 use crc32fast::Hasher;
-use std::collections::HashMap;
 use uuid::Uuid;
 
+use toon_format::collections::HashMap;
 use toon_format::{
-    constants, Metadata, Serializer, Token, TokenId, TokenRef, TokenRefStrength, Value,
+    constants, Metadata, MetadataFlags, Serializer, Token, TokenId, TokenRef, TokenRefStrength,
+    Value,
 };
 
 fn crc32(bytes: &[u8]) -> u32 {
@@ -242,3 +243,161 @@ fn serialize_ref_layout_and_checksum() {
         _ => panic!("expected ref"),
     }
 }
+
+#[test]
+fn encoded_len_matches_serialized_length() {
+    let mut inner = HashMap::new();
+    inner.insert("ключ".to_string(), Value::String("värde ✓".to_string()));
+    inner.insert(
+        "ref".to_string(),
+        Value::Ref(TokenRef::weak(TokenId::from(Uuid::from_bytes([1; 16])))),
+    );
+    let value = Value::Array(vec![
+        Value::Null,
+        Value::Bool(true),
+        Value::Int(-7),
+        Value::Float(0.25),
+        Value::Object(inner),
+        Value::Array(vec![]),
+    ]);
+    let token = Token::new(
+        TokenId::from(Uuid::from_bytes([2; 16])),
+        value,
+        Metadata::new(0, 0),
+    );
+
+    let bytes = Serializer::new().serialize(&token).unwrap();
+    let payload_len = u32::from_le_bytes(bytes[18..22].try_into().unwrap()) as usize;
+
+    assert_eq!(token.value().encoded_len(), payload_len);
+    assert_eq!(Serializer::new().encoded_len(&token).unwrap(), bytes.len());
+    assert_eq!(Value::Null.encoded_len(), 0);
+    assert_eq!(Value::Array(vec![]).encoded_len(), 4);
+}
+
+#[test]
+fn serialize_pinned_ref_layout() {
+    let target = TokenId::from(Uuid::from_bytes([3; 16]));
+    let reference = TokenRef::weak(target)
+        .pinned(0xA1B2_C3D4)
+        .with_fragment("/x");
+    let token = Token::new(
+        TokenId::from(Uuid::from_bytes([4; 16])),
+        Value::Ref(reference),
+        Metadata::new(0, 0),
+    );
+
+    let bytes = Serializer::new().serialize(&token).unwrap();
+    let len = u32::from_le_bytes(bytes[18..22].try_into().unwrap()) as usize;
+    assert_eq!(len, 17 + 4 + 2);
+    assert_eq!(
+        bytes[22],
+        constants::REF_WEAK | constants::REF_PINNED | constants::REF_FRAGMENT
+    );
+    assert_eq!(&bytes[23..39], &target.as_bytes()[..]);
+    assert_eq!(&bytes[39..43], &0xA1B2_C3D4u32.to_le_bytes());
+    assert_eq!(&bytes[43..45], b"/x");
+    assert_eq!(token.value().encoded_len(), len);
+}
+
+#[test]
+fn token_checksum_covers_only_the_value() {
+    let id = TokenId::from(Uuid::from_bytes([5; 16]));
+    let token = Token::new(id, Value::from("versioned"), Metadata::new(0, 0));
+    let bytes = Serializer::new().serialize(&token).unwrap();
+    let mut value = vec![bytes[17]];
+    value.extend_from_slice(&bytes[22..bytes.len() - 4]);
+    assert_eq!(token.checksum().unwrap(), crc32(&value));
+
+    let restamped = Token::new(
+        TokenId::from(Uuid::from_bytes([6; 16])),
+        Value::from("versioned"),
+        Metadata::new(1_000, 0).with_attribute("author", "ops"),
+    );
+    assert_eq!(restamped.checksum().unwrap(), token.checksum().unwrap());
+
+    let edited = Token::new(id, Value::from("versioned!"), Metadata::new(0, 0));
+    assert_ne!(edited.checksum().unwrap(), token.checksum().unwrap());
+}
+
+#[test]
+fn serialize_ref_fragment_layout() {
+    let target = TokenId::from(Uuid::from_bytes([3; 16]));
+    let reference = TokenRef::strong(target).with_fragment("/chapters/3");
+    let token = Token::new(
+        TokenId::from(Uuid::from_bytes([4; 16])),
+        Value::Ref(reference.clone()),
+        Metadata::new(0, 0),
+    );
+
+    let bytes = Serializer::new().serialize(&token).unwrap();
+    let len = u32::from_le_bytes(bytes[18..22].try_into().unwrap()) as usize;
+    assert_eq!(len, 17 + "/chapters/3".len());
+    assert_eq!(bytes[22], constants::REF_FRAGMENT);
+    assert_eq!(&bytes[23..39], &target.as_bytes()[..]);
+    assert_eq!(&bytes[39..22 + len], b"/chapters/3");
+    assert_eq!(token.value().encoded_len(), len);
+
+    // An empty fragment is the whole token and is not written.
+    assert_eq!(
+        TokenRef::strong(target).with_fragment(""),
+        TokenRef::strong(target)
+    );
+}
+
+#[test]
+fn serialize_tombstone_layout() {
+    let id = TokenId::from(Uuid::from_bytes([6; 16]));
+    let token = Token::tombstone(id, 1_700_000_000_000);
+    assert!(token.is_tombstone());
+    assert_eq!(token.deleted_at_ms(), Some(1_700_000_000_000));
+    assert_eq!(token.value(), &Value::Null);
+
+    let bytes = Serializer::new().serialize(&token).unwrap();
+    assert_eq!(bytes[17], constants::TYPE_TOMBSTONE);
+    assert_eq!(&bytes[18..22], &8u32.to_le_bytes());
+    assert_eq!(&bytes[22..30], &1_700_000_000_000u64.to_le_bytes());
+    assert_eq!(Serializer::new().encoded_len(&token).unwrap(), bytes.len());
+
+    let live = Token::new(id, Value::Null, Metadata::new(1_700_000_000_000, 0));
+    assert!(!live.is_tombstone());
+    assert_eq!(live.deleted_at_ms(), None);
+}
+
+#[test]
+fn serialize_metadata_section_layout() {
+    let id = TokenId::from(Uuid::from_bytes([7; 16]));
+    let metadata = Metadata::new(1_000, 0)
+        .with_flags(MetadataFlags::SIGNED)
+        .with_attribute("ct", "json");
+    let token = Token::new(id, Value::Int(9), metadata);
+
+    let bytes = Serializer::new().serialize(&token).unwrap();
+    assert_eq!(bytes[17], constants::TYPE_METADATA);
+    let len = u32::from_le_bytes(bytes[18..22].try_into().unwrap()) as usize;
+    let payload = &bytes[22..22 + len];
+
+    let mut expected = MetadataFlags::SIGNED.bits().to_le_bytes().to_vec();
+    expected.extend_from_slice(&1_000u64.to_le_bytes());
+    expected.extend_from_slice(&1u32.to_le_bytes());
+    expected.extend_from_slice(&2u32.to_le_bytes());
+    expected.extend_from_slice(b"ct");
+    expected.extend_from_slice(&4u32.to_le_bytes());
+    expected.extend_from_slice(b"json");
+    expected.push(constants::TYPE_INT64);
+    expected.extend_from_slice(&9i64.to_le_bytes());
+    assert_eq!(payload, expected.as_slice());
+    assert_eq!(Serializer::new().encoded_len(&token).unwrap(), bytes.len());
+
+    // Metadata without flags or attributes adds nothing to the frame, so a
+    // creation time alone does not change the bytes.
+    let bare = Serializer::new()
+        .serialize(&Token::new(id, Value::Int(9), Metadata::new(0, 0)))
+        .unwrap();
+    assert_eq!(bare[17], constants::TYPE_INT64);
+    for metadata in [Metadata::new(1_000, 0), Metadata::default()] {
+        let token = Token::new(id, Value::Int(9), metadata);
+        assert_eq!(Serializer::new().serialize(&token).unwrap(), bare);
+        assert_eq!(Serializer::new().encoded_len(&token).unwrap(), bare.len());
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use toon_format::{toon, Path, SharedValue, TokenId, TokenRef, Value};
+
+fn sample() -> Value {
+    toon! {
+        "title": "Field Notes",
+        "rating": 4.5,
+        "author": TokenRef::weak(TokenId::from(Uuid::from_bytes([1; 16]))),
+        "chapters": [
+            { "name": "Spring", "pages": 12 },
+            { "name": "Summer", "pages": 30 },
+        ],
+    }
+}
+
+#[test]
+fn converts_to_and_from_value() {
+    let value = sample();
+    let shared = SharedValue::from(&value);
+
+    assert_eq!(shared, value);
+    assert_eq!(value, shared);
+    assert_eq!(shared.to_value(), value);
+    assert_eq!(Value::from(shared.clone()), value);
+    assert_ne!(shared, Value::Null);
+}
+
+#[test]
+fn clones_share_subtrees() {
+    let shared = SharedValue::from(sample());
+    let chapters = shared.get("chapters").unwrap().clone();
+
+    let (SharedValue::Array(original), SharedValue::Array(cloned)) =
+        (shared.get("chapters").unwrap(), &chapters)
+    else {
+        panic!("expected arrays");
+    };
+    assert!(Arc::ptr_eq(original, cloned));
+
+    let name = shared.get_path(&Path::root().key("chapters").index(1).key("name"));
+    assert_eq!(name.and_then(SharedValue::as_str), Some("Summer"));
+    assert_eq!(
+        shared.get("rating").and_then(SharedValue::as_f64),
+        Some(4.5)
+    );
+    assert!(shared
+        .get("author")
+        .and_then(SharedValue::as_token_ref)
+        .is_some());
+}
+
+#[test]
+fn mutation_copies_only_shared_containers() {
+    let original = SharedValue::from(sample());
+    let mut edited = original.clone();
+
+    let chapters = edited
+        .make_object_mut()
+        .unwrap()
+        .get_mut("chapters")
+        .unwrap();
+    chapters
+        .make_array_mut()
+        .unwrap()
+        .push(SharedValue::from(toon! { "name": "Autumn", "pages": 8 }));
+
+    assert_eq!(
+        original.get("chapters").unwrap().as_array().unwrap().len(),
+        2
+    );
+    assert_eq!(edited.get("chapters").unwrap().as_array().unwrap().len(), 3);
+
+    // Untouched siblings are still shared between the two versions.
+    let (SharedValue::String(a), SharedValue::String(b)) =
+        (original.get("title").unwrap(), edited.get("title").unwrap())
+    else {
+        panic!("expected strings");
+    };
+    assert!(Arc::ptr_eq(a, b));
+}
+
+#[test]
+fn equality_matches_value() {
+    let nan = SharedValue::from(Value::Float(f64::NAN));
+    assert_eq!(nan, nan.clone());
+    assert_ne!(
+        SharedValue::from(Value::Float(0.0)),
+        SharedValue::from(Value::Float(-0.0))
+    );
+    assert_ne!(
+        SharedValue::from(toon! { "a": 1 }),
+        SharedValue::from(toon! { "a": 1, "b": 2 })
+    );
+}
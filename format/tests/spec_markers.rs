@@ -11,13 +11,19 @@ fn type_markers_are_unique() {
         constants::TYPE_INT64,
         constants::TYPE_F64,
         constants::TYPE_STRING,
+        constants::TYPE_STRING_DICT,
         constants::TYPE_ARRAY,
         constants::TYPE_OBJECT,
+        constants::TYPE_OBJECT_DICT,
         constants::TYPE_REF,
+        constants::TYPE_ENCRYPTED,
+        constants::TYPE_DELTA,
+        constants::TYPE_TOMBSTONE,
+        constants::TYPE_METADATA,
     ];
 
     let set: HashSet<u8> = markers.into_iter().collect();
-    assert_eq!(set.len(), 9);
+    assert_eq!(set.len(), markers.len());
 }
 
 #[test]
@@ -34,9 +40,30 @@ fn type_markers_are_stable() {
     assert_eq!(constants::TYPE_F64, 0x11);
 
     assert_eq!(constants::TYPE_STRING, 0x20);
+    assert_eq!(constants::TYPE_STRING_DICT, 0x21);
 
     assert_eq!(constants::TYPE_ARRAY, 0x30);
     assert_eq!(constants::TYPE_OBJECT, 0x31);
+    assert_eq!(constants::TYPE_OBJECT_DICT, 0x32);
 
     assert_eq!(constants::TYPE_REF, 0x40);
+    assert_eq!(constants::REF_WEAK, 0x01);
+    assert_eq!(constants::REF_PINNED, 0x40);
+    assert_eq!(constants::REF_FRAGMENT, 0x80);
+
+    assert_eq!(constants::TYPE_ENCRYPTED, 0x50);
+    assert_eq!(constants::TYPE_DELTA, 0x51);
+    assert_eq!(constants::TYPE_TOMBSTONE, 0x52);
+    assert_eq!(constants::TYPE_METADATA, 0x53);
+}
+
+#[test]
+fn metadata_flag_bits_are_stable() {
+    use toon_format::MetadataFlags;
+
+    assert_eq!(MetadataFlags::COMPRESSED.bits(), 0x01);
+    assert_eq!(MetadataFlags::ENCRYPTED.bits(), 0x02);
+    assert_eq!(MetadataFlags::SIGNED.bits(), 0x04);
+    assert_eq!(MetadataFlags::TOMBSTONE.bits(), 0x08);
+    assert_eq!(MetadataFlags::CANONICAL.bits(), 0x10);
 }
@@ -0,0 +1,47 @@
+use proptest::prelude::*;
+
+use toon_format::testing::{strategy, GraphConfig, ValueConfig};
+use toon_format::{
+    BatchDeserializer, BatchSerializer, Deserializer, RegistryError, Serializer, Token,
+    TokenRegistry,
+};
+
+fn registry_with(tokens: &[Token]) -> TokenRegistry {
+    let registry = TokenRegistry::new();
+    for token in tokens {
+        registry.register(token.clone());
+    }
+    registry
+}
+
+proptest! {
+    #[test]
+    fn proptest_round_trip_token(token in strategy::token(ValueConfig::default())) {
+        let bytes = Serializer::new().serialize(&token).unwrap();
+
+        prop_assert_eq!(Serializer::new().encoded_len(&token).unwrap(), bytes.len());
+        prop_assert_eq!(Deserializer::new(&bytes).deserialize().unwrap(), token);
+    }
+
+    #[test]
+    fn proptest_round_trip_batch(tokens in proptest::collection::vec(strategy::token(ValueConfig::default()), 0..8)) {
+        let bytes = BatchSerializer::new().with_value_dictionary(2).serialize(&tokens).unwrap();
+
+        prop_assert_eq!(BatchDeserializer::new(&bytes).deserialize().unwrap(), tokens);
+    }
+
+    #[test]
+    fn proptest_acyclic_graphs_load(tokens in strategy::token_graph(GraphConfig::default())) {
+        let registry = registry_with(&tokens);
+
+        prop_assert!(registry.ensure_loaded_and_acyclic(tokens[0].id(), |_| None).is_ok());
+    }
+
+    #[test]
+    fn proptest_cyclic_graphs_are_detected(tokens in strategy::token_graph(GraphConfig { cycle: true, ..GraphConfig::default() })) {
+        let registry = registry_with(&tokens);
+
+        let result = registry.ensure_loaded_and_acyclic(tokens[0].id(), |_| None);
+        prop_assert!(matches!(result, Err(RegistryError::CircularReference(_))));
+    }
+}
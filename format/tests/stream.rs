@@ -0,0 +1,121 @@
+use uuid::Uuid;
+
+use toon_format::{
+    toon, Decoded, DeserializeError, Metadata, Serializer, Token, TokenDecoder, TokenId,
+};
+
+fn token(id: u8) -> Token {
+    Token::new(
+        TokenId::from(Uuid::from_bytes([id; 16])),
+        toon! { "seq": i64::from(id), "tags": ["a", "b"], "note": "streamed" },
+        Metadata::new(0, 0),
+    )
+}
+
+fn stream(tokens: &[Token]) -> Vec<u8> {
+    let serializer = Serializer::new();
+    tokens
+        .iter()
+        .flat_map(|t| serializer.serialize(t).unwrap())
+        .collect()
+}
+
+fn drain(decoder: &mut TokenDecoder, mut chunk: &[u8], out: &mut Vec<Token>) -> usize {
+    loop {
+        match decoder.decode(&mut chunk).unwrap() {
+            Decoded::Token(token) => out.push(token),
+            Decoded::NeedMore(n) => {
+                assert!(chunk.is_empty());
+                return n;
+            }
+        }
+    }
+}
+
+#[test]
+fn decodes_any_chunking() {
+    let tokens: Vec<Token> = (0..5).map(token).collect();
+    let bytes = stream(&tokens);
+
+    for chunk_len in [1, 2, 7, 22, 23, 64, bytes.len()] {
+        let mut decoder = TokenDecoder::new();
+        let mut decoded = Vec::new();
+        for chunk in bytes.chunks(chunk_len) {
+            drain(&mut decoder, chunk, &mut decoded);
+        }
+        assert_eq!(decoded, tokens, "chunk length {chunk_len}");
+        assert_eq!(decoder.buffered_len(), 0);
+    }
+}
+
+#[test]
+fn reports_missing_bytes() {
+    let bytes = stream(&[token(1)]);
+    let mut decoder = TokenDecoder::new();
+    let mut decoded = Vec::new();
+
+    assert_eq!(drain(&mut decoder, &[], &mut decoded), 22);
+    assert_eq!(drain(&mut decoder, &bytes[..10], &mut decoded), 12);
+    assert_eq!(
+        drain(&mut decoder, &bytes[10..30], &mut decoded),
+        bytes.len() - 30
+    );
+    assert_eq!(drain(&mut decoder, &bytes[30..], &mut decoded), 22);
+    assert_eq!(decoded, vec![token(1)]);
+}
+
+#[test]
+fn rejects_oversized_frames_from_the_header() {
+    let bytes = stream(&[token(1)]);
+    let mut decoder = TokenDecoder::with_max_frame_len(bytes.len() - 1);
+    let mut input = &bytes[..22];
+
+    assert_eq!(
+        decoder.decode(&mut input),
+        Err(DeserializeError::FrameTooLarge(bytes.len()))
+    );
+    assert_eq!(decoder.buffered_len(), 0);
+}
+
+#[test]
+fn recovers_after_a_rejected_header() {
+    let bytes = stream(&[token(1)]);
+    let mut decoder = TokenDecoder::new();
+
+    let mut bad = bytes.clone();
+    bad[0] = 0xEE;
+    let mut input = &bad[..];
+    assert_eq!(
+        decoder.decode(&mut input),
+        Err(DeserializeError::UnsupportedVersion)
+    );
+    assert_eq!(decoder.buffered_len(), 0);
+
+    let mut huge = bytes.clone();
+    huge[18..22].copy_from_slice(&u32::MAX.to_le_bytes());
+    let mut input = &huge[..];
+    assert!(matches!(
+        decoder.decode(&mut input),
+        Err(DeserializeError::FrameTooLarge(_))
+    ));
+    assert_eq!(decoder.buffered_len(), 0);
+
+    let mut input = &bytes[..];
+    assert_eq!(decoder.decode(&mut input), Ok(Decoded::Token(token(1))));
+}
+
+#[test]
+fn skips_a_corrupt_frame_and_continues() {
+    let mut bytes = stream(&[token(1), token(2)]);
+    let first_len = bytes.len() / 2;
+    bytes[30] ^= 0xFF;
+
+    let mut decoder = TokenDecoder::new();
+    let mut input = &bytes[..];
+    assert_eq!(
+        decoder.decode(&mut input),
+        Err(DeserializeError::ChecksumMismatch)
+    );
+    assert_eq!(input.len(), bytes.len() - first_len);
+    assert_eq!(decoder.decode(&mut input), Ok(Decoded::Token(token(2))));
+}
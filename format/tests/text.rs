@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use proptest::prelude::*;
+use uuid::Uuid;
+
+use toon_format::text::{self, ParseErrorKind};
+use toon_format::{TokenId, TokenRef, Value};
+
+#[test]
+fn parses_fixture_with_comments_refs_and_trailing_commas() {
+    let value = text::parse(include_str!("fixtures/order.toon")).unwrap();
+
+    let Value::Object(map) = &value else {
+        panic!("expected object");
+    };
+    assert_eq!(map["id"], Value::Int(1042));
+    assert_eq!(map["total"], Value::Float(99.5));
+    assert_eq!(map["paid"], Value::Bool(false));
+    assert_eq!(map["note"], Value::Null);
+
+    let customer = Uuid::parse_str("6f9619ff-8b86-d011-b42d-00cf4fc964ff").unwrap();
+    assert_eq!(
+        map["customer"],
+        Value::Ref(TokenRef::strong(TokenId::from(customer)))
+    );
+    let previous = Uuid::from_u128(1);
+    assert_eq!(
+        map["previous"],
+        Value::Ref(TokenRef::weak(TokenId::from(previous)))
+    );
+
+    match &map["items"] {
+        Value::Array(items) => assert_eq!(items.len(), 2),
+        other => panic!("expected array, got {other:?}"),
+    }
+}
+
+#[test]
+fn ints_and_floats_stay_distinct() {
+    assert_eq!(text::parse("1").unwrap(), Value::Int(1));
+    assert_eq!(text::parse("1.0").unwrap(), Value::Float(1.0));
+    assert_eq!(text::parse("-2e3").unwrap(), Value::Float(-2000.0));
+    assert_eq!(
+        text::parse("-inf").unwrap(),
+        Value::Float(f64::NEG_INFINITY)
+    );
+
+    assert_eq!(text::to_string(&Value::Float(1.0)), "1.0");
+    assert_eq!(text::to_string(&Value::Int(1)), "1");
+    assert_eq!(text::to_string(&Value::Float(f64::NAN)), "nan");
+}
+
+#[test]
+fn pretty_printer_sorts_keys_and_indents() {
+    let mut inner = HashMap::new();
+    inner.insert("b".to_string(), Value::Int(2));
+    inner.insert("a".to_string(), Value::String("x\n\"y\"".to_string()));
+
+    let mut map = HashMap::new();
+    map.insert(
+        "z".to_string(),
+        Value::Array(vec![Value::Null, Value::Bool(true)]),
+    );
+    map.insert("m".to_string(), Value::Object(inner));
+    map.insert("e".to_string(), Value::Array(Vec::new()));
+    let value = Value::Object(map);
+
+    let expected = r#"{
+  "e": [],
+  "m": {
+    "a": "x\n\"y\"",
+    "b": 2
+  },
+  "z": [
+    null,
+    true
+  ]
+}"#;
+    assert_eq!(text::to_string_pretty(&value), expected);
+    assert_eq!(format!("{value:#}"), expected);
+    assert_eq!(
+        text::to_string(&value),
+        r#"{"e":[],"m":{"a":"x\n\"y\"","b":2},"z":[null,true]}"#
+    );
+    assert_eq!(text::parse(expected).unwrap(), value);
+}
+
+#[test]
+fn errors_report_line_and_column() {
+    let err = text::parse("{\n  \"a\": 1,\n  \"b\": ?\n}").unwrap_err();
+    assert_eq!((err.line, err.column), (3, 8));
+    assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('?'));
+    assert_eq!(err.to_string(), "3:8: unexpected character '?'");
+
+    let err = text::parse("[1, 2").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnexpectedEnd);
+
+    let err = text::parse("\"abc").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 1));
+    assert_eq!(err.kind, ParseErrorKind::UnterminatedString);
+
+    let err = text::parse("{\"a\": 1, \"a\": 2}").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::DuplicateKey("a".to_string()));
+    assert_eq!(err.column, 10);
+
+    let err = text::parse("99999999999999999999").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::IntegerOverflow);
+
+    let err = text::parse("&strong:not-a-uuid").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::InvalidReference);
+
+    let err = text::parse("1 2").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::TrailingCharacters);
+}
+
+#[test]
+fn nesting_is_limited() {
+    let err = text::parse(&"[".repeat(200_000)).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::TooDeep);
+    assert_eq!((err.line, err.column), (1, text::MAX_DEPTH + 1));
+
+    let err = text::parse(&"{\"a\":".repeat(200_000)).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::TooDeep);
+
+    let deepest = format!(
+        "{}{}",
+        "[".repeat(text::MAX_DEPTH),
+        "]".repeat(text::MAX_DEPTH)
+    );
+    assert!(text::parse(&deepest).is_ok());
+}
+
+#[test]
+fn unicode_escapes_round_trip() {
+    assert_eq!(
+        text::parse(r#""é😀""#).unwrap(),
+        Value::String("é😀".to_string())
+    );
+    let control = Value::String("\u{1}".to_string());
+    assert_eq!(text::to_string(&control), r#""\u0001""#);
+    assert_eq!(text::parse(&text::to_string(&control)).unwrap(), control);
+}
+
+#[test]
+fn reference_fragments_round_trip() {
+    let id = TokenId::from(Uuid::from_bytes([6; 16]));
+    let value = Value::Ref(TokenRef::weak(id).with_fragment("/notes/0/a\"b"));
+    let text = text::to_string(&value);
+
+    assert_eq!(
+        text,
+        r#"&weak:06060606-0606-0606-0606-060606060606#"/notes/0/a\"b""#
+    );
+    assert_eq!(text::parse(&text).unwrap(), value);
+}
+
+#[test]
+fn reference_pins_round_trip() {
+    let id = TokenId::from(Uuid::from_bytes([6; 16]));
+    let value = Value::Ref(TokenRef::strong(id).pinned(0x0A0B_0C0D).with_fragment("/a"));
+    let text = text::to_string(&value);
+
+    assert_eq!(
+        text,
+        r#"&strong:06060606-0606-0606-0606-060606060606@0a0b0c0d#"/a""#
+    );
+    assert_eq!(text::parse(&text).unwrap(), value);
+    assert!(text::parse("&strong:06060606-0606-0606-0606-060606060606@abc").is_err());
+}
+
+fn value_strategy() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(Value::Int),
+        any::<f64>()
+            .prop_filter("not NaN", |v| !v.is_nan())
+            .prop_map(Value::Float),
+        any::<String>().prop_map(Value::String),
+        any::<[u8; 16]>()
+            .prop_map(|b| Value::Ref(TokenRef::weak(TokenId::from(Uuid::from_bytes(b))))),
+    ];
+
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            proptest::collection::vec(inner.clone(), 0..8).prop_map(Value::Array),
+            proptest::collection::hash_map(any::<String>(), inner, 0..8).prop_map(Value::Object),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn proptest_text_round_trip(value in value_strategy()) {
+        prop_assert_eq!(&text::parse(&text::to_string(&value)).unwrap(), &value);
+        prop_assert_eq!(&text::parse(&text::to_string_pretty(&value)).unwrap(), &value);
+    }
+}
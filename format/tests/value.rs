@@ -0,0 +1,146 @@
+use std::collections::{BTreeMap, HashMap};
+
+use uuid::Uuid;
+
+use toon_format::{text, toon, Path, TokenId, TokenRef, TypeMismatch, Value};
+
+#[test]
+fn macro_builds_nested_values() {
+    let parent = TokenId::from(Uuid::from_bytes([70u8; 16]));
+    let sibling = TokenId::from(Uuid::from_bytes([71u8; 16]));
+    let count = 3;
+
+    let value = toon! {
+        "name": "x",
+        "tags": ["a", count, -1.5, null, true, &weak sibling, [], {}],
+        "parent": &strong parent,
+        "meta": { "draft": false, ("computed".to_string() + "_key"): count * 2 },
+    };
+
+    let expected = text::parse(&format!(
+        r#"{{
+            "name": "x",
+            "tags": ["a", 3, -1.5, null, true, &weak:{sibling}, [], {{}}],
+            "parent": &strong:{parent},
+            "meta": {{"draft": false, "computed_key": 6}}
+        }}"#,
+        sibling = Uuid::from(sibling),
+        parent = Uuid::from(parent),
+    ))
+    .unwrap();
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn macro_accepts_scalars_and_braced_objects() {
+    assert_eq!(toon!(null), Value::Null);
+    assert_eq!(toon!(42), Value::Int(42));
+    assert_eq!(toon!("s"), Value::String("s".to_string()));
+    assert_eq!(
+        toon!([1, 2,]),
+        Value::Array(vec![Value::Int(1), Value::Int(2)])
+    );
+    assert_eq!(toon!({ "a": 1 }), toon! { "a": 1 });
+    assert_eq!(toon! {}, Value::Object(HashMap::new()));
+}
+
+#[test]
+fn from_impls_cover_primitives_collections_and_refs() {
+    let id = TokenId::from(Uuid::from_bytes([72u8; 16]));
+
+    assert_eq!(Value::from(7u8), Value::Int(7));
+    assert_eq!(Value::from(-7i32), Value::Int(-7));
+    assert_eq!(Value::from(u32::MAX), Value::Int(u32::MAX as i64));
+    assert_eq!(Value::from(0.5f32), Value::Float(0.5));
+    assert_eq!(Value::from(true), Value::Bool(true));
+    assert_eq!(Value::from("s"), Value::String("s".to_string()));
+    assert_eq!(Value::from(None::<i64>), Value::Null);
+    assert_eq!(Value::from(Some(1)), Value::Int(1));
+    assert_eq!(
+        Value::from(TokenRef::weak(id)),
+        Value::Ref(TokenRef::weak(id))
+    );
+    assert_eq!(Value::from(vec![1, 2]), toon!([1, 2]));
+    assert_eq!(Value::from(&["a", "b"][..]), toon!(["a", "b"]));
+    assert_eq!((1..=3).collect::<Value>(), toon!([1, 2, 3]));
+
+    let mut hash = HashMap::new();
+    hash.insert("k", 1);
+    assert_eq!(Value::from(hash), toon! { "k": 1 });
+
+    let mut btree = BTreeMap::new();
+    btree.insert("k".to_string(), vec![true]);
+    assert_eq!(Value::from(btree), toon! { "k": [true] });
+}
+
+#[test]
+fn try_from_extracts_or_reports_mismatch() {
+    let id = TokenId::from(Uuid::from_bytes([73u8; 16]));
+
+    assert_eq!(i64::try_from(toon!(5)), Ok(5));
+    assert_eq!(f64::try_from(&toon!(1.5)), Ok(1.5));
+    assert_eq!(bool::try_from(&toon!(true)), Ok(true));
+    assert_eq!(String::try_from(toon!("s")), Ok("s".to_string()));
+    assert_eq!(<&str>::try_from(&toon!("s")), Ok("s"));
+    assert_eq!(
+        TokenRef::try_from(toon!(&strong id)),
+        Ok(TokenRef::strong(id))
+    );
+    assert_eq!(Vec::<Value>::try_from(toon!([1])), Ok(vec![Value::Int(1)]));
+    assert_eq!(
+        HashMap::<String, Value>::try_from(toon! { "a": null }).unwrap()["a"],
+        Value::Null
+    );
+
+    let err = i64::try_from(toon!(1.0)).unwrap_err();
+    assert_eq!(
+        err,
+        TypeMismatch {
+            expected: "int",
+            found: "float"
+        }
+    );
+    assert_eq!(err.to_string(), "expected int, found float");
+}
+
+#[test]
+fn accessors_and_indexing() {
+    let mut value = toon! {
+        "orders": [{ "sku": "a", "qty": 2 }, { "sku": "b" }],
+        "a/b": { "~x": 1 },
+        "ok": true,
+    };
+
+    assert_eq!(value["orders"][0]["sku"].as_str(), Some("a"));
+    assert_eq!(value["orders"][1]["qty"], Value::Null);
+    assert_eq!(value["missing"][3], Value::Null);
+    assert_eq!(value["ok"].as_bool(), Some(true));
+    assert_eq!(value["ok"].as_i64(), None);
+    assert!(value["orders"].as_array().is_some());
+    assert!(value.as_object().unwrap().contains_key("ok"));
+
+    assert_eq!(
+        value.get("orders").and_then(|o| o.get(1)),
+        Some(&toon! { "sku": "b" })
+    );
+    assert_eq!(value.get(0), None);
+    assert_eq!(value.pointer("/orders/0/qty"), Some(&Value::Int(2)));
+    assert_eq!(value.pointer("/a~1b/~0x"), Some(&Value::Int(1)));
+    assert_eq!(value.pointer(""), Some(&value));
+    assert_eq!(value.pointer("/orders/01"), None);
+    assert_eq!(value.pointer("orders"), None);
+
+    let path = Path::root().key("orders").index(1).key("sku");
+    assert_eq!(value.get_path(&path), Some(&toon!("b")));
+
+    *value.pointer_mut("/orders/1/sku").unwrap() = toon!("c");
+    value
+        .get_mut("orders")
+        .unwrap()
+        .as_array_mut()
+        .unwrap()
+        .push(toon!(null));
+    assert_eq!(value["orders"][1]["sku"], toon!("c"));
+    assert!(value["orders"][2].is_null());
+    assert_eq!(value["orders"].as_array().unwrap().len(), 3);
+}
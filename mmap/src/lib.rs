@@ -0,0 +1,174 @@
+//! Memory-mapped access to token and batch files.
+//!
+//! `MappedFile` maps a file read-only and hands out `Deserializer`,
+//! `BatchDeserializer` and borrowed payload views over the mapping, so large
+//! archives are read without first being copied into memory.
+//!
+//! # Truncation
+//!
+//! A mapping is only as stable as the file behind it. If another process
+//! truncates the file while it is mapped, touching a page past the new end of
+//! file raises `SIGBUS` on Unix (and an access violation on Windows); writes
+//! to the file are visible through the mapping and may tear a token in the
+//! middle of a read. Neither can be caught in safe code.
+//!
+//! `MappedFile` narrows this window rather than closing it: every accessor
+//! first compares the file's current length against the mapped length and
+//! returns `MmapError::Truncated` if it shrank, and views over the payload are
+//! checksum-verified when they are created. Files that may be modified
+//! concurrently should be copied with `std::fs::read` instead.
+
+use std::fs::File;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+use crc32fast::Hasher;
+use memmap2::Mmap;
+use thiserror::Error;
+use toon_format::{
+    BatchDeserializer, DeserializeError, Deserializer, Token, TokenHeader, TokenId, TokenLayout,
+};
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum MmapError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error("file shrank from {mapped} to {current} bytes while mapped")]
+    Truncated { mapped: u64, current: u64 },
+
+    #[error(transparent)]
+    Deserialize(#[from] DeserializeError),
+}
+
+/// A read-only memory mapping of a token or batch file.
+pub struct MappedFile {
+    file: File,
+    /// `None` for empty files, which cannot be mapped on every platform.
+    map: Option<Mmap>,
+}
+
+impl MappedFile {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, MmapError> {
+        Self::from_file(File::open(path)?)
+    }
+
+    /// Maps an already opened file. The file must be readable.
+    pub fn from_file(file: File) -> Result<Self, MmapError> {
+        let len = file.metadata()?.len();
+        let map = if len == 0 {
+            None
+        } else {
+            // SAFETY: the mapping is read-only and never handed out mutably.
+            // Concurrent truncation cannot be prevented here; see the crate
+            // documentation and `check`.
+            Some(unsafe { Mmap::map(&file)? })
+        };
+        Ok(Self { file, map })
+    }
+
+    /// Length of the mapping in bytes.
+    pub fn len(&self) -> usize {
+        self.map.as_ref().map_or(0, |map| map.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Fails with `MmapError::Truncated` if the file is now shorter than the
+    /// mapping.
+    pub fn check(&self) -> Result<(), MmapError> {
+        let mapped = self.len() as u64;
+        let current = self.file.metadata()?.len();
+        if current < mapped {
+            return Err(MmapError::Truncated { mapped, current });
+        }
+        Ok(())
+    }
+
+    /// The mapped bytes, after `check`.
+    pub fn bytes(&self) -> Result<&[u8], MmapError> {
+        self.check()?;
+        Ok(self.map.as_deref().unwrap_or_default())
+    }
+
+    pub fn deserializer(&self) -> Result<Deserializer<'_>, MmapError> {
+        Ok(Deserializer::new(self.bytes()?))
+    }
+
+    pub fn batch(&self) -> Result<BatchDeserializer<'_>, MmapError> {
+        Ok(BatchDeserializer::new(self.bytes()?))
+    }
+
+    /// Decodes the single token the file holds.
+    pub fn deserialize(&self) -> Result<Token, MmapError> {
+        Ok(self.deserializer()?.deserialize()?)
+    }
+
+    /// Decodes every token in a batch file.
+    pub fn deserialize_batch(&self) -> Result<Vec<Token>, MmapError> {
+        Ok(self.batch()?.deserialize()?)
+    }
+
+    /// Returns a checksum-verified view of the token the file holds, without
+    /// decoding or copying its payload.
+    pub fn token(&self) -> Result<TokenView<'_>, MmapError> {
+        TokenView::new(self.bytes()?)
+    }
+}
+
+/// A verified token frame borrowed from a mapping.
+#[derive(Debug, Clone)]
+pub struct TokenView<'a> {
+    bytes: &'a [u8],
+    layout: TokenLayout,
+}
+
+impl<'a> TokenView<'a> {
+    /// Checks the frame's layout and checksum without decoding the payload.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, MmapError> {
+        let layout = Deserializer::new(bytes).layout()?;
+        let checksum_offset = layout.checksum_range.start;
+
+        let mut hasher = Hasher::new();
+        hasher.update(&bytes[..checksum_offset]);
+        if hasher.finalize().to_le_bytes() != bytes[layout.checksum_range.clone()] {
+            return Err(DeserializeError::ChecksumMismatch.into());
+        }
+
+        Ok(Self { bytes, layout })
+    }
+
+    pub fn header(&self) -> TokenHeader {
+        self.layout.header
+    }
+
+    pub fn id(&self) -> TokenId {
+        TokenId::from(Uuid::from_bytes(self.layout.header.id))
+    }
+
+    pub fn type_marker(&self) -> u8 {
+        self.layout.header.type_marker
+    }
+
+    /// The encoded payload, borrowed from the mapping.
+    pub fn payload(&self) -> &'a [u8] {
+        &self.bytes[self.payload_range()]
+    }
+
+    pub fn payload_range(&self) -> Range<usize> {
+        self.layout.payload_range.clone()
+    }
+
+    /// The whole frame, including header and checksum.
+    pub fn frame(&self) -> &'a [u8] {
+        &self.bytes[..self.layout.checksum_range.end]
+    }
+
+    pub fn deserialize(&self) -> Result<Token, MmapError> {
+        Ok(Deserializer::new(self.bytes).deserialize()?)
+    }
+}
@@ -0,0 +1,130 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use tempfile::NamedTempFile;
+use toon_format::{toon, BatchSerializer, DeserializeError, Metadata, Serializer, Token, TokenId};
+use toon_mmap::{MappedFile, MmapError, TokenView};
+
+fn token(n: i64) -> Token {
+    Token::new(
+        TokenId::new(),
+        toon! { "n": n, "tags": ["archived", "cold"] },
+        Metadata::new(0, 0),
+    )
+}
+
+fn file_with(bytes: &[u8]) -> NamedTempFile {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(bytes).unwrap();
+    file.flush().unwrap();
+    file
+}
+
+#[test]
+fn deserializes_a_mapped_token() {
+    let token = token(1);
+    let bytes = Serializer::new().serialize(&token).unwrap();
+    let file = file_with(&bytes);
+
+    let mapped = MappedFile::open(file.path()).unwrap();
+    assert_eq!(mapped.len(), bytes.len());
+    assert_eq!(mapped.bytes().unwrap(), &bytes[..]);
+    assert_eq!(mapped.deserialize().unwrap(), token);
+}
+
+#[test]
+fn token_view_borrows_the_payload() {
+    let token = token(2);
+    let bytes = Serializer::new().serialize(&token).unwrap();
+    let file = file_with(&bytes);
+    let mapped = MappedFile::open(file.path()).unwrap();
+
+    let view = mapped.token().unwrap();
+    assert_eq!(view.id(), token.id());
+    assert_eq!(view.type_marker(), bytes[17]);
+    assert_eq!(view.payload(), &bytes[22..bytes.len() - 4]);
+    assert_eq!(view.frame(), &bytes[..]);
+    assert_eq!(view.deserialize().unwrap(), token);
+
+    let base = mapped.bytes().unwrap().as_ptr();
+    assert_eq!(view.payload().as_ptr(), base.wrapping_add(22));
+}
+
+#[test]
+fn token_view_verifies_the_checksum() {
+    let mut bytes = Serializer::new().serialize(&token(3)).unwrap();
+    let last = bytes.len() - 5;
+    bytes[last] ^= 0xFF;
+
+    assert!(matches!(
+        TokenView::new(&bytes),
+        Err(MmapError::Deserialize(DeserializeError::ChecksumMismatch))
+    ));
+}
+
+#[test]
+fn deserializes_a_mapped_batch() {
+    let tokens: Vec<Token> = (0..50).map(token).collect();
+    let bytes = BatchSerializer::new().serialize(&tokens).unwrap();
+    let file = file_with(&bytes);
+
+    let mapped = MappedFile::open(file.path()).unwrap();
+    assert_eq!(mapped.deserialize_batch().unwrap(), tokens);
+    assert_eq!(mapped.batch().unwrap().dictionary().unwrap().len(), 2);
+}
+
+#[test]
+fn empty_files_map_to_no_bytes() {
+    let file = file_with(&[]);
+    let mapped = MappedFile::open(file.path()).unwrap();
+
+    assert!(mapped.is_empty());
+    assert_eq!(mapped.bytes().unwrap(), &[] as &[u8]);
+    assert!(matches!(
+        mapped.deserialize(),
+        Err(MmapError::Deserialize(DeserializeError::Truncated))
+    ));
+}
+
+#[test]
+fn reports_truncation_instead_of_reading_past_the_end() {
+    let bytes = Serializer::new().serialize(&token(4)).unwrap();
+    let file = file_with(&bytes);
+    let mapped = MappedFile::open(file.path()).unwrap();
+    assert!(mapped.check().is_ok());
+
+    OpenOptions::new()
+        .write(true)
+        .open(file.path())
+        .unwrap()
+        .set_len(10)
+        .unwrap();
+
+    let expected = (bytes.len() as u64, 10);
+    for result in [
+        mapped.check(),
+        mapped.token().map(|_| ()),
+        mapped.deserialize().map(|_| ()),
+    ] {
+        match result {
+            Err(MmapError::Truncated { mapped, current }) => {
+                assert_eq!((mapped, current), expected)
+            }
+            other => panic!("expected truncation, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn growth_is_not_an_error() {
+    let token = token(5);
+    let bytes = Serializer::new().serialize(&token).unwrap();
+    let mut file = file_with(&bytes);
+    let mapped = MappedFile::open(file.path()).unwrap();
+
+    file.write_all(b"appended").unwrap();
+    file.flush().unwrap();
+
+    assert_eq!(mapped.len(), bytes.len());
+    assert_eq!(mapped.deserialize().unwrap(), token);
+}